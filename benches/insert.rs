@@ -0,0 +1,43 @@
+//! Times `Ranger::insert` on the same values inserted in ascending and in
+//! shuffled order, with the runs either touching or kept apart. Ascending
+//! inserts land above the last run and skip the tree lookup, so should come
+//! out well ahead. Run with `cargo bench --bench insert`.
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use ranger::Ranger;
+use std::{hint::black_box, time::Instant};
+
+const VALUES: u64 = 1 << 20;
+const ROUNDS: usize = 5;
+
+/// Returns the fastest of a few rounds of inserting `values`, in nanoseconds
+/// per value
+fn time_inserts(values: &[u64]) -> f64 {
+    let mut best = f64::INFINITY;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let mut ranger = Ranger::new();
+        for &v in values {
+            black_box(ranger.insert(v));
+        }
+        let elapsed = start.elapsed();
+        black_box(&ranger);
+        best = best.min(elapsed.as_nanos() as f64 / values.len() as f64);
+    }
+    best
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for (name, step) in [("one run", 1), ("apart", 2)] {
+        let ascending: Vec<u64> = (0..VALUES).map(|v| v * step).collect();
+        let mut shuffled = ascending.clone();
+        shuffled.shuffle(&mut rng);
+        let ascending = time_inserts(&ascending);
+        let shuffled = time_inserts(&shuffled);
+        println!(
+            "{name:>8}: ascending {ascending:6.1} ns, shuffled {shuffled:6.1} ns, {:.1}x",
+            shuffled / ascending
+        );
+    }
+}
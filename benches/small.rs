@@ -0,0 +1,37 @@
+//! Times inserts and removes at random positions in a `SmallRanger` and a
+//! `Ranger` holding the same runs, at a range of run counts, to find where
+//! shifting the vector starts to cost more than walking the tree. Run with
+//! `cargo bench --bench small`.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use ranger::{Ranger, SmallRanger};
+use std::{hint::black_box, time::Instant};
+
+const OPS: usize = 100_000;
+
+/// Returns the nanoseconds per operation of toggling the values in `ops`,
+/// which keeps the number of runs roughly steady
+fn time_ops<S>(set: &mut S, ops: &[u64], toggle: impl Fn(&mut S, u64) -> bool) -> f64 {
+    let start = Instant::now();
+    for &v in ops {
+        black_box(toggle(set, v));
+    }
+    start.elapsed().as_nanos() as f64 / ops.len() as f64
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for runs in [16, 128, 1024, 2048, 4096, 8192, 16384] {
+        let ops: Vec<u64> = (0..OPS).map(|_| rng.gen_range(0..4 * runs)).collect();
+        // runs of two values, with a gap of two between each
+        let mut small = SmallRanger::new();
+        let mut tree = Ranger::new();
+        for i in 0..runs {
+            small.insert_range(4 * i..=4 * i + 1);
+            tree.insert_range(4 * i..=4 * i + 1);
+        }
+        let small = time_ops(&mut small, &ops, |s, v| s.remove(&v) || s.insert(v));
+        let tree = time_ops(&mut tree, &ops, |s, v| s.remove(&v) || s.insert(v));
+        println!("{runs:>6} runs: small {small:6.1} ns, tree {tree:6.1} ns");
+    }
+}
@@ -0,0 +1,196 @@
+//! Derive macro for the `Discrete` trait of the `ranger` crate, enabled there
+//! by its `derive` feature and re-exported as `ranger::Discrete`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Index, Member};
+
+/// Types the derived implementation can delegate to, by the last segment of
+/// their path
+const SUPPORTED: &[&str] = &[
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "char",
+    "NonZeroU8",
+    "NonZeroU16",
+    "NonZeroU32",
+    "NonZeroU64",
+    "NonZeroU128",
+    "NonZeroUsize",
+    "NonZeroI8",
+    "NonZeroI16",
+    "NonZeroI32",
+    "NonZeroI64",
+    "NonZeroI128",
+    "NonZeroIsize",
+    "Ipv4Addr",
+    "Ipv6Addr",
+];
+
+/// Implements `Discrete` for a struct by delegating every method to one of
+/// its fields, which must be of a primitive integer type, `char`, a
+/// `NonZero` integer or an IP address.
+///
+/// A struct with one field steps through that field. A struct with more
+/// must mark the one to step through with `#[ranger(transparent)]`; the
+/// others are filled with `Default::default()` in every value the
+/// implementation makes, so they should be markers such as `PhantomData`
+/// that take no part in ordering.
+///
+/// `Discrete` requires `Ord` and `Clone`, which are best derived alongside
+/// it so that they agree with the field.
+///
+/// ```
+/// use ranger::{Discrete, Ranger};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+/// struct PortId(u16);
+///
+/// let mut ports = Ranger::new();
+/// for port in [8080, 8081, 8082, 443] {
+///     ports.insert(PortId(port));
+/// }
+/// assert_eq!(ports.range_count(), 2);
+/// assert!(ports.contains(&PortId(8081)));
+/// assert_eq!(PortId(u16::MAX).successor(), None);
+/// ```
+///
+/// With more than one field:
+///
+/// ```
+/// use core::marker::PhantomData;
+/// use ranger::Discrete;
+///
+/// #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+/// struct Id<Kind> {
+///     #[ranger(transparent)]
+///     raw: u32,
+///     kind: PhantomData<Kind>,
+/// }
+///
+/// let id = Id::<()> { raw: 7, kind: PhantomData };
+/// assert_eq!(id.forward(3).map(|id| id.raw), Some(10));
+/// ```
+#[proc_macro_derive(Discrete, attributes(ranger))]
+pub fn derive_discrete(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "#[derive(Discrete)] is only supported on structs",
+        ));
+    };
+    let fields: Vec<_> = data.fields.iter().collect();
+    let mut marked = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("ranger")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("transparent") {
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `transparent`"))
+                }
+            })?;
+            marked.push(i);
+        }
+    }
+    let key = match (fields.len(), marked.as_slice()) {
+        (0, _) => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "#[derive(Discrete)] needs a field to step through",
+            ))
+        }
+        (1, _) => 0,
+        (_, [key]) => *key,
+        (_, []) => {
+            return Err(Error::new(
+                data.fields.span(),
+                "mark the field to step through with #[ranger(transparent)]",
+            ))
+        }
+        (_, [_, second, ..]) => {
+            return Err(Error::new_spanned(
+                fields[*second],
+                "only one field can be marked #[ranger(transparent)]",
+            ))
+        }
+    };
+    let ty = &fields[key].ty;
+    let supported = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => {
+            path.path.segments.last().is_some_and(|s| {
+                s.arguments.is_empty() && SUPPORTED.contains(&&*s.ident.to_string())
+            })
+        }
+        _ => false,
+    };
+    if !supported {
+        return Err(Error::new_spanned(
+            ty,
+            "#[derive(Discrete)] needs a field of a primitive integer type, `char`, a `NonZero` \
+             integer or an IP address",
+        ));
+    }
+
+    let member = |i: usize| match &fields[i].ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(i)),
+    };
+    let key_member = member(key);
+    // builds the struct around a new value `v` of the field
+    let others = (0..fields.len()).filter(|&i| i != key).map(member);
+    let construct = quote! {
+        Self { #key_member: v, #(#others: ::core::default::Default::default(),)* }
+    };
+
+    let name = &input.ident;
+    // the impl holds wherever the supertraits do, as derived for generic
+    // structs they may need bounds on the parameters
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(Self: ::core::cmp::Ord + ::core::clone::Clone));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::ranger::Discrete for #name #ty_generics #where_clause {
+            fn adjacent(lower: &Self, upper: &Self) -> bool {
+                <#ty as ::ranger::Discrete>::adjacent(&lower.#key_member, &upper.#key_member)
+            }
+            fn successor(&self) -> ::core::option::Option<Self> {
+                <#ty as ::ranger::Discrete>::successor(&self.#key_member).map(|v| #construct)
+            }
+            fn predecessor(&self) -> ::core::option::Option<Self> {
+                <#ty as ::ranger::Discrete>::predecessor(&self.#key_member).map(|v| #construct)
+            }
+            fn distance(lower: &Self, upper: &Self) -> ::core::option::Option<u128> {
+                <#ty as ::ranger::Discrete>::distance(&lower.#key_member, &upper.#key_member)
+            }
+            fn forward(&self, steps: u128) -> ::core::option::Option<Self> {
+                <#ty as ::ranger::Discrete>::forward(&self.#key_member, steps).map(|v| #construct)
+            }
+            fn backward(&self, steps: u128) -> ::core::option::Option<Self> {
+                <#ty as ::ranger::Discrete>::backward(&self.#key_member, steps).map(|v| #construct)
+            }
+        }
+    })
+}
@@ -0,0 +1,9 @@
+use ranger::Discrete;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+enum Level {
+    Low,
+    High,
+}
+
+fn main() {}
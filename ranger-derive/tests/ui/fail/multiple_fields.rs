@@ -0,0 +1,6 @@
+use ranger::Discrete;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+struct Versioned(u32, u32);
+
+fn main() {}
@@ -0,0 +1,6 @@
+use ranger::Discrete;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+struct Name(String);
+
+fn main() {}
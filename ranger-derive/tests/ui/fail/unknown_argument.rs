@@ -0,0 +1,10 @@
+use ranger::Discrete;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+struct Tagged {
+    #[ranger(opaque)]
+    id: u64,
+    tag: (),
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use core::{marker::PhantomData, num::NonZeroU8};
+use ranger::{Discrete, Ranger};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+struct Sector(core::primitive::u64);
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+struct Slot<Owner> {
+    owner: PhantomData<Owner>,
+    #[ranger(transparent)]
+    index: NonZeroU8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Discrete)]
+struct Letter(char);
+
+fn main() {
+    let mut sectors = Ranger::new();
+    sectors.insert_range(Sector(10)..=Sector(19));
+    sectors.insert(Sector(20));
+    assert_eq!(sectors.range_count(), 1);
+    assert_eq!(Sector::distance(&Sector(3), &Sector(10)), Some(7));
+
+    let slot = |i| Slot::<()> {
+        owner: PhantomData,
+        index: NonZeroU8::new(i).unwrap(),
+    };
+    assert_eq!(slot(1).predecessor(), None);
+    assert_eq!(slot(255).backward(254), Some(slot(1)));
+
+    assert!(Letter::adjacent(&Letter('\u{d7ff}'), &Letter('\u{e000}')));
+}
@@ -0,0 +1,159 @@
+use crate::{Discrete, ParseAlphaError};
+use core::{fmt, str::FromStr};
+
+/// A spreadsheet-style column, counted from zero but written in letters as
+/// `A` to `Z`, then `AA` to `ZZ`, then `AAA` and so on, a base 26 without a
+/// zero digit. Columns follow one another across a change in length, so
+/// sets of them parse and display in that form:
+///
+#[cfg_attr(feature = "alloc", doc = "```")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use ranger::{AlphaIndex, Ranger};
+///
+/// let mut columns: Ranger<AlphaIndex> = "A-D,F,Y-Z".parse().unwrap();
+/// columns.insert("AA".parse().unwrap());
+/// assert_eq!(columns.to_string(), "A-D,F,Y-AA");
+/// assert!(columns.contains(&AlphaIndex(1)));
+/// ```
+///
+/// Parsing accepts either case, and displays in upper case.
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct AlphaIndex(pub u32);
+
+/// Letters in the longest column name, that of `u32::MAX`
+const MAX_LETTERS: usize = 7;
+
+impl Discrete for AlphaIndex {
+    fn successor(&self) -> Option<Self> {
+        self.0.successor().map(AlphaIndex)
+    }
+    fn predecessor(&self) -> Option<Self> {
+        self.0.predecessor().map(AlphaIndex)
+    }
+    fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+        u32::distance(&lower.0, &upper.0)
+    }
+    fn forward(&self, steps: u128) -> Option<Self> {
+        self.0.forward(steps).map(AlphaIndex)
+    }
+    fn backward(&self, steps: u128) -> Option<Self> {
+        self.0.backward(steps).map(AlphaIndex)
+    }
+}
+
+impl fmt::Display for AlphaIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut letters = [0; MAX_LETTERS];
+        let mut start = MAX_LETTERS;
+        // one more than the index, which has no zero digit
+        let mut rest = u64::from(self.0) + 1;
+        while rest > 0 {
+            rest -= 1;
+            start -= 1;
+            letters[start] = b'A' + (rest % 26) as u8;
+            rest /= 26;
+        }
+        let letters = core::str::from_utf8(&letters[start..]).expect("ASCII letters");
+        f.pad(letters)
+    }
+}
+
+impl FromStr for AlphaIndex {
+    type Err = ParseAlphaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseAlphaError);
+        }
+        let mut number: u64 = 0;
+        for b in s.bytes() {
+            if !b.is_ascii_alphabetic() || number > u64::from(u32::MAX) {
+                return Err(ParseAlphaError);
+            }
+            number = number * 26 + u64::from(b.to_ascii_uppercase() - b'A') + 1;
+        }
+        u32::try_from(number - 1)
+            .map(AlphaIndex)
+            .map_err(|_| ParseAlphaError)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::Ranger;
+    use alloc::{format, string::ToString};
+
+    fn col(s: &str) -> AlphaIndex {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn letters_round_trip() {
+        let mut previous = None;
+        for i in 0..2000 {
+            let letters = AlphaIndex(i).to_string();
+            assert_eq!(col(&letters), AlphaIndex(i), "{letters}");
+            // longer names come later, and names of a length are in order
+            if let Some(previous) = previous.replace(letters.clone()) {
+                assert!((previous.len(), &previous) < (letters.len(), &letters));
+            }
+        }
+        assert_eq!(AlphaIndex(u32::MAX).to_string(), "MWLQKWV");
+        assert_eq!(col("MWLQKWV"), AlphaIndex(u32::MAX));
+        assert_eq!(col("aB"), col("AB"));
+        assert_eq!(format!("[{:>4}]", AlphaIndex(27)), "[  AB]");
+    }
+
+    #[test]
+    fn boundaries() {
+        for (letters, index) in [
+            ("A", 0),
+            ("Z", 25),
+            ("AA", 26),
+            ("AZ", 51),
+            ("BA", 52),
+            ("ZZ", 701),
+            ("AAA", 702),
+            ("ZZZ", 18277),
+            ("AAAA", 18278),
+        ] {
+            assert_eq!(col(letters), AlphaIndex(index));
+            assert_eq!(AlphaIndex(index).to_string(), letters);
+        }
+        assert!(AlphaIndex::adjacent(&col("Z"), &col("AA")));
+        assert!(AlphaIndex::adjacent(&col("ZZ"), &col("AAA")));
+        assert_eq!(AlphaIndex::distance(&col("A"), &col("AAA")), Some(702));
+    }
+
+    #[test]
+    fn rejects_bad_letters() {
+        for bad in [
+            "",
+            "A1",
+            "-A",
+            "A B",
+            "É",
+            "MWLQKWW",
+            "ZZZZZZZ",
+            "AAAAAAAAAAAAAAAAAAAA",
+        ] {
+            assert_eq!(bad.parse::<AlphaIndex>(), Err(ParseAlphaError), "{bad}");
+        }
+    }
+
+    #[test]
+    fn column_sets() {
+        let ranger: Ranger<AlphaIndex> = "A-D,F,AA-AD".parse().unwrap();
+        assert_eq!(ranger.len(), 9);
+        assert_eq!(ranger.to_string(), "A-D,F,AA-AD");
+        // merging across a change in length
+        let mut ranger: Ranger<AlphaIndex> = "X-Z,AB".parse().unwrap();
+        ranger.insert(col("AA"));
+        assert_eq!(ranger.to_string(), "X-AB");
+        let ranger: Ranger<AlphaIndex> = "zy-aab, b".parse().unwrap();
+        assert_eq!(ranger.to_string(), "B,ZY-AAB");
+        assert!("C-A".parse::<Ranger<AlphaIndex>>().is_err());
+        assert!("A-3".parse::<Ranger<AlphaIndex>>().is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{Config, Discrete, Ranger, Unit};
+use alloc::{collections::BTreeSet, vec::Vec};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Builds a well-formed set directly: arbitrary values are sorted and paired
+/// off into runs, with touching runs joined, rather than inserted one by one
+impl<'a, T> Arbitrary<'a> for Ranger<T>
+where
+    T: Arbitrary<'a> + Discrete,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut endpoints = u.arbitrary_iter::<T>()?.collect::<Result<Vec<_>>>()?;
+        endpoints.sort();
+        endpoints.dedup();
+        let mut runs: Vec<(T, T)> = Vec::with_capacity(endpoints.len().div_ceil(2));
+        let mut endpoints = endpoints.into_iter();
+        while let Some(low) = endpoints.next() {
+            let high = endpoints.next().unwrap_or_else(|| low.clone());
+            match runs.last_mut() {
+                Some(last) if T::adjacent(&last.1, &low) => last.1 = high,
+                _ => runs.push((low, high)),
+            }
+        }
+        let set: BTreeSet<_> = runs.into_iter().map(|(l, h)| Unit::new(l, h)).collect();
+        Ok(Ranger(set, Config::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{thread_rng, RngCore};
+
+    fn assert_well_formed<T: Discrete>(ranger: &Ranger<T>) {
+        let mut previous: Option<&T> = None;
+        for u in ranger.0.iter() {
+            assert!(u.h.as_ref().is_none_or(|h| h > &u.l));
+            assert!(previous.is_none_or(|p| p < &u.l && !T::adjacent(p, &u.l)));
+            previous = Some(u.high());
+        }
+    }
+
+    #[test]
+    fn arbitrary_sets() {
+        let bytes = [1, 9, 1, 3, 1, 5, 1, 200, 1, 10, 1, 20, 1, 3, 0];
+        let ranger = Ranger::<u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert_eq!(ranger.to_string(), "3-5,9-10,20-200");
+        // 3-4 and 5-9 touch, as do 5-9 and 10-20
+        let bytes = [1, 3, 1, 4, 1, 5, 1, 9, 1, 10, 1, 20, 1, 200, 0];
+        let ranger = Ranger::<u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert_eq!(ranger.to_string(), "3-20,200");
+        let ranger = Ranger::<u8>::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert!(ranger.is_empty());
+
+        let mut bytes = [0u8; 512];
+        for _ in 0..200 {
+            thread_rng().fill_bytes(&mut bytes);
+            let ranger = Ranger::<u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert_well_formed(&ranger);
+            let ranger = Ranger::<i32>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+            assert_well_formed(&ranger);
+        }
+    }
+}
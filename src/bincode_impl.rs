@@ -0,0 +1,137 @@
+use crate::{Config, Discrete, Ranger, Unit};
+use alloc::collections::BTreeSet;
+use bincode::{
+    de::{BorrowDecoder, Decoder},
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+    BorrowDecode, Decode, Encode,
+};
+
+/// Writes a `u64` run count followed by each run's `(low, high)` pair in
+/// ascending order, the same layout bincode gives a `Vec<(T, T)>`
+impl<T: Encode> Encode for Ranger<T> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        (self.0.len() as u64).encode(encoder)?;
+        for u in self.0.iter() {
+            u.l.encode(encoder)?;
+            u.high().encode(encoder)?;
+        }
+        Ok(())
+    }
+}
+
+/// Accepts only the layout encoding produces: pairs in ascending order that
+/// neither overlap nor touch, so equal sets have exactly one encoding
+impl<Context, T: Decode<Context> + Discrete> Decode<Context> for Ranger<T> {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let len = u64::decode(decoder)?;
+        let len = usize::try_from(len).map_err(|_| DecodeError::OutsideUsizeRange(len))?;
+        decoder.claim_container_read::<(T, T)>(len)?;
+        let mut set = BTreeSet::new();
+        let mut previous: Option<T> = None;
+        for _ in 0..len {
+            // each value claims its own bytes as it is read
+            decoder.unclaim_bytes_read(core::mem::size_of::<(T, T)>());
+            let low = T::decode(decoder)?;
+            let high = T::decode(decoder)?;
+            if low > high || previous.is_some_and(|p| p >= low || T::adjacent(&p, &low)) {
+                return Err(DecodeError::Other(
+                    "Ranges are out of order, reversed or touching",
+                ));
+            }
+            previous = Some(high.clone());
+            set.insert(Unit::new(low, high));
+        }
+        Ok(Ranger(set, Config::default()))
+    }
+}
+
+impl<'de, Context, T: Decode<Context> + Discrete> BorrowDecode<'de, Context> for Ranger<T> {
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        Self::decode(decoder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use bincode::config::{legacy, standard};
+    use rand::{thread_rng, Rng};
+
+    fn encode<T: Encode>(value: &T) -> Vec<u8> {
+        bincode::encode_to_vec(value, standard()).unwrap()
+    }
+
+    fn decode<T: Decode<()>>(bytes: &[u8]) -> Result<T, DecodeError> {
+        bincode::decode_from_slice(bytes, standard()).map(|(v, _)| v)
+    }
+
+    #[test]
+    fn golden_bytes() {
+        let ranger: Ranger<u16> = "0-2,4,6-8,300-65535".parse().unwrap();
+        let bytes = [4, 0, 2, 4, 4, 6, 8, 251, 44, 1, 251, 255, 255];
+        assert_eq!(encode(&ranger), bytes);
+        assert_eq!(decode::<Ranger<u16>>(&bytes).unwrap(), ranger);
+        // zigzag varints for signed values
+        let ranger: Ranger<i32> = "-3--1,5".parse().unwrap();
+        assert_eq!(encode(&ranger), [2, 5, 1, 10, 10]);
+        let legacy_bytes = bincode::encode_to_vec(&ranger, legacy()).unwrap();
+        assert_eq!(
+            legacy_bytes,
+            [
+                2, 0, 0, 0, 0, 0, 0, 0, 253, 255, 255, 255, 255, 255, 255, 255, 5, 0, 0, 0, 5, 0,
+                0, 0
+            ]
+        );
+        assert_eq!(encode(&Ranger::<u64>::new()), [0]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = thread_rng();
+        for _ in 0..50 {
+            let mut small = Ranger::<u16>::new();
+            let mut wide = Ranger::<u64>::new();
+            let mut signed = Ranger::<i32>::new();
+            for _ in 0..rng.gen_range(0..40) {
+                let l = rng.gen::<u16>();
+                small.insert_range(l..=l.saturating_add(rng.gen_range(0..300)));
+                let l = rng.gen::<u64>();
+                wide.insert_range(l..=l.saturating_add(rng.gen()));
+                let l = rng.gen::<i32>();
+                signed.insert_range(l..=l.saturating_add(rng.gen_range(0..1 << 20)));
+            }
+            assert_eq!(decode::<Ranger<u16>>(&encode(&small)).unwrap(), small);
+            assert_eq!(decode::<Ranger<u64>>(&encode(&wide)).unwrap(), wide);
+            assert_eq!(decode::<Ranger<i32>>(&encode(&signed)).unwrap(), signed);
+            // the same layout as a list of pairs
+            let pairs: Vec<(i32, i32)> = signed.ranges().map(|s| s.into_inner()).collect();
+            assert_eq!(encode(&signed), encode(&pairs));
+        }
+        let full: Ranger<u64> = "0-18446744073709551615".parse().unwrap();
+        assert_eq!(decode::<Ranger<u64>>(&encode(&full)).unwrap(), full);
+    }
+
+    #[test]
+    fn rejects_invalid_pairs() {
+        for pairs in [
+            Vec::from([(4u16, 5u16), (0, 2)]),
+            Vec::from([(0, 2), (3, 5)]),
+            Vec::from([(0, 4), (3, 5)]),
+            Vec::from([(0, 2), (6, 5)]),
+        ] {
+            let err = decode::<Ranger<u16>>(&encode(&pairs)).unwrap_err();
+            assert!(matches!(err, DecodeError::Other(_)), "{err:?}");
+        }
+        let bytes = encode(&Vec::from([(0u16, 2u16), (4, 5)]));
+        assert!(decode::<Ranger<u16>>(&bytes).is_ok());
+        assert!(decode::<Ranger<u16>>(&bytes[..bytes.len() - 1]).is_err());
+        // a huge count is refused by the limit before anything is allocated
+        let limited = standard().with_limit::<64>();
+        let huge = encode(&u64::MAX);
+        assert!(bincode::decode_from_slice::<Ranger<u16>, _>(&huge, limited).is_err());
+    }
+}
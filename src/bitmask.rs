@@ -0,0 +1,278 @@
+use crate::{Config, Discrete, OutOfRange, Ranger, Unit};
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use num_traits::Zero;
+
+impl<T: Discrete + Zero> Ranger<T> {
+    /// Returns a mask with bit `i` set for each stored value `i`, failing if
+    /// any value is negative or above 63
+    pub fn to_bitmask(&self) -> Result<u64, OutOfRange> {
+        let mask = self.to_bitmask_u128()?;
+        u64::try_from(mask).map_err(|_| OutOfRange)
+    }
+    /// Returns a mask with bit `i` set for each stored value `i`, failing if
+    /// any value is negative or above 127
+    pub fn to_bitmask_u128(&self) -> Result<u128, OutOfRange> {
+        let zero = T::zero();
+        let mut mask = 0u128;
+        for u in self.0.iter() {
+            if u.l < zero {
+                return Err(OutOfRange);
+            }
+            let l = T::distance(&zero, &u.l).filter(|l| *l < 128);
+            let h = T::distance(&zero, u.high()).filter(|h| *h < 128);
+            let (Some(l), Some(h)) = (l, h) else {
+                return Err(OutOfRange);
+            };
+            // every bit from l up, less every bit above h
+            mask |= (u128::MAX << l) & (u128::MAX >> (127 - h));
+        }
+        Ok(mask)
+    }
+    /// Returns the set of the indices of the bits set in `mask`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` cannot hold the index of a set bit.
+    pub fn from_bitmask(mask: u64) -> Ranger<T> {
+        Self::from_bitmask_u128(mask.into())
+    }
+    /// Returns the set of the indices of the bits set in `mask`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` cannot hold the index of a set bit.
+    pub fn from_bitmask_u128(mask: u128) -> Ranger<T> {
+        let zero = T::zero();
+        let at = |i: u32| {
+            zero.forward(i.into())
+                .expect("bit index out of range for the type")
+        };
+        let mut set = BTreeSet::new();
+        let (mut rest, mut base) = (mask, 0);
+        // each pass skips a group of clear bits and takes the group of set
+        // bits after it as one run
+        while rest != 0 {
+            let clear = rest.trailing_zeros();
+            rest >>= clear;
+            base += clear;
+            let ones = rest.trailing_ones();
+            set.insert(Unit::new(at(base), at(base + ones - 1)));
+            rest = rest.checked_shr(ones).unwrap_or(0);
+            base += ones;
+        }
+        Ranger(set, Config::default())
+    }
+    /// Returns the set of the indices of the bits set in `bytes`, where bit
+    /// `k` of byte `n` (counting from the least significant bit) stands for
+    /// the value `8 * n + k`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` cannot hold the index of a set bit.
+    pub fn from_bitmap_bytes(bytes: &[u8]) -> Ranger<T> {
+        let zero = T::zero();
+        let at = |i: u128| {
+            zero.forward(i)
+                .expect("bit index out of range for the type")
+        };
+        let mut set = BTreeSet::new();
+        // the index of the first bit of the run still being read, if any
+        let mut open = None;
+        let chunks = bytes.chunks(8).map(|chunk| {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(word)
+        });
+        for (base, word) in (0u128..).step_by(64).zip(chunks) {
+            // each pass takes a whole group of clear bits and then of set
+            // bits, so a clear or full word costs a single step
+            let mut p = 0;
+            while p < 64 {
+                let rest = word >> p;
+                let clear = rest.trailing_zeros().min(64 - p);
+                if clear > 0 {
+                    if let Some(l) = open.take() {
+                        set.insert(Unit::new(at(l), at(base + p as u128 - 1)));
+                    }
+                    p += clear;
+                    if p == 64 {
+                        break;
+                    }
+                }
+                open.get_or_insert(base + p as u128);
+                p += (word >> p).trailing_ones();
+            }
+        }
+        if let Some(l) = open {
+            let end = bytes.len() as u128 * 8 - 1;
+            set.insert(Unit::new(at(l), at(end)));
+        }
+        Ranger(set, Config::default())
+    }
+    /// Writes the set to `out` as [`from_bitmap_bytes`](Self::from_bitmap_bytes)
+    /// reads it, clearing every other bit. Fails without touching `out` if
+    /// any value is negative or does not fit in its `8 * out.len()` bits.
+    pub fn write_bitmap_bytes(&self, out: &mut [u8]) -> Result<(), OutOfRange> {
+        let zero = T::zero();
+        let bits = out.len() as u128 * 8;
+        let index = |v: &T| T::distance(&zero, v).filter(|i| *i < bits);
+        // the set is sorted, so checking its two ends covers every value
+        if let (Some(first), Some(last)) = (self.0.first(), self.0.last()) {
+            if first.l < zero || index(last.high()).is_none() {
+                return Err(OutOfRange);
+            }
+        }
+        out.fill(0);
+        for u in self.0.iter() {
+            // both fit below `bits`, itself a bound on a slice length
+            let l = index(&u.l).ok_or(OutOfRange)? as usize;
+            let h = index(u.high()).ok_or(OutOfRange)? as usize;
+            let (lo, hi) = (l / 8, h / 8);
+            let (head, tail) = (0xFFu8 << (l % 8), 0xFFu8 >> (7 - h % 8));
+            if lo == hi {
+                out[lo] |= head & tail;
+            } else {
+                out[lo] |= head;
+                out[lo + 1..hi].fill(0xFF);
+                out[hi] |= tail;
+            }
+        }
+        Ok(())
+    }
+    /// Returns the set as `len` bytes laid out as
+    /// [`write_bitmap_bytes`](Self::write_bitmap_bytes) writes them
+    pub fn to_bitmap_bytes(&self, len: usize) -> Result<Vec<u8>, OutOfRange> {
+        let mut out = vec![0; len];
+        self.write_bitmap_bytes(&mut out)?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn word_masks() {
+        let pins = Ranger::<u8>::from_bitmask(0b1011_1001);
+        assert_eq!(pins.to_string(), "0,3-5,7");
+        assert_eq!(pins.to_bitmask(), Ok(0b1011_1001));
+        assert!(Ranger::<u8>::from_bitmask(0).is_empty());
+        assert_eq!(Ranger::<u8>::new().to_bitmask(), Ok(0));
+        let all = Ranger::<u32>::from_bitmask(u64::MAX);
+        assert_eq!(all.to_string(), "0-63");
+        assert_eq!(all.to_bitmask(), Ok(u64::MAX));
+        let top = Ranger::<i16>::from_bitmask(1 << 63 | 1 << 62 | 1);
+        assert_eq!(top.to_string(), "0,62-63");
+        assert_eq!(top.to_bitmask(), Ok(1 << 63 | 1 << 62 | 1));
+        let all = Ranger::<u8>::from_bitmask_u128(u128::MAX);
+        assert_eq!(all.to_string(), "0-127");
+        assert_eq!(all.to_bitmask_u128(), Ok(u128::MAX));
+        assert_eq!(all.to_bitmask(), Err(OutOfRange));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let ranger: Ranger<i32> = "-1,3".parse().unwrap();
+        assert_eq!(ranger.to_bitmask(), Err(OutOfRange));
+        assert_eq!(ranger.to_bitmask_u128(), Err(OutOfRange));
+        let ranger: Ranger<u32> = "60-64".parse().unwrap();
+        assert_eq!(ranger.to_bitmask(), Err(OutOfRange));
+        assert_eq!(ranger.to_bitmask_u128(), Ok(0b11111 << 60));
+        let ranger: Ranger<u64> = "127-128".parse().unwrap();
+        assert_eq!(ranger.to_bitmask_u128(), Err(OutOfRange));
+        let ranger: Ranger<u64> = "5,1000000-1000001".parse().unwrap();
+        assert_eq!(ranger.to_bitmask(), Err(OutOfRange));
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut rng = thread_rng();
+        for _ in 0..2000 {
+            // sparse, dense and evenly mixed masks
+            let (a, b) = (rng.gen::<u64>(), rng.gen::<u64>());
+            for mask in [a, a & b, a | b] {
+                let ranger = Ranger::<u8>::from_bitmask(mask);
+                assert_eq!(ranger.len(), mask.count_ones() as u128);
+                for i in 0..64u8 {
+                    assert_eq!(ranger.contains(&i), mask >> i & 1 == 1);
+                }
+                assert_eq!(ranger.to_bitmask(), Ok(mask));
+            }
+            let wide = (a as u128) << 64 | b as u128;
+            let ranger = Ranger::<i64>::from_bitmask_u128(wide);
+            assert_eq!(ranger.to_bitmask_u128(), Ok(wide));
+        }
+    }
+
+    #[test]
+    fn bytes_across_boundaries() {
+        // bits 6-9 cross a byte, 60-70 a word and 127-128 both at once
+        let mut bytes = [0u8; 24];
+        for i in [
+            6, 7, 8, 9, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 127, 128, 191,
+        ] {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+        let ranger = Ranger::<u64>::from_bitmap_bytes(&bytes);
+        assert_eq!(ranger.to_string(), "6-9,60-70,127-128,191");
+        assert_eq!(ranger.to_bitmap_bytes(24).unwrap(), bytes);
+        // full bytes and words inside a longer run, ending on the last bit
+        let mut bytes = [0xFFu8; 20];
+        bytes[0] = 0b1000_0000;
+        let ranger = Ranger::<u32>::from_bitmap_bytes(&bytes);
+        assert_eq!(ranger.to_string(), "7-159");
+        assert_eq!(ranger.to_bitmap_bytes(20).unwrap(), bytes);
+        // a short tail past the last whole word
+        let ranger = Ranger::<u16>::from_bitmap_bytes(&[0, 0, 0, 0, 0, 0, 0, 0, 0b10]);
+        assert_eq!(ranger.to_string(), "65");
+        assert!(Ranger::<u8>::from_bitmap_bytes(&[]).is_empty());
+        assert!(Ranger::<u8>::from_bitmap_bytes(&[0; 17]).is_empty());
+        assert_eq!(Ranger::<u8>::new().to_bitmap_bytes(3), Ok(vec![0; 3]));
+    }
+
+    #[test]
+    fn bytes_out_of_range() {
+        let ranger: Ranger<i32> = "3-15".parse().unwrap();
+        let mut out = [0xAA; 2];
+        assert_eq!(ranger.write_bitmap_bytes(&mut out), Ok(()));
+        assert_eq!(out, [0xF8, 0xFF]);
+        // a failed write leaves the buffer alone
+        let mut out = [0xAA; 1];
+        assert_eq!(ranger.write_bitmap_bytes(&mut out), Err(OutOfRange));
+        assert_eq!(out, [0xAA]);
+        let ranger: Ranger<i32> = "-1,3".parse().unwrap();
+        assert_eq!(ranger.to_bitmap_bytes(8), Err(OutOfRange));
+        let ranger: Ranger<u8> = "0".parse().unwrap();
+        assert_eq!(ranger.to_bitmap_bytes(0), Err(OutOfRange));
+    }
+
+    #[test]
+    fn random_byte_round_trips() {
+        let mut rng = thread_rng();
+        for _ in 0..500 {
+            let len = rng.gen_range(0..40);
+            // runs of clear and full bytes as well as mixed ones
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| match rng.gen_range(0..4) {
+                    0 => 0,
+                    1 => 0xFF,
+                    _ => rng.gen(),
+                })
+                .collect();
+            let ranger = Ranger::<u16>::from_bitmap_bytes(&bytes);
+            let ones: u32 = bytes.iter().map(|b| b.count_ones()).sum();
+            assert_eq!(ranger.len(), ones as u128);
+            for i in 0..len * 8 {
+                let bit = bytes[i / 8] >> (i % 8) & 1 == 1;
+                assert_eq!(ranger.contains(&(i as u16)), bit);
+            }
+            assert_eq!(ranger.to_bitmap_bytes(len).unwrap(), bytes);
+            let mut wider = bytes.clone();
+            wider.resize(len + 9, 0);
+            assert_eq!(ranger.to_bitmap_bytes(len + 9).unwrap(), wider);
+        }
+    }
+}
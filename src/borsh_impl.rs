@@ -0,0 +1,88 @@
+use crate::{Config, Discrete, Ranger, Unit};
+use alloc::collections::BTreeSet;
+use borsh::{
+    io::{Error, ErrorKind, Read, Result, Write},
+    BorshDeserialize, BorshSerialize,
+};
+
+/// Writes a `u32` run count followed by each run's `(low, high)` pair in
+/// ascending order, the same layout borsh gives a `Vec<(T, T)>`
+impl<T: BorshSerialize> BorshSerialize for Ranger<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let len = u32::try_from(self.0.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Too many ranges"))?;
+        len.serialize(writer)?;
+        for u in self.0.iter() {
+            u.l.serialize(writer)?;
+            u.high().serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Accepts only the layout serialization produces: pairs in ascending order
+/// that neither overlap nor touch, so equal sets have exactly one encoding
+impl<T: BorshDeserialize + Discrete> BorshDeserialize for Ranger<T> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        let mut set = BTreeSet::new();
+        let mut previous: Option<T> = None;
+        for _ in 0..len {
+            let low = T::deserialize_reader(reader)?;
+            let high = T::deserialize_reader(reader)?;
+            if low > high || previous.is_some_and(|p| p >= low || T::adjacent(&p, &low)) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "Ranges are out of order, reversed or touching",
+                ));
+            }
+            previous = Some(high.clone());
+            set.insert(Unit::new(low, high));
+        }
+        Ok(Ranger(set, Config::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn round_trip() {
+        let ranger: Ranger<u16> = "0-2,4,6-8".parse().unwrap();
+        let bytes = borsh::to_vec(&ranger).unwrap();
+        assert_eq!(
+            bytes,
+            borsh::to_vec(&[(0u16, 2u16), (4, 4), (6, 8)].to_vec()).unwrap()
+        );
+        assert_eq!(borsh::from_slice::<Ranger<u16>>(&bytes).unwrap(), ranger);
+        let reordered: Ranger<u16> = "8,7,6,4,2,1,0".parse().unwrap();
+        assert_eq!(borsh::to_vec(&reordered).unwrap(), bytes);
+
+        let ranger: Ranger<i64> = "-9223372036854775808--5,9223372036854775807"
+            .parse()
+            .unwrap();
+        let bytes = borsh::to_vec(&ranger).unwrap();
+        assert_eq!(borsh::from_slice::<Ranger<i64>>(&bytes).unwrap(), ranger);
+        let empty = Ranger::<u8>::new();
+        assert_eq!(borsh::to_vec(&empty).unwrap(), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_invalid_pairs() {
+        for pairs in [
+            Vec::from([(4u16, 5u16), (0, 2)]),
+            Vec::from([(0, 2), (3, 5)]),
+            Vec::from([(0, 4), (3, 5)]),
+            Vec::from([(0, 2), (6, 5)]),
+        ] {
+            let bytes = borsh::to_vec(&pairs).unwrap();
+            let err = borsh::from_slice::<Ranger<u16>>(&bytes).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+        let bytes = borsh::to_vec(&Vec::from([(0u16, 2u16), (4, 5)])).unwrap();
+        assert!(borsh::from_slice::<Ranger<u16>>(&bytes).is_ok());
+        assert!(borsh::from_slice::<Ranger<u16>>(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+#[cfg(feature = "alloc")]
+use crate::Ranger;
+use crate::{write_runs, FormatOptions, Span};
+use core::ops::Bound;
+use core::{
+    fmt::{self, Display},
+    ops::{RangeBounds, RangeInclusive},
+};
+
+/// A set of `u8` values held as a 256-bit bitmap, for tracking which bytes
+/// have been seen.
+///
+/// It writes the same text as a `Ranger<u8>` and answers the same queries,
+/// but membership changes take constant time and whole-set operations work a
+/// word at a time. Runs are rebuilt from the bits when asked for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteRanger([u64; 4]);
+
+/// Bits of a word from `from` to `to`, both within it
+fn mask(from: u8, to: u8) -> u64 {
+    let (from, to) = (from % 64, to % 64);
+    (u64::MAX >> (63 - to)) & (u64::MAX << from)
+}
+
+impl ByteRanger {
+    pub const fn new() -> Self {
+        ByteRanger([0; 4])
+    }
+    pub fn contains(&self, value: &u8) -> bool {
+        self.0[*value as usize / 64] & (1 << (value % 64)) != 0
+    }
+    /// Inserts `value`, returning whether it was not already present
+    pub fn insert(&mut self, value: u8) -> bool {
+        let present = self.contains(&value);
+        self.0[value as usize / 64] |= 1 << (value % 64);
+        !present
+    }
+    /// Removes `value`, returning whether it was present
+    pub fn remove(&mut self, value: &u8) -> bool {
+        let present = self.contains(value);
+        self.0[*value as usize / 64] &= !(1 << (value % 64));
+        present
+    }
+    /// Calls `f` with each word index and the mask of the bits from `l` to
+    /// `h` within it
+    fn each_word(l: u8, h: u8, mut f: impl FnMut(usize, u64)) {
+        for word in l as usize / 64..=h as usize / 64 {
+            let from = if word == l as usize / 64 { l } else { 0 };
+            let to = if word == h as usize / 64 { h } else { 63 };
+            f(word, mask(from, to));
+        }
+    }
+    /// Inserts every value in `range`, returning the number of values that
+    /// were not already present
+    pub fn insert_range(&mut self, range: RangeInclusive<u8>) -> u128 {
+        let (l, h) = range.into_inner();
+        if l > h {
+            return 0;
+        }
+        let mut added = 0;
+        Self::each_word(l, h, |word, bits| {
+            added += (bits & !self.0[word]).count_ones() as u128;
+            self.0[word] |= bits;
+        });
+        added
+    }
+    /// Removes every value within `bounds`, which may have exclusive or
+    /// unbounded ends, returning the number of values that were present
+    pub fn remove_range<R: RangeBounds<u8>>(&mut self, bounds: R) -> u128 {
+        let l = match bounds.start_bound() {
+            Bound::Included(l) => *l,
+            Bound::Excluded(l) if *l == u8::MAX => return 0,
+            Bound::Excluded(l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let h = match bounds.end_bound() {
+            Bound::Included(h) => *h,
+            Bound::Excluded(0) => return 0,
+            Bound::Excluded(h) => h - 1,
+            Bound::Unbounded => u8::MAX,
+        };
+        if l > h {
+            return 0;
+        }
+        let mut removed = 0;
+        Self::each_word(l, h, |word, bits| {
+            removed += (bits & self.0[word]).count_ones() as u128;
+            self.0[word] &= !bits;
+        });
+        removed
+    }
+    /// Returns the number of values stored
+    pub fn len(&self) -> u128 {
+        self.0.iter().map(|w| w.count_ones() as u128).sum()
+    }
+    /// Returns whether no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.0 == [0; 4]
+    }
+    /// Returns the number of runs
+    pub fn range_count(&self) -> usize {
+        self.ranges().count()
+    }
+    /// First value from `from` on whose bit is `set`, if any
+    fn next_bit(&self, from: u16, set: bool) -> Option<u16> {
+        let mut word = from as usize / 64;
+        let mut bits = if set { self.0[word] } else { !self.0[word] } & (u64::MAX << (from % 64));
+        loop {
+            if bits != 0 {
+                return Some(word as u16 * 64 + bits.trailing_zeros() as u16);
+            }
+            word += 1;
+            bits = *self.0.get(word)?;
+            if !set {
+                bits = !bits;
+            }
+        }
+    }
+    /// Iterates over the runs in ascending order
+    pub fn ranges(&self) -> impl Iterator<Item = Span<u8>> + '_ {
+        let mut from = 0u16;
+        core::iter::from_fn(move || {
+            if from > u8::MAX as u16 {
+                return None;
+            }
+            let l = self.next_bit(from, true)?;
+            let h = self.next_bit(l, false).unwrap_or(256) - 1;
+            from = h + 2;
+            Some(Span::new_unchecked(l as u8, h as u8))
+        })
+    }
+    /// Returns the values in either set
+    pub fn union(&self, other: &ByteRanger) -> ByteRanger {
+        ByteRanger(core::array::from_fn(|i| self.0[i] | other.0[i]))
+    }
+    /// Returns the values in both sets
+    pub fn intersection(&self, other: &ByteRanger) -> ByteRanger {
+        ByteRanger(core::array::from_fn(|i| self.0[i] & other.0[i]))
+    }
+}
+
+impl Display for ByteRanger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let runs = self.ranges().map(Span::into_inner);
+        write_runs(f, runs, &FormatOptions::new())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&Ranger<u8>> for ByteRanger {
+    fn from(ranger: &Ranger<u8>) -> Self {
+        let mut bytes = ByteRanger::new();
+        for u in ranger.0.iter() {
+            bytes.insert_range(u.l..=*u.high());
+        }
+        bytes
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<ByteRanger> for Ranger<u8> {
+    fn from(bytes: ByteRanger) -> Self {
+        let mut ranger = Ranger::new();
+        for r in bytes.ranges() {
+            ranger.insert_range(r.into());
+        }
+        ranger
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn agrees_with_ranger() {
+        let mut rng = thread_rng();
+        for _ in 0..300 {
+            let mut bytes = ByteRanger::new();
+            let mut ranger = Ranger::new();
+            for _ in 0..100 {
+                let v: u8 = rng.gen();
+                let w = v.saturating_add(rng.gen_range(0..70));
+                match rng.gen_range(0..4) {
+                    0 => assert_eq!(bytes.insert(v), ranger.insert(v)),
+                    1 => assert_eq!(bytes.remove(&v), ranger.remove(&v)),
+                    2 => assert_eq!(bytes.insert_range(v..=w), ranger.insert_range(v..=w)),
+                    _ => assert_eq!(bytes.remove_range(v..w), ranger.remove_range(v..w)),
+                }
+                assert_eq!(bytes.to_string(), ranger.to_string());
+                assert_eq!(bytes.len(), ranger.len());
+                assert_eq!(bytes.range_count(), ranger.range_count());
+                assert_eq!(bytes.is_empty(), ranger.is_empty());
+            }
+            for v in 0..=u8::MAX {
+                assert_eq!(bytes.contains(&v), ranger.contains(&v));
+            }
+            assert_eq!(ByteRanger::from(&ranger), bytes);
+            assert_eq!(Ranger::from(bytes), ranger);
+        }
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let mut bytes = ByteRanger::new();
+        assert_eq!(bytes.insert_range(63..=64), 2);
+        assert!(bytes.insert(127) && bytes.insert(128));
+        assert!(bytes.insert(255));
+        assert_eq!(bytes.to_string(), "63-64,127-128,255");
+        assert_eq!(bytes.range_count(), 3);
+        assert_eq!(bytes.insert_range(0..=255), 251);
+        assert_eq!(bytes.to_string(), "0-255");
+        assert_eq!(bytes.remove_range(64..128), 64);
+        assert_eq!(bytes.to_string(), "0-63,128-255");
+        assert!(bytes.remove(&255) && !bytes.contains(&255));
+        assert_eq!(bytes.remove_range(..), 191);
+        assert!(bytes.is_empty());
+        assert_eq!(bytes.remove_range(..0), 0);
+
+        let low = ByteRanger::from(&"0-64,200".parse::<Ranger<u8>>().unwrap());
+        let high = ByteRanger::from(&"63-127".parse::<Ranger<u8>>().unwrap());
+        assert_eq!(low.union(&high).to_string(), "0-127,200");
+        assert_eq!(low.intersection(&high).to_string(), "63-64");
+    }
+}
@@ -0,0 +1,190 @@
+//! Compact binary form: the run count, then for each run the number of
+//! absent values separating it from the previous run (the first run's
+//! distance from the type's minimum instead) and its width, all as LEB128
+//! varints.
+
+use crate::{Config, DecodeError, Discrete, Ranger};
+use alloc::{collections::BTreeSet, vec::Vec};
+use num_traits::Bounded;
+
+fn write_varint(out: &mut impl Extend<u8>, mut v: u128) {
+    while v >= 0x80 {
+        out.extend([v as u8 | 0x80]);
+        v >>= 7;
+    }
+    out.extend([v as u8]);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u128, DecodeError> {
+    let start = *pos;
+    let mut v = 0u128;
+    for shift in (0..).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or(DecodeError::Truncated)?;
+        *pos += 1;
+        let bits = (byte & 0x7f) as u128;
+        if shift >= 128 || (shift > 121 && bits >> (128 - shift) != 0) {
+            return Err(DecodeError::Overlong(start));
+        }
+        v |= bits << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    Ok(v)
+}
+
+impl<T: Discrete + Bounded> Ranger<T> {
+    /// Appends the compact binary form of the set to `out`
+    pub fn encode_into(&self, out: &mut impl Extend<u8>) {
+        write_varint(out, self.0.len() as u128);
+        let mut previous: Option<&T> = None;
+        for u in self.0.iter() {
+            let skip = match previous {
+                Some(high) => T::distance(high, &u.l).unwrap() - 1,
+                None => T::distance(&T::min_value(), &u.l).unwrap(),
+            };
+            write_varint(out, skip);
+            write_varint(out, T::distance(&u.l, u.high()).unwrap());
+            previous = Some(u.high());
+        }
+    }
+    /// Returns the compact binary form of the set
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+    /// Reads a set written by [`Ranger::encode`], which must span all of
+    /// `bytes`
+    pub fn decode(bytes: &[u8]) -> Result<Ranger<T>, DecodeError> {
+        let mut pos = 0;
+        let count = read_varint(bytes, &mut pos)?;
+        let mut set = BTreeSet::new();
+        let mut previous: Option<T> = None;
+        for _ in 0..count {
+            let at = pos;
+            let skip = read_varint(bytes, &mut pos)?;
+            let low = match previous {
+                // adjacent runs would have been merged, so at least one
+                // value must separate them
+                Some(_) if skip == 0 => return Err(DecodeError::Unordered(at)),
+                Some(high) => skip.checked_add(1).and_then(|s| high.forward(s)),
+                None => T::min_value().forward(skip),
+            }
+            .ok_or(DecodeError::OutOfRange(at))?;
+            let at = pos;
+            let w = read_varint(bytes, &mut pos)?;
+            let high = low.forward(w).ok_or(DecodeError::OutOfRange(at))?;
+            previous = Some(high.clone());
+            set.insert(crate::Unit::new(low, high));
+        }
+        if pos < bytes.len() {
+            return Err(DecodeError::TrailingBytes(pos));
+        }
+        Ok(Ranger(set, Config::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use num_traits::Zero;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn golden_bytes() {
+        let ranger: Ranger<u16> = "0-2,4,6-8,1000".parse().unwrap();
+        let bytes = ranger.encode();
+        assert_eq!(bytes, [4, 0, 2, 1, 0, 1, 2, 0xdf, 0x07, 0]);
+        assert_eq!(Ranger::decode(&bytes), Ok(ranger));
+
+        let ranger: Ranger<i8> = "-128--126,-1-2,127".parse().unwrap();
+        let bytes = ranger.encode();
+        assert_eq!(bytes, [3, 0, 2, 124, 3, 124, 0]);
+        assert_eq!(Ranger::decode(&bytes), Ok(ranger));
+
+        assert_eq!(Ranger::<u64>::new().encode(), [0]);
+        let mut full = Ranger::<u128>::new();
+        full.insert_range(0..=u128::MAX);
+        let bytes = full.encode();
+        assert_eq!(bytes.len(), 1 + 1 + 19);
+        assert_eq!(Ranger::decode(&bytes), Ok(full));
+
+        let mut out = vec![0xaa];
+        Ranger::<u8>::decode(&[1, 5, 0])
+            .unwrap()
+            .encode_into(&mut out);
+        assert_eq!(out, [0xaa, 1, 5, 0]);
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(Ranger::<u16>::decode(&[]), Err(DecodeError::Truncated));
+        assert_eq!(
+            Ranger::<u16>::decode(&[2, 0, 2]),
+            Err(DecodeError::Truncated)
+        );
+        assert_eq!(
+            Ranger::<u16>::decode(&[1, 0x80]),
+            Err(DecodeError::Truncated)
+        );
+        assert_eq!(
+            Ranger::<u16>::decode(&[1, 0, 2, 9]),
+            Err(DecodeError::TrailingBytes(3))
+        );
+        assert_eq!(
+            Ranger::<u16>::decode(&[2, 0, 2, 0, 1]),
+            Err(DecodeError::Unordered(3))
+        );
+        assert_eq!(
+            Ranger::<u8>::decode(&[1, 0x80, 0x02, 0]),
+            Err(DecodeError::OutOfRange(1))
+        );
+        assert_eq!(
+            Ranger::<u8>::decode(&[1, 100, 0xc8, 0x01]),
+            Err(DecodeError::OutOfRange(2))
+        );
+        assert_eq!(
+            Ranger::<u8>::decode(&[2, 0, 0, 0xff, 0x01, 0]),
+            Err(DecodeError::OutOfRange(3))
+        );
+        let mut overlong = vec![1];
+        overlong.extend([0xff; 18]);
+        overlong.push(0x04);
+        assert_eq!(
+            Ranger::<u128>::decode(&overlong),
+            Err(DecodeError::Overlong(1))
+        );
+        overlong[19] = 0x03;
+        overlong.push(0);
+        assert!(Ranger::<u128>::decode(&overlong).is_ok());
+    }
+
+    fn round_trip<T>(max: T)
+    where
+        T: Discrete + Bounded + Zero + Copy,
+        T: rand::distributions::uniform::SampleUniform + core::fmt::Debug,
+    {
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let mut ranger = Ranger(BTreeSet::new(), Config::default());
+            for _ in 0..rng.gen_range(0..20) {
+                let low = rng.gen_range(T::zero()..=max);
+                let high = rng.gen_range(low..=max);
+                ranger.insert_range(low..=high);
+            }
+            assert_eq!(Ranger::decode(&ranger.encode()), Ok(ranger));
+        }
+    }
+
+    #[test]
+    fn random_round_trips() {
+        round_trip(u16::MAX);
+        round_trip(1000u16);
+        round_trip(u32::MAX);
+        round_trip(100_000u32);
+        round_trip(u64::MAX);
+        round_trip(10_000u64);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{Discrete, Ranger};
+use core::{cell::RefCell, fmt};
+use critical_section::Mutex;
+
+/// A set that can be shared between interrupt handlers and the main loop,
+/// or between threads, taking a critical section around every access.
+///
+/// It can be built in a `static`, as it starts empty and allocates only
+/// once values are inserted. Inserting may then allocate within the
+/// critical section, so the global allocator must be safe to call there.
+///
+/// ```
+/// use ranger::CriticalRanger;
+///
+/// static SEEN: CriticalRanger<u32> = CriticalRanger::new();
+///
+/// // from an interrupt handler
+/// SEEN.insert(7);
+/// SEEN.insert(8);
+/// // and from the main loop
+/// assert!(SEEN.contains(&8));
+/// assert_eq!(SEEN.with(|seen| seen.to_string()), "7-8");
+/// ```
+pub struct CriticalRanger<T>(Mutex<RefCell<Ranger<T>>>);
+
+impl<T> CriticalRanger<T> {
+    /// Returns an empty set
+    pub const fn new() -> Self {
+        CriticalRanger(Mutex::new(RefCell::new(Ranger::new())))
+    }
+    /// Calls `f` on the set within a critical section, returning its result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within `f` or from [`with_mut`](Self::with_mut)
+    /// on the same set.
+    pub fn with<R>(&self, f: impl FnOnce(&Ranger<T>) -> R) -> R {
+        critical_section::with(|cs| f(&self.0.borrow(cs).borrow()))
+    }
+    /// Calls `f` on the set for mutation within a critical section,
+    /// returning its result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within `f` or from [`with`](Self::with) on the
+    /// same set.
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut Ranger<T>) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.0.borrow(cs).borrow_mut()))
+    }
+    /// Unwraps the set
+    pub fn into_inner(self) -> Ranger<T> {
+        self.0.into_inner().into_inner()
+    }
+}
+
+impl<T: Discrete> CriticalRanger<T> {
+    /// Inserts `value`, returning whether it was not already present
+    pub fn insert(&self, value: T) -> bool {
+        self.with_mut(|r| r.insert(value))
+    }
+    /// Removes `value`, returning whether it was present
+    pub fn remove(&self, value: &T) -> bool {
+        self.with_mut(|r| r.remove(value))
+    }
+    /// Returns whether `value` is stored
+    pub fn contains(&self, value: &T) -> bool {
+        self.with(|r| r.contains(value))
+    }
+}
+
+impl<T> Default for CriticalRanger<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Ranger<T>> for CriticalRanger<T> {
+    fn from(ranger: Ranger<T>) -> Self {
+        CriticalRanger(Mutex::new(RefCell::new(ranger)))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CriticalRanger<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with(|r| f.debug_tuple("CriticalRanger").field(r).finish())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::{format, string::ToString, vec::Vec};
+    use std::thread;
+
+    static SHARED: CriticalRanger<u32> = CriticalRanger::new();
+
+    #[test]
+    fn threads_interleave() {
+        // each thread owns the values congruent to its index, so the runs
+        // only join up once every thread has done its part
+        let threads: Vec<_> = (0..4)
+            .map(|t| {
+                thread::spawn(move || {
+                    for v in (t..4000).step_by(4) {
+                        assert!(SHARED.insert(v));
+                        assert!(SHARED.contains(&v));
+                        SHARED.with(|r| assert!(r.len() <= 4000));
+                    }
+                    for v in (t..100).step_by(4) {
+                        assert!(SHARED.remove(&v));
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+        assert_eq!(SHARED.with(|r| r.to_string()), "100-3999");
+    }
+
+    #[test]
+    fn accessors() {
+        let shared = CriticalRanger::from("1-3".parse::<Ranger<i8>>().unwrap());
+        assert!(!shared.insert(2));
+        assert!(shared.insert(5));
+        assert_eq!(shared.with_mut(|r| r.insert_range(-5..=0)), 6);
+        assert!(!shared.remove(&4));
+        let inner = shared.with(|r| format!("{r:?}"));
+        assert_eq!(format!("{shared:?}"), format!("CriticalRanger({inner})"));
+        assert_eq!(shared.into_inner().to_string(), "-5-3,5");
+        assert!(CriticalRanger::<u8>::default().with(Ranger::is_empty));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    discrete::{step_down, step_up},
+    Discrete, Ranger, Span, Unit,
+};
+use alloc::collections::{btree_set, BTreeSet};
+use core::ops::Bound::{Excluded, Unbounded};
+
+/// Runs a forward seek steps through before falling back to a fresh search
+const SCAN: usize = 8;
+
+/// A position among the runs of a [`Ranger`] that can be moved either way
+/// or sought to a value, returned by [`Ranger::cursor`].
+///
+/// Besides the runs, the cursor can rest on a ghost position that lies both
+/// before the first run and after the last, where it starts. Moving forward
+/// from the ghost reaches the first run and moving back reaches the last, so
+/// the cursor wraps around rather than ending like an iterator.
+#[derive(Clone, Debug)]
+pub struct RangerCursor<'a, T> {
+    set: &'a BTreeSet<Unit<T>>,
+    current: Option<&'a Unit<T>>,
+    /// The runs after `current`, kept so that stepping and short seeks
+    /// forward need no search
+    ahead: btree_set::Range<'a, Unit<T>>,
+}
+
+impl<T: Discrete> Ranger<T> {
+    /// Returns a cursor resting on the ghost position, before the first run
+    pub fn cursor(&self) -> RangerCursor<'_, T> {
+        RangerCursor {
+            set: &self.0,
+            current: None,
+            ahead: self.0.range::<Unit<T>, _>(..),
+        }
+    }
+}
+
+impl<'a, T: Discrete> RangerCursor<'a, T> {
+    /// Returns the ends of the run the cursor is on, or `None` on the ghost
+    /// position
+    pub fn current(&self) -> Option<(&'a T, &'a T)> {
+        self.current.map(|u| (&u.l, u.high()))
+    }
+    /// Moves to the first run ending at or above `to`, or to the ghost
+    /// position if there is none, and returns that run.
+    ///
+    /// Seeking forward by a few runs steps through them, so a series of
+    /// ascending seeks costs about as much as walking the runs once.
+    pub fn seek(&mut self, to: &T) -> Option<(&'a T, &'a T)> {
+        if self.current.is_some_and(|u| u.high() < to) {
+            for _ in 0..SCAN {
+                self.current = self.ahead.next();
+                match self.current {
+                    Some(u) if u.high() < to => {}
+                    _ => return self.current(),
+                }
+            }
+        }
+        self.ahead = self.set.range::<T, _>(to..);
+        self.current = self.ahead.next();
+        self.current()
+    }
+    /// Moves to the next run, or from the last run to the ghost position,
+    /// and returns it
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(&'a T, &'a T)> {
+        if self.current.is_none() {
+            self.ahead = self.set.range::<Unit<T>, _>(..);
+        }
+        self.current = self.ahead.next();
+        self.current()
+    }
+    /// Moves to the previous run, or from the first run to the ghost
+    /// position, and returns it
+    pub fn prev(&mut self) -> Option<(&'a T, &'a T)> {
+        let mut before = match self.current {
+            Some(u) => self.set.range::<Unit<T>, _>((Unbounded, Excluded(u))),
+            None => self.set.range::<Unit<T>, _>(..),
+        };
+        self.current = before.next_back();
+        if let Some(u) = self.current {
+            self.ahead = self.set.range::<Unit<T>, _>((Excluded(u), Unbounded));
+        }
+        self.current()
+    }
+    /// Returns the values missing between the run the cursor is on and the
+    /// next, or `None` on the ghost position or the last run
+    pub fn peek_gap_after(&self) -> Option<Span<T>> {
+        let u = self.current?;
+        let next = self.ahead.clone().next()?;
+        Some(Span::new_unchecked(step_up(u.high()), step_down(&next.l)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use rand::{thread_rng, Rng};
+
+    fn runs() -> Ranger<u32> {
+        "2-4,10,20-29,40-41,60".parse().unwrap()
+    }
+
+    #[test]
+    fn walks_match_ranges() {
+        let ranger = runs();
+        let mut cursor = ranger.cursor();
+        assert_eq!(cursor.current(), None);
+        let mut forward = Vec::new();
+        while let Some((l, h)) = cursor.next() {
+            forward.push(Span::new(*l, *h).unwrap());
+        }
+        assert!(forward.iter().cloned().eq(ranger.ranges()));
+        // and back again from the ghost position
+        let mut backward = Vec::new();
+        while let Some((l, h)) = cursor.prev() {
+            backward.push(Span::new(*l, *h).unwrap());
+        }
+        backward.reverse();
+        assert_eq!(backward, forward);
+        assert_eq!(cursor.next(), Some((&2, &4)));
+        let empty = Ranger::<u32>::new();
+        let mut cursor = empty.cursor();
+        assert_eq!(cursor.next(), None);
+        assert_eq!(cursor.prev(), None);
+        assert_eq!(cursor.seek(&5), None);
+    }
+
+    #[test]
+    fn seeks_and_steps() {
+        let ranger = runs();
+        let mut cursor = ranger.cursor();
+        // before the first run, inside a run and in a gap
+        assert_eq!(cursor.seek(&0), Some((&2, &4)));
+        assert_eq!(cursor.seek(&25), Some((&20, &29)));
+        assert_eq!(cursor.seek(&30), Some((&40, &41)));
+        assert_eq!(cursor.peek_gap_after(), Span::new(42, 59));
+        assert_eq!(cursor.prev(), Some((&20, &29)));
+        assert_eq!(cursor.peek_gap_after(), Span::new(30, 39));
+        // backwards
+        assert_eq!(cursor.seek(&5), Some((&10, &10)));
+        assert_eq!(cursor.next(), Some((&20, &29)));
+        // past the last run
+        assert_eq!(cursor.seek(&61), None);
+        assert_eq!(cursor.peek_gap_after(), None);
+        assert_eq!(cursor.prev(), Some((&60, &60)));
+        assert_eq!(cursor.peek_gap_after(), None);
+        assert_eq!(cursor.next(), None);
+        assert_eq!(cursor.next(), Some((&2, &4)));
+        assert_eq!(cursor.prev(), None);
+    }
+
+    #[test]
+    fn ascending_probes() {
+        let mut rng = thread_rng();
+        let mut ranger = Ranger::<u32>::new();
+        for _ in 0..300 {
+            let l = rng.gen_range(0..10_000);
+            ranger.insert_range(l..=l + rng.gen_range(0..20));
+        }
+        let mut cursor = ranger.cursor();
+        let mut probe = 0;
+        while probe < 10_100 {
+            let found = cursor.seek(&probe).map(|(l, h)| (*l, *h));
+            let expected = ranger.ranges().find(|s| s.high() >= &probe);
+            assert_eq!(found, expected.map(Span::into_inner), "{probe}");
+            probe += rng.gen_range(0..200);
+        }
+    }
+}
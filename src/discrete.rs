@@ -0,0 +1,539 @@
+use core::{
+    net::{Ipv4Addr, Ipv6Addr},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+};
+use num_traits::{NumCast, ToPrimitive};
+
+/// A totally ordered type whose values follow one another in discrete steps,
+/// which is all a [`Ranger`](crate::Ranger) needs of its elements.
+///
+/// Implemented for the primitive integers, for `char`, which steps over the
+/// surrogate range, for the `NonZero` integers, which step over zero, and for
+/// `Ipv4Addr` and `Ipv6Addr`, plus `BigUint` and `BigInt` with the
+/// `num-bigint` feature and `time::Date` with the `time` feature. As
+/// `num_traits` provides no `Bounded` for the `NonZero` integers, the methods
+/// needing it are unavailable for them, and their domain is best given
+/// explicitly as `MIN..=MAX`. Other types, such as newtypes around an
+/// integer, can implement it by delegation, which the `derive` feature writes
+/// for them as `#[derive(Discrete)]`:
+///
+#[cfg_attr(feature = "alloc", doc = "```")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use core::fmt;
+/// use ranger::{Discrete, Ranger};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct BlockId(u64);
+///
+/// impl Discrete for BlockId {
+///     fn successor(&self) -> Option<Self> {
+///         self.0.successor().map(BlockId)
+///     }
+///     fn predecessor(&self) -> Option<Self> {
+///         self.0.predecessor().map(BlockId)
+///     }
+///     fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+///         u64::distance(&lower.0, &upper.0)
+///     }
+///     fn forward(&self, steps: u128) -> Option<Self> {
+///         self.0.forward(steps).map(BlockId)
+///     }
+///     fn backward(&self, steps: u128) -> Option<Self> {
+///         self.0.backward(steps).map(BlockId)
+///     }
+/// }
+///
+/// impl fmt::Display for BlockId {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "#{}", self.0)
+///     }
+/// }
+///
+/// let mut blocks = Ranger::new();
+/// for id in [9, 7, 8, 12] {
+///     blocks.insert(BlockId(id));
+/// }
+/// assert_eq!(blocks.to_string(), "#7-#9,#12");
+/// assert!(blocks.contains(&BlockId(8)));
+/// ```
+///
+/// # Laws
+///
+/// The set relies on these holding, and may store the wrong values or panic
+/// if they don't:
+///
+/// - `a.successor()` is `Some(b)` exactly when `b` is the least value greater
+///   than `a`, and `b.predecessor()` is then `Some(a)`
+/// - `adjacent(a, b)` holds exactly when `a.successor()` is `Some(b)`
+/// - `distance(a, b)` is the number of successor steps from `a` up to `b`,
+///   and returns `None` only when that count does not fit in a `u128`
+/// - `a.forward(n)` is the value `n` successor steps up from `a`, and
+///   `backward` the value `n` predecessor steps down, with `None` when the
+///   type runs out of values
+pub trait Discrete: Ord + Clone {
+    /// Whether `upper` is the value immediately following `lower`
+    fn adjacent(lower: &Self, upper: &Self) -> bool {
+        lower.successor().as_ref() == Some(upper)
+    }
+    /// Returns the value immediately following this one, or `None` at the
+    /// top of the type
+    fn successor(&self) -> Option<Self>;
+    /// Returns the value immediately preceding this one, or `None` at the
+    /// bottom of the type
+    fn predecessor(&self) -> Option<Self>;
+    /// Number of steps from `lower` up to `upper`, which must not be below
+    /// it, or `None` if that does not fit in a `u128`
+    fn distance(lower: &Self, upper: &Self) -> Option<u128>;
+    /// Moves `steps` values up, or returns `None` past the top of the type
+    fn forward(&self, steps: u128) -> Option<Self>;
+    /// Moves `steps` values down, or returns `None` past the bottom of the
+    /// type
+    fn backward(&self, steps: u128) -> Option<Self>;
+}
+
+/// Number of steps from `l` up to `h`, or `None` if it cannot be represented
+fn width<T: ToPrimitive>(l: &T, h: &T) -> Option<u128> {
+    if let (Some(l), Some(h)) = (l.to_u128(), h.to_u128()) {
+        Some(h - l)
+    } else if let (Some(l), Some(h)) = (l.to_i128(), h.to_i128()) {
+        // the true difference always fits in a u128
+        Some(h.wrapping_sub(l) as u128)
+    } else {
+        None
+    }
+}
+
+/// Moves `v` by `steps` upwards (or downwards), or returns `None` if the
+/// result does not fit in `T`
+fn step<T: ToPrimitive + NumCast>(v: &T, steps: u128, up: bool) -> Option<T> {
+    // work in sign and magnitude so that no intermediate can overflow
+    let (neg, mag) = match v.to_u128() {
+        Some(mag) => (false, mag),
+        None => (true, v.to_i128()?.unsigned_abs()),
+    };
+    let (neg, mag) = if neg != up {
+        (neg, mag.checked_add(steps)?)
+    } else if mag >= steps {
+        (neg, mag - steps)
+    } else {
+        (!neg, steps - mag)
+    };
+    if neg && mag > 0 {
+        // i128::MIN is the one negative value whose magnitude isn't an i128
+        let v = if mag == 1 << 127 {
+            i128::MIN
+        } else {
+            -i128::try_from(mag).ok()?
+        };
+        T::from(v)
+    } else {
+        T::from(mag)
+    }
+}
+
+/// The value after `v`, which the caller knows lies below some other value
+/// and so is not the top of the type. Every step across the edge of a run
+/// goes through this or [`step_down`], never past either end of the domain.
+pub(crate) fn step_up<T: Discrete>(v: &T) -> T {
+    v.successor().expect("stepped above the top of the type")
+}
+
+/// The value before `v`, which the caller knows lies above some other value
+/// and so is not the bottom of the type
+pub(crate) fn step_down<T: Discrete>(v: &T) -> T {
+    v.predecessor()
+        .expect("stepped below the bottom of the type")
+}
+
+/// Number of values from `l` to `h`, saturating at `u128::MAX`
+pub(crate) fn run_len<T: Discrete>(l: &T, h: &T) -> u128 {
+    T::distance(l, h).map_or(u128::MAX, |w| w.saturating_add(1))
+}
+
+/// Number of values from `l` to `h` that are new once the `absorbed` values
+/// of the runs merged into it are discounted, saturating at `u128::MAX`
+pub(crate) fn added_len<T: Discrete>(l: &T, h: &T, absorbed: u128) -> u128 {
+    // at least one value is new, so the merged width is at least the
+    // absorbed count and this cannot overflow short of the full domain
+    T::distance(l, h).map_or(u128::MAX, |w| w.saturating_sub(absorbed).saturating_add(1))
+}
+
+macro_rules! discrete_int {
+    ($($t:ty)*) => {$(
+        impl Discrete for $t {
+            fn adjacent(lower: &Self, upper: &Self) -> bool {
+                lower.checked_add(1) == Some(*upper)
+            }
+            fn successor(&self) -> Option<Self> {
+                self.checked_add(1)
+            }
+            fn predecessor(&self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+            fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+                width(lower, upper)
+            }
+            fn forward(&self, steps: u128) -> Option<Self> {
+                step(self, steps, true)
+            }
+            fn backward(&self, steps: u128) -> Option<Self> {
+                step(self, steps, false)
+            }
+        }
+    )*};
+}
+
+discrete_int!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+/// Steps through a nonzero integer type by way of its position among the
+/// nonzero values: one below the value when positive, the value itself when
+/// negative
+macro_rules! discrete_nonzero {
+    (@impl $t:ty, $int:ty, $index:expr, $from_index:expr) => {
+        impl Discrete for $t {
+            fn successor(&self) -> Option<Self> {
+                $from_index($index(*self).successor()?)
+            }
+            fn predecessor(&self) -> Option<Self> {
+                $from_index($index(*self).predecessor()?)
+            }
+            fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+                <$int>::distance(&$index(*lower), &$index(*upper))
+            }
+            fn forward(&self, steps: u128) -> Option<Self> {
+                $from_index($index(*self).forward(steps)?)
+            }
+            fn backward(&self, steps: u128) -> Option<Self> {
+                $from_index($index(*self).backward(steps)?)
+            }
+        }
+    };
+    (unsigned $($t:ty => $int:ty),*) => {$(
+        discrete_nonzero!(@impl $t, $int,
+            |v: $t| v.get() - 1,
+            |i: $int| <$t>::new(i.checked_add(1)?));
+    )*};
+    (signed $($t:ty => $int:ty),*) => {$(
+        discrete_nonzero!(@impl $t, $int,
+            |v: $t| if v.get() < 0 { v.get() } else { v.get() - 1 },
+            |i: $int| <$t>::new(if i < 0 { i } else { i.checked_add(1)? }));
+    )*};
+}
+
+discrete_nonzero!(unsigned
+    NonZeroU8 => u8, NonZeroU16 => u16, NonZeroU32 => u32,
+    NonZeroU64 => u64, NonZeroU128 => u128, NonZeroUsize => usize);
+discrete_nonzero!(signed
+    NonZeroI8 => i8, NonZeroI16 => i16, NonZeroI32 => i32,
+    NonZeroI64 => i64, NonZeroI128 => i128, NonZeroIsize => isize);
+
+/// Position of `c` among the scalar values, skipping the surrogates
+fn char_index(c: char) -> u32 {
+    match c as u32 {
+        v @ 0xe000.. => v - 0x800,
+        v => v,
+    }
+}
+
+fn char_from_index(i: u128) -> Option<char> {
+    let i = u32::try_from(i).ok()?;
+    char::from_u32(if i >= 0xd800 { i + 0x800 } else { i })
+}
+
+impl Discrete for char {
+    fn successor(&self) -> Option<Self> {
+        match self {
+            '\u{d7ff}' => Some('\u{e000}'),
+            c => char::from_u32(*c as u32 + 1),
+        }
+    }
+    fn predecessor(&self) -> Option<Self> {
+        match self {
+            '\u{e000}' => Some('\u{d7ff}'),
+            c => char::from_u32((*c as u32).checked_sub(1)?),
+        }
+    }
+    fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+        Some((char_index(*upper) - char_index(*lower)) as u128)
+    }
+    fn forward(&self, steps: u128) -> Option<Self> {
+        char_from_index((char_index(*self) as u128).checked_add(steps)?)
+    }
+    fn backward(&self, steps: u128) -> Option<Self> {
+        char_from_index((char_index(*self) as u128).checked_sub(steps)?)
+    }
+}
+
+/// Steps through a type by way of the integer holding its bits
+macro_rules! discrete_bits {
+    ($($t:ty => $bits:ty),*) => {$(
+        impl Discrete for $t {
+            fn adjacent(lower: &Self, upper: &Self) -> bool {
+                <$bits>::adjacent(&lower.to_bits(), &upper.to_bits())
+            }
+            fn successor(&self) -> Option<Self> {
+                self.to_bits().successor().map(<$t>::from_bits)
+            }
+            fn predecessor(&self) -> Option<Self> {
+                self.to_bits().predecessor().map(<$t>::from_bits)
+            }
+            fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+                <$bits>::distance(&lower.to_bits(), &upper.to_bits())
+            }
+            fn forward(&self, steps: u128) -> Option<Self> {
+                self.to_bits().forward(steps).map(<$t>::from_bits)
+            }
+            fn backward(&self, steps: u128) -> Option<Self> {
+                self.to_bits().backward(steps).map(<$t>::from_bits)
+            }
+        }
+    )*};
+}
+
+discrete_bits!(Ipv4Addr => u32, Ipv6Addr => u128);
+
+#[cfg(feature = "num-bigint")]
+mod bigint {
+    use super::Discrete;
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::{One, ToPrimitive, Zero};
+
+    impl Discrete for BigUint {
+        fn adjacent(lower: &Self, upper: &Self) -> bool {
+            upper > lower && (upper - lower).is_one()
+        }
+        fn successor(&self) -> Option<Self> {
+            Some(self + 1u32)
+        }
+        fn predecessor(&self) -> Option<Self> {
+            (!self.is_zero()).then(|| self - 1u32)
+        }
+        fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+            (upper - lower).to_u128()
+        }
+        fn forward(&self, steps: u128) -> Option<Self> {
+            Some(self + steps)
+        }
+        fn backward(&self, steps: u128) -> Option<Self> {
+            let steps = BigUint::from(steps);
+            (&steps <= self).then(|| self - steps)
+        }
+    }
+
+    impl Discrete for BigInt {
+        fn adjacent(lower: &Self, upper: &Self) -> bool {
+            (upper - lower).is_one()
+        }
+        fn successor(&self) -> Option<Self> {
+            Some(self + 1)
+        }
+        fn predecessor(&self) -> Option<Self> {
+            Some(self - 1)
+        }
+        fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+            (upper - lower).to_u128()
+        }
+        fn forward(&self, steps: u128) -> Option<Self> {
+            Some(self + steps)
+        }
+        fn backward(&self, steps: u128) -> Option<Self> {
+            Some(self - steps)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::Ranger;
+    use alloc::string::ToString;
+
+    #[test]
+    fn char_steps() {
+        assert!(char::adjacent(&'\u{d7ff}', &'\u{e000}'));
+        assert!(!char::adjacent(&'a', &'a'));
+        assert_eq!(char::MAX.successor(), None);
+        assert_eq!('\0'.predecessor(), None);
+        assert_eq!('\u{e000}'.predecessor(), Some('\u{d7ff}'));
+        assert_eq!(char::distance(&'\0', &char::MAX), Some(0x10ffff - 0x800));
+        assert_eq!('\u{d7fe}'.forward(3), Some('\u{e001}'));
+        assert_eq!('\u{e001}'.backward(3), Some('\u{d7fe}'));
+        assert_eq!(char::MAX.forward(1), None);
+        assert_eq!('b'.backward(99), None);
+    }
+
+    #[test]
+    fn char_ranger() {
+        let mut ranger = Ranger::new();
+        for c in "the quick brown fox jumps over the lazy dog".chars() {
+            ranger.insert(c);
+        }
+        assert_eq!(ranger.to_string(), " ,a-z");
+        assert_eq!(ranger.len(), 27);
+        assert!(ranger.remove(&'m'));
+        assert_eq!(ranger.to_string(), " ,a-l,n-z");
+
+        let mut ranger = Ranger::new();
+        ranger.insert('\u{d7ff}');
+        ranger.insert('\u{e000}');
+        assert_eq!(ranger.range_count(), 1);
+        assert_eq!(ranger.len(), 2);
+        assert!(ranger.remove(&'\u{e000}'));
+        assert!(ranger.contains(&'\u{d7ff}'));
+
+        let mut ranger: Ranger<char> = "a-f,x,0-9,--/".parse().unwrap();
+        assert_eq!(ranger.to_string(), "--9,a-f,x");
+        assert_eq!(ranger.len(), 3 + 10 + 6 + 1);
+        assert_eq!(ranger.invert_within('a'..='z').to_string(), "g-w,y-z");
+        assert_eq!(ranger.remove_range('0'..'a'), 10);
+        assert_eq!(ranger.to_string(), "--/,a-f,x");
+    }
+
+    #[test]
+    fn ipv4_ranger() {
+        let mut ranger = Ranger::new();
+        for last in [9, 4, 6, 5, 8, 7] {
+            ranger.insert(Ipv4Addr::new(10, 0, 0, last));
+        }
+        assert_eq!(ranger.to_string(), "10.0.0.4-10.0.0.9");
+        ranger.insert(Ipv4Addr::new(10, 0, 0, 255));
+        ranger.insert(Ipv4Addr::new(10, 0, 1, 0));
+        ranger.insert(Ipv4Addr::new(10, 255, 255, 255));
+        ranger.insert(Ipv4Addr::new(11, 0, 0, 0));
+        assert_eq!(
+            ranger.to_string(),
+            "10.0.0.4-10.0.0.9,10.0.0.255-10.0.1.0,10.255.255.255-11.0.0.0"
+        );
+        assert_eq!(ranger.len(), 10);
+        let parsed: Ranger<Ipv4Addr> = ranger.to_string().parse().unwrap();
+        assert_eq!(parsed, ranger);
+        assert!("10.0.0.9-10.0.0.4".parse::<Ranger<Ipv4Addr>>().is_err());
+
+        let mut ranger: Ranger<Ipv4Addr> = "0.0.0.0,255.255.255.255".parse().unwrap();
+        assert!(ranger.contains(&Ipv4Addr::UNSPECIFIED));
+        assert!(ranger.contains(&Ipv4Addr::BROADCAST));
+        assert!(!ranger.contains(&Ipv4Addr::new(0, 0, 0, 1)));
+        assert!(!ranger.contains(&Ipv4Addr::new(255, 255, 255, 254)));
+        ranger.insert_range(Ipv4Addr::new(0, 0, 0, 1)..=Ipv4Addr::new(255, 255, 255, 254));
+        assert_eq!(ranger.to_string(), "0.0.0.0-255.255.255.255");
+        assert_eq!(ranger.len(), 1 << 32);
+        assert!(ranger.remove(&Ipv4Addr::BROADCAST));
+        assert_eq!(ranger.to_string(), "0.0.0.0-255.255.255.254");
+    }
+
+    #[test]
+    fn ipv6_ranger() {
+        let mut ranger: Ranger<Ipv6Addr> = "2001:db8::1-2001:db8::ff".parse().unwrap();
+        assert_eq!(ranger.len(), 255);
+        ranger.insert("2001:db8::100".parse().unwrap());
+        assert_eq!(ranger.to_string(), "2001:db8::1-2001:db8::100");
+
+        // crossing from one 16-bit group into the next
+        let mut ranger = Ranger::new();
+        ranger.insert("2001:db8::1:0".parse::<Ipv6Addr>().unwrap());
+        ranger.insert("2001:db8::ffff".parse().unwrap());
+        assert_eq!(ranger.to_string(), "2001:db8::ffff-2001:db8::1:0");
+        ranger.insert("::ffff:ffff".parse().unwrap());
+        ranger.insert("::1:0:0".parse().unwrap());
+        assert_eq!(ranger.range_count(), 2);
+        assert!(ranger.remove(&"2001:db8::ffff".parse().unwrap()));
+        assert_eq!(ranger.to_string(), "::ffff:ffff-::1:0:0,2001:db8::1:0");
+        let parsed: Ranger<Ipv6Addr> = ranger.to_string().parse().unwrap();
+        assert_eq!(parsed, ranger);
+
+        // wider than u64, and the full space saturates
+        let mut ranger = Ranger::new();
+        ranger.insert_range(Ipv6Addr::UNSPECIFIED..="::1:0:0:0:0".parse().unwrap());
+        assert_eq!(ranger.len(), (1 << 64) + 1);
+        ranger.insert_range(Ipv6Addr::UNSPECIFIED..=Ipv6Addr::from_bits(u128::MAX));
+        assert_eq!(ranger.len(), u128::MAX);
+        assert_eq!(ranger.first_n(3).to_string(), "::-::2");
+    }
+
+    #[test]
+    fn nonzero_ranger() {
+        let id = |v| NonZeroU32::new(v).unwrap();
+        assert!(NonZeroU32::adjacent(&id(1), &id(2)));
+        assert_eq!(NonZeroU32::MIN.predecessor(), None);
+        assert_eq!(NonZeroU32::MAX.successor(), None);
+        assert_eq!(
+            NonZeroU32::distance(&NonZeroU32::MIN, &NonZeroU32::MAX),
+            Some(u32::MAX as u128 - 1)
+        );
+        assert_eq!(id(5).backward(4), Some(id(1)));
+        assert_eq!(id(5).backward(5), None);
+
+        // hand out the lowest free ids, starting from one
+        let domain = NonZeroU32::MIN..=NonZeroU32::MAX;
+        let mut allocated = Ranger::new();
+        for _ in 0..3 {
+            let free = allocated.invert_within(domain.clone());
+            allocated.insert(free.0.first().unwrap().l);
+        }
+        assert_eq!(allocated.to_string(), "1-3");
+        allocated.remove(&id(2));
+        let free = allocated.invert_within(domain.clone());
+        assert_eq!(free.first_n(2).to_string(), "2,4");
+        assert_eq!(free.len(), u32::MAX as u128 - 2);
+
+        let parsed: Ranger<NonZeroU32> = "1-3,7".parse().unwrap();
+        assert_eq!(parsed.to_string(), "1-3,7");
+        assert_eq!(
+            "0-3".parse::<Ranger<NonZeroU32>>(),
+            Err(crate::ParseRangeError::Invalid(0))
+        );
+        assert_eq!(
+            "4,0".parse::<Ranger<NonZeroU32>>(),
+            Err(crate::ParseRangeError::Invalid(2))
+        );
+
+        // signed ones step straight from -1 to 1
+        let signed: Ranger<NonZeroI8> = "-3--1,1-2".parse().unwrap();
+        assert_eq!(signed.to_string(), "-3-2");
+        assert_eq!(signed.len(), 5);
+        let full = NonZeroI8::MIN..=NonZeroI8::MAX;
+        assert_eq!(Ranger::new().invert_within(full).len(), 255);
+        assert_eq!(NonZeroI8::MAX.successor(), None);
+        assert_eq!(NonZeroI8::MIN.predecessor(), None);
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn bigint_ranger() {
+        use num_bigint::{BigInt, BigUint};
+
+        let big = BigUint::from(u128::MAX) * 1000u32;
+        let mut ranger = Ranger::new();
+        ranger.insert(big.clone() + 2u32);
+        ranger.insert(big.clone());
+        assert_eq!(ranger.range_count(), 2);
+        ranger.insert(big.clone() + 1u32);
+        assert_eq!(ranger.range_count(), 1);
+        assert_eq!(ranger.len(), 3);
+        assert_eq!(
+            ranger.to_string(),
+            alloc::format!("{}-{}", big, big.clone() + 2u32)
+        );
+        assert!(ranger.remove(&(big.clone() + 1u32)));
+        assert_eq!(ranger.range_count(), 2);
+        let parsed: Ranger<BigUint> = ranger.to_string().parse().unwrap();
+        assert_eq!(parsed, ranger);
+        let mut ranger: Ranger<BigUint> = "0-5,10".parse().unwrap();
+        assert!(ranger.remove(&BigUint::from(0u32)));
+        assert_eq!(ranger.to_string(), "1-5,10");
+
+        let mut ranger: Ranger<BigInt> = "-3--1,1".parse().unwrap();
+        ranger.insert(BigInt::from(0));
+        assert_eq!(ranger.to_string(), "-3-1");
+        ranger.insert_range(BigInt::from(-10)..=BigInt::from(i128::MIN) * 4);
+        assert_eq!(ranger.to_string(), "-3-1");
+        let mut huge = Ranger::new();
+        huge.insert_range(BigInt::from(i128::MIN) * 4..=BigInt::from(i128::MAX) * 4);
+        assert_eq!(huge.len(), u128::MAX);
+        // ends too wide for digit counting are measured by writing them
+        assert_eq!(huge.display_len(), huge.to_string().len());
+    }
+}
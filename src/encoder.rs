@@ -0,0 +1,233 @@
+use crate::{Discrete, Ranger};
+use core::{fmt, ops::RangeInclusive};
+
+/// What a [`RangeEncoder`] does with a value that arrives too late to join
+/// the runs it has already emitted
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
+pub enum LatePolicy {
+    /// Discards the value. It is still counted by
+    /// [`late_count`](RangeEncoder::late_count).
+    #[default]
+    Drop,
+    /// Emits the value at once as a run of its own, out of order with the
+    /// runs around it
+    Emit,
+}
+
+/// Coalesces a stream of mostly ascending values into runs, handing each to
+/// a callback once no value still to come can extend it.
+///
+/// Values may arrive out of order by up to `window` steps: a value is
+/// accepted while it lies within `window` steps below the highest value seen
+/// so far, and a run is emitted once the value after its end falls outside
+/// that window. Only runs still open are held, so memory is bounded by the
+/// window and the number of open runs rather than the length of the stream.
+/// Runs are emitted in ascending order, except as a [`LatePolicy::Emit`]
+/// puts late values among them.
+///
+/// A value is late if it lies further below the highest value seen than the
+/// window allows, or at or below the end of a run already emitted, which
+/// includes repeats of emitted values.
+///
+/// ```
+/// use ranger::{LatePolicy, RangeEncoder};
+///
+/// let mut runs = Vec::new();
+/// let mut encoder = RangeEncoder::new(2, LatePolicy::Drop, |r| runs.push(r));
+/// for seq in [1, 3, 2, 4, 8, 7, 9, 20, 5] {
+///     encoder.push(seq);
+/// }
+/// assert_eq!(encoder.late_count(), 1);
+/// encoder.finish();
+/// assert_eq!(runs, [1..=4, 7..=9, 20..=20]);
+/// ```
+pub struct RangeEncoder<T, F> {
+    window: u128,
+    policy: LatePolicy,
+    emit: F,
+    /// The runs not yet emitted
+    open: Ranger<T>,
+    /// The highest value seen
+    max: Option<T>,
+    /// The end of the last run emitted in order
+    frontier: Option<T>,
+    late: u64,
+}
+
+impl<T: Discrete, F: FnMut(RangeInclusive<T>)> RangeEncoder<T, F> {
+    /// Returns an encoder tolerating values up to `window` steps out of
+    /// order, treating later ones as `policy` says and handing runs to `emit`
+    pub fn new(window: u128, policy: LatePolicy, emit: F) -> Self {
+        RangeEncoder {
+            window,
+            policy,
+            emit,
+            open: Ranger::new(),
+            max: None,
+            frontier: None,
+            late: 0,
+        }
+    }
+    /// Adds `value` to the stream, emitting any runs it closes
+    pub fn push(&mut self, value: T) {
+        if self.is_late(&value) {
+            self.late = self.late.saturating_add(1);
+            if self.policy == LatePolicy::Emit {
+                (self.emit)(value.clone()..=value);
+            }
+            return;
+        }
+        if self.max.as_ref().is_none_or(|max| &value > max) {
+            self.max = Some(value.clone());
+        }
+        self.open.insert(value);
+        self.emit_closed();
+    }
+    /// Emits every run still open, even those later values could have
+    /// extended. Values at or below the end of the last of them are late
+    /// from then on.
+    pub fn flush(&mut self) {
+        while let Some(u) = self.open.0.pop_first() {
+            let high = u.high().clone();
+            self.frontier = Some(high.clone());
+            (self.emit)(u.l..=high);
+        }
+    }
+    /// Emits every run still open and drops the encoder
+    pub fn finish(mut self) {
+        self.flush();
+    }
+    /// Returns the number of late values pushed so far, whether dropped or
+    /// emitted
+    pub fn late_count(&self) -> u64 {
+        self.late
+    }
+    /// Returns the number of runs held open
+    pub fn open_runs(&self) -> usize {
+        self.open.range_count()
+    }
+    fn is_late(&self, value: &T) -> bool {
+        if self.frontier.as_ref().is_some_and(|f| value <= f) {
+            return true;
+        }
+        match &self.max {
+            Some(max) if value < max => T::distance(value, max).is_none_or(|d| d > self.window),
+            _ => false,
+        }
+    }
+    /// Emits the runs that no value within the window can extend any more
+    fn emit_closed(&mut self) {
+        let Some(max) = &self.max else {
+            return;
+        };
+        // the value after a run's end is late once the end is this far back
+        let reach = self.window.saturating_add(1);
+        while let Some(u) = self.open.0.first() {
+            if T::distance(u.high(), max).is_some_and(|d| d <= reach) {
+                break;
+            }
+            let u = self.open.0.pop_first().expect("a run was found");
+            let high = u.high().clone();
+            self.frontier = Some(high.clone());
+            (self.emit)(u.l..=high);
+        }
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for RangeEncoder<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RangeEncoder")
+            .field("window", &self.window)
+            .field("policy", &self.policy)
+            .field("open", &self.open)
+            .field("late", &self.late)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use rand::{thread_rng, Rng};
+
+    fn encode(window: u128, policy: LatePolicy, values: &[u32]) -> (Vec<RangeInclusive<u32>>, u64) {
+        let mut runs = Vec::new();
+        let mut encoder = RangeEncoder::new(window, policy, |r| runs.push(r));
+        for &v in values {
+            encoder.push(v);
+        }
+        let late = encoder.late_count();
+        encoder.finish();
+        (runs, late)
+    }
+
+    #[test]
+    fn shuffled_within_window_matches_batch() {
+        let mut rng = thread_rng();
+        for window in [0, 1, 5, 40] {
+            let mut values = Vec::new();
+            let mut v = 0u32;
+            for _ in 0..2000 {
+                v += rng.gen_range(0..4);
+                values.push(v);
+            }
+            // delaying each value by at most the window keeps every value
+            // within the window of the highest seen before it
+            let mut keyed: Vec<_> = values
+                .iter()
+                .map(|&v| (v as u128 + rng.gen_range(0..=window), v))
+                .collect();
+            keyed.sort();
+            let shuffled: Vec<_> = keyed.into_iter().map(|(_, v)| v).collect();
+            let (runs, late) = encode(window, LatePolicy::Drop, &shuffled);
+            assert_eq!(late, 0);
+            let mut batch = Ranger::new();
+            for v in values {
+                batch.insert(v);
+            }
+            assert!(runs.into_iter().eq(batch.ranges().map(Into::into)));
+        }
+    }
+
+    #[test]
+    fn emits_runs_once_closed() {
+        let mut runs = Vec::new();
+        let mut encoder = RangeEncoder::new(3, LatePolicy::Drop, |r| runs.push(r));
+        for v in [10u8, 11, 12, 14, 16] {
+            encoder.push(v);
+        }
+        // 13 could still arrive
+        assert_eq!(encoder.open_runs(), 3);
+        encoder.push(17);
+        assert_eq!(encoder.open_runs(), 2);
+        encoder.push(u8::MAX);
+        assert_eq!(encoder.open_runs(), 1);
+        encoder.flush();
+        assert_eq!(encoder.open_runs(), 0);
+        drop(encoder);
+        assert_eq!(runs, [10..=12, 14..=14, 16..=17, 255..=255]);
+    }
+
+    #[test]
+    fn late_values_follow_the_policy() {
+        let values = [5, 6, 7, 20, 16, 15, 3, 21, 7];
+        let (runs, late) = encode(5, LatePolicy::Drop, &values);
+        assert_eq!(runs, [5..=7, 15..=16, 20..=21]);
+        assert_eq!(late, 2);
+        let (runs, late) = encode(5, LatePolicy::Emit, &values);
+        assert_eq!(runs, [5..=7, 3..=3, 7..=7, 15..=16, 20..=21]);
+        assert_eq!(late, 2);
+        // values below a flushed run are late too
+        let mut runs = Vec::new();
+        let mut encoder = RangeEncoder::new(100, LatePolicy::Emit, |r| runs.push(r));
+        encoder.push(-4i64);
+        encoder.push(-2);
+        encoder.flush();
+        encoder.push(-3);
+        encoder.push(-1);
+        assert_eq!(encoder.late_count(), 1);
+        encoder.finish();
+        assert_eq!(runs, [-4..=-4, -2..=-2, -3..=-3, -1..=-1]);
+    }
+}
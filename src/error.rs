@@ -0,0 +1,241 @@
+use core::fmt;
+
+/// An error returned when an operation would move a value past the bounds of
+/// its type.
+///
+/// Operations returning this error leave the set untouched.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct OverflowError;
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Value out of range for its type")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OverflowError {}
+
+/// An error returned when an insertion would need more runs than the set was
+/// configured to hold.
+///
+/// Operations returning this error leave the set untouched.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct CapacityExceeded;
+
+impl fmt::Display for CapacityExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Insertion would exceed the maximum number of ranges")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityExceeded {}
+
+/// An error returned when decoding the compact binary form of a set fails.
+///
+/// Each member other than `Truncated` carries the byte offset of the
+/// offending varint.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum DecodeError {
+    /// The input ended before every run was read
+    Truncated,
+    /// A varint is longer than any 128-bit value needs
+    Overlong(usize),
+    /// A decoded value does not fit in the element type
+    OutOfRange(usize),
+    /// A run touches the previous one, which encoding never produces
+    Unordered(usize),
+    /// Bytes remain after the last run
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => f.write_str("Input ended before the last range"),
+            DecodeError::Overlong(pos) => write!(f, "Overlong varint at byte {}", pos),
+            DecodeError::OutOfRange(pos) => {
+                write!(f, "Value out of range for its type at byte {}", pos)
+            }
+            DecodeError::Unordered(pos) => {
+                write!(f, "Range does not follow the previous one at byte {}", pos)
+            }
+            DecodeError::TrailingBytes(pos) => write!(f, "Trailing bytes at byte {}", pos),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// An error returned when a span over a circular space covers more than half
+/// of it, so that serial number ordering can no longer tell its ends apart.
+///
+/// Operations returning this error leave the set untouched.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct AmbiguousSpan;
+
+impl fmt::Display for AmbiguousSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Span covers more than half of the circular space")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AmbiguousSpan {}
+
+/// An error returned when building a set from input that should be ascending
+/// but isn't. Carries the position of the first value below its predecessor.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct NotSorted(pub usize);
+
+impl fmt::Display for NotSorted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Value below its predecessor at position {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NotSorted {}
+
+/// An error returned when a stored value has no place in the target
+/// representation, such as a bit beyond the width of a mask.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Value out of range for the representation")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OutOfRange {}
+
+/// An error returned when building a set from `(low, high)` pairs, one of
+/// which has its low end above its high end. Carries the index of that pair.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct ReversedPair(pub usize);
+
+impl fmt::Display for ReversedPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Pair low end above its high end at index {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReversedPair {}
+
+/// An error returned when a set of indices reaches past the end of the slice
+/// it selects from. Carries the lowest such index.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct IndexOutOfBounds(pub usize);
+
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Index {} out of bounds", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IndexOutOfBounds {}
+
+/// An error returned when a set's runs break the invariants every operation
+/// relies on. Each member carries the index of the offending run, counting
+/// from the lowest; the pair-wise ones concern that run and the one before
+/// it.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum InvariantViolation {
+    /// The run's low end lies above its high end
+    Reversed(usize),
+    /// The run starts at or below where the previous one ends
+    Overlapping(usize),
+    /// The run starts right after the previous one ends, so the two should
+    /// have been joined
+    Adjacent(usize),
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvariantViolation::Reversed(i) => {
+                write!(f, "Run {} has its low end above its high end", i)
+            }
+            InvariantViolation::Overlapping(i) => {
+                write!(f, "Run {} overlaps run {}", i, i - 1)
+            }
+            InvariantViolation::Adjacent(i) => {
+                write!(f, "Run {} touches run {} without being joined", i, i - 1)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantViolation {}
+
+/// An error returned when a batch of changes given to
+/// [`Ranger::apply_ops`](crate::Ranger::apply_ops) can't be applied, which
+/// leaves the set untouched.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum ApplyError {
+    /// A range has its low end above its high end
+    ReversedRange,
+    /// An insertion would need more runs than the set was configured to hold
+    CapacityExceeded,
+}
+
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApplyError::ReversedRange => f.write_str("Range low end above its high end"),
+            ApplyError::CapacityExceeded => {
+                f.write_str("Insertion would exceed the maximum number of ranges")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ApplyError {}
+
+/// An error returned when parsing an [`AlphaIndex`](crate::AlphaIndex) from
+/// anything but one or more ASCII letters naming a column that fits in a
+/// `u32`.
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct ParseAlphaError;
+
+impl fmt::Display for ParseAlphaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Invalid or out of range column letters")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseAlphaError {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{NotSorted, Ranger};
+    use std::{boxed::Box, error::Error, string::ToString};
+
+    fn parse_and_extend(list: &str, extra: &[u8]) -> Result<Ranger<u8>, Box<dyn Error>> {
+        let mut ranger: Ranger<u8> = list.parse()?;
+        for s in Ranger::from_sorted_iter(extra.iter().copied())?.ranges() {
+            ranger.insert_range(s.into());
+        }
+        Ok(ranger)
+    }
+
+    #[test]
+    fn errors_box_into_dyn_error() {
+        let err = parse_and_extend("1,,3", &[]).unwrap_err();
+        assert_eq!(err.to_string(), "Empty range at position 2");
+        assert!(err.source().is_none());
+        let err = parse_and_extend("1-3", &[9, 5]).unwrap_err();
+        assert_eq!(err.downcast_ref::<NotSorted>(), Some(&NotSorted(1)));
+        let ranger = parse_and_extend("1-3", &[4, 9]).unwrap();
+        assert_eq!(ranger.to_string(), "1-4,9");
+    }
+}
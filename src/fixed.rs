@@ -0,0 +1,251 @@
+use crate::{
+    discrete::{added_len, run_len, step_down, step_up},
+    write_runs, CapacityExceeded, Discrete, FormatOptions, Span,
+};
+use core::{
+    fmt::{self, Display, Write},
+    ops::RangeInclusive,
+};
+
+/// A set of at most `N` runs held inline, for targets with neither a heap
+/// nor `alloc`.
+///
+/// Insertions fail with [`CapacityExceeded`] only when they would need an
+/// `N + 1`-th run, so one that merges into or between existing runs always
+/// succeeds. Removal is the reverse: taking a value from the middle of a run
+/// splits it in two, which can fail on a full set even though the set is
+/// shrinking, while removing from either end of a run never does.
+#[derive(Clone, Debug)]
+pub struct FixedRanger<T, const N: usize> {
+    runs: [Option<(T, T)>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> Default for FixedRanger<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> FixedRanger<T, N> {
+    pub const fn new() -> Self {
+        FixedRanger {
+            runs: [const { None }; N],
+            len: 0,
+        }
+    }
+    /// Returns the number of runs
+    pub fn range_count(&self) -> usize {
+        self.len
+    }
+    /// Returns the most runs the set can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+    /// Returns whether no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    fn run(&self, i: usize) -> &(T, T) {
+        self.runs[i].as_ref().unwrap()
+    }
+    fn pairs(&self) -> impl Iterator<Item = &(T, T)> + '_ {
+        self.runs[..self.len].iter().map(|r| r.as_ref().unwrap())
+    }
+    /// Puts `run` at index `i`, moving the runs from there on up one slot
+    fn insert_at(&mut self, i: usize, run: (T, T)) -> Result<(), CapacityExceeded> {
+        if self.len == N {
+            return Err(CapacityExceeded);
+        }
+        self.runs[self.len] = Some(run);
+        self.runs[i..=self.len].rotate_right(1);
+        self.len += 1;
+        Ok(())
+    }
+    /// Drops the runs with indices in `from..to`, moving those above down
+    fn remove_between(&mut self, from: usize, to: usize) {
+        self.runs[from..self.len].rotate_left(to - from);
+        for slot in &mut self.runs[self.len - (to - from)..self.len] {
+            *slot = None;
+        }
+        self.len -= to - from;
+    }
+}
+
+impl<T: Discrete, const N: usize> FixedRanger<T, N> {
+    /// Index of the first run ending at or above `value`
+    fn locate(&self, value: &T) -> usize {
+        self.runs[..self.len].partition_point(|r| &r.as_ref().unwrap().1 < value)
+    }
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.locate(value);
+        i < self.len && &self.run(i).0 <= value
+    }
+    /// Inserts `value`, returning whether it was not already present, or
+    /// fails if it would need a new run on a full set
+    pub fn try_insert(&mut self, value: T) -> Result<bool, CapacityExceeded> {
+        self.try_insert_range(value.clone()..=value)
+            .map(|added| added > 0)
+    }
+    /// Inserts every value in `range`, merging with any runs it overlaps or
+    /// touches. Returns the number of values that were not already present,
+    /// or fails if the range would need a new run on a full set, leaving the
+    /// set untouched.
+    pub fn try_insert_range(&mut self, range: RangeInclusive<T>) -> Result<u128, CapacityExceeded> {
+        let (l, h) = range.into_inner();
+        if l > h {
+            return Ok(0);
+        }
+        let runs = &self.runs[..self.len];
+        let start = runs.partition_point(|r| {
+            let rh = &r.as_ref().unwrap().1;
+            rh < &l && !T::adjacent(rh, &l)
+        });
+        let end = runs.partition_point(|r| {
+            let rl = &r.as_ref().unwrap().0;
+            rl <= &h || T::adjacent(&h, rl)
+        });
+        if start == end {
+            let added = run_len(&l, &h);
+            self.insert_at(start, (l, h))?;
+            return Ok(added);
+        }
+        let (first, last) = (self.run(start), self.run(end - 1));
+        if first.0 <= l && first.1 >= h {
+            return Ok(0);
+        }
+        let absorbed = self
+            .pairs()
+            .skip(start)
+            .take(end - start)
+            .fold(0u128, |acc, (rl, rh)| acc.saturating_add(run_len(rl, rh)));
+        let l = core::cmp::min(l, first.0.clone());
+        let h = core::cmp::max(h, last.1.clone());
+        let added = added_len(&l, &h, absorbed);
+        self.runs[start] = Some((l, h));
+        self.remove_between(start + 1, end);
+        Ok(added)
+    }
+    /// Removes `value`, returning whether it was present, or fails if that
+    /// would split a run on a full set, leaving the set untouched
+    pub fn remove(&mut self, value: &T) -> Result<bool, CapacityExceeded> {
+        let i = self.locate(value);
+        if i == self.len || &self.run(i).0 > value {
+            return Ok(false);
+        }
+        let (l, h) = self.runs[i].take().unwrap();
+        match (&l == value, &h == value) {
+            (true, true) => {
+                self.runs[i] = Some((l, h));
+                self.remove_between(i, i + 1);
+            }
+            (true, false) => self.runs[i] = Some((step_up(value), h)),
+            (false, true) => self.runs[i] = Some((l, step_down(value))),
+            (false, false) if self.len == N => {
+                self.runs[i] = Some((l, h));
+                return Err(CapacityExceeded);
+            }
+            (false, false) => {
+                self.runs[i] = Some((l, step_down(value)));
+                self.insert_at(i + 1, (step_up(value), h))?;
+            }
+        }
+        Ok(true)
+    }
+    /// Returns the number of values stored, saturating at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        self.pairs()
+            .fold(0u128, |acc, (l, h)| acc.saturating_add(run_len(l, h)))
+    }
+    /// Iterates over the runs in ascending order
+    pub fn ranges(&self) -> impl Iterator<Item = Span<T>> + '_ {
+        self.pairs()
+            .map(|(l, h)| Span::new_unchecked(l.clone(), h.clone()))
+    }
+}
+
+impl<T: Eq + Display, const N: usize> Display for FixedRanger<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_runs(f, self.pairs().map(|(l, h)| (l, h)), &FormatOptions::new())
+    }
+}
+
+/// Writes into a byte slice, failing once it is full
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    written: usize,
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.written + s.len();
+        let dest = self.buf.get_mut(self.written..end).ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.written = end;
+        Ok(())
+    }
+}
+
+impl<T: Eq + Display, const N: usize> FixedRanger<T, N> {
+    /// Writes the `Display` form into `buf` without allocating, returning the
+    /// written text, or fails if `buf` is too short
+    pub fn write_to_slice<'a>(&self, buf: &'a mut [u8]) -> Result<&'a str, fmt::Error> {
+        let mut writer = SliceWriter { buf, written: 0 };
+        write!(writer, "{}", self)?;
+        let SliceWriter { buf, written } = writer;
+        Ok(core::str::from_utf8(&buf[..written]).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity() {
+        let mut fixed = FixedRanger::<u8, 4>::new();
+        for v in [0, 2, 4, 6] {
+            assert_eq!(fixed.try_insert(v), Ok(true));
+        }
+        assert_eq!(fixed.range_count(), fixed.capacity());
+        assert_eq!(fixed.try_insert(8), Err(CapacityExceeded));
+        assert_eq!(fixed.try_insert_range(10..=12), Err(CapacityExceeded));
+        assert_eq!(fixed.try_insert(4), Ok(false));
+        let mut buf = [0; 32];
+        assert_eq!(fixed.write_to_slice(&mut buf), Ok("0,2,4,6"));
+
+        // merging needs no slot, and frees one
+        assert_eq!(fixed.try_insert(1), Ok(true));
+        assert_eq!(fixed.try_insert_range(3..=7), Ok(3));
+        assert_eq!(fixed.write_to_slice(&mut buf), Ok("0-7"));
+        assert_eq!(fixed.range_count(), 1);
+        assert_eq!(fixed.len(), 8);
+        assert!(fixed.contains(&5) && !fixed.contains(&8));
+        assert_eq!(fixed.write_to_slice(&mut [0; 2]), Err(fmt::Error));
+    }
+
+    #[test]
+    fn splitting() {
+        let mut fixed = FixedRanger::<i16, 2>::new();
+        fixed.try_insert_range(0..=9).unwrap();
+        fixed.try_insert_range(20..=29).unwrap();
+        assert_eq!(fixed.remove(&5), Err(CapacityExceeded));
+        assert_eq!(fixed.remove(&0), Ok(true));
+        assert_eq!(fixed.remove(&29), Ok(true));
+        assert_eq!(fixed.remove(&15), Ok(false));
+        let mut buf = [0; 16];
+        assert_eq!(fixed.write_to_slice(&mut buf), Ok("1-9,20-28"));
+
+        let mut roomy = FixedRanger::<i16, 3>::new();
+        roomy.try_insert_range(-5..=5).unwrap();
+        assert_eq!(roomy.remove(&0), Ok(true));
+        assert_eq!(roomy.write_to_slice(&mut buf), Ok("-5--1,1-5"));
+        assert_eq!(roomy.remove(&-5), Ok(true));
+        assert_eq!(roomy.try_insert(7), Ok(true));
+        assert_eq!(roomy.remove(&7), Ok(true));
+        let ranges = [(-4, -1), (1, 5)];
+        assert!(roomy.ranges().map(<(i16, i16)>::from).eq(ranges));
+        assert_eq!(roomy.try_insert(0), Ok(true));
+        assert_eq!(roomy.write_to_slice(&mut buf), Ok("-4-5"));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{discrete::run_len, write_runs, Discrete, FormatOptions, Ranger, Span, Unit};
+use alloc::boxed::Box;
+use core::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
+
+/// An immutable snapshot of a [`Ranger`], holding its runs as one boxed
+/// slice of endpoint pairs.
+///
+/// It takes exactly `2 * range_count() * size_of::<T>()` bytes besides the
+/// box itself, with no per-node overhead, and answers queries by binary
+/// search. Made by [`Ranger::freeze`] and turned back by
+/// [`thaw`](FrozenRanger::thaw).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FrozenRanger<T>(Box<[(T, T)]>);
+
+impl<T: Clone> Ranger<T> {
+    /// Converts the set into an immutable, compact snapshot
+    pub fn freeze(self) -> FrozenRanger<T> {
+        FrozenRanger(
+            self.0
+                .into_iter()
+                .map(|u| {
+                    let h = u.high().clone();
+                    (u.l, h)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T> FrozenRanger<T> {
+    /// Returns the number of runs
+    pub fn range_count(&self) -> usize {
+        self.0.len()
+    }
+    /// Returns the heap bytes the runs take up, which for a snapshot is
+    /// exact, elements' own heap data aside
+    pub fn memory_usage(&self) -> usize {
+        size_of_val(&*self.0)
+    }
+    /// Returns whether no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<T: Discrete> FrozenRanger<T> {
+    /// Converts the snapshot back into a set that can be changed
+    pub fn thaw(self) -> Ranger<T> {
+        let mut ranger = Ranger::new();
+        ranger.0 = self
+            .0
+            .into_vec()
+            .into_iter()
+            .map(|(l, h)| Unit::new(l, h))
+            .collect();
+        ranger
+    }
+    /// Index of the first run ending at or above `value`
+    fn locate(&self, value: &T) -> usize {
+        self.0.partition_point(|(_, h)| h < value)
+    }
+    pub fn contains(&self, value: &T) -> bool {
+        self.0
+            .get(self.locate(value))
+            .is_some_and(|(l, _)| l <= value)
+    }
+    /// Returns whether every value in `range` is stored. An empty range is
+    /// trivially contained.
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        self.0
+            .get(self.locate(range.start()))
+            .is_some_and(|(l, h)| l <= range.start() && h >= range.end())
+    }
+    /// Iterates over the runs in ascending order
+    pub fn ranges(&self) -> impl Iterator<Item = Span<T>> + '_ {
+        self.0
+            .iter()
+            .map(|(l, h)| Span::new_unchecked(l.clone(), h.clone()))
+    }
+    /// Returns the run at `index` counting from the lowest, or `None` past
+    /// the last, in constant time
+    pub fn nth_range(&self, index: usize) -> Option<RangeInclusive<T>> {
+        self.0.get(index).map(|(l, h)| l.clone()..=h.clone())
+    }
+    /// Iterates over up to `len` runs in ascending order, starting from the
+    /// one at index `start`
+    pub fn ranges_page(
+        &self,
+        start: usize,
+        len: usize,
+    ) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let start = start.min(self.0.len());
+        let end = start.saturating_add(len).min(self.0.len());
+        self.0[start..end]
+            .iter()
+            .map(|(l, h)| l.clone()..=h.clone())
+    }
+    /// Returns the number of values stored, saturating at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        Self::len_of(&self.0)
+    }
+    /// Returns the number of stored values below `value`, saturating at
+    /// `u128::MAX`. This walks every run below it.
+    pub fn rank(&self, value: &T) -> u128 {
+        let i = self.locate(value);
+        let below = Self::len_of(&self.0[..i]);
+        match self.0.get(i) {
+            Some((l, _)) if l < value => {
+                below.saturating_add(T::distance(l, value).unwrap_or(u128::MAX))
+            }
+            _ => below,
+        }
+    }
+    /// Number of values in `runs`, saturating at `u128::MAX`
+    fn len_of(runs: &[(T, T)]) -> u128 {
+        runs.iter()
+            .fold(0u128, |acc, (l, h)| acc.saturating_add(run_len(l, h)))
+    }
+}
+
+impl<T: Eq + Display> Display for FrozenRanger<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_runs(f, self.0.iter().map(|(l, h)| (l, h)), &FormatOptions::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+    use rand::{seq::SliceRandom, thread_rng};
+
+    #[test]
+    fn agrees_with_ranger() {
+        let mut rng = thread_rng();
+        let mut values: Vec<i16> = (-300..300).collect();
+        for n in [0, 1, 50, 300, 600] {
+            values.shuffle(&mut rng);
+            let mut ranger = Ranger::new();
+            for v in &values[..n] {
+                ranger.insert(*v);
+            }
+            let frozen = ranger.clone().freeze();
+            assert_eq!(frozen.to_string(), ranger.to_string());
+            assert_eq!(frozen.len(), ranger.len());
+            assert_eq!(frozen.range_count(), ranger.range_count());
+            assert_eq!(frozen.is_empty(), ranger.is_empty());
+            assert_eq!(frozen.memory_usage(), frozen.range_count() * 4);
+            let mut rank = 0;
+            for v in -310..310 {
+                assert_eq!(frozen.contains(&v), ranger.contains(&v));
+                assert_eq!(frozen.rank(&v), rank);
+                rank += ranger.contains(&v) as u128;
+                let r = v..=v + 3;
+                assert_eq!(frozen.contains_range(&r), ranger.contains_range(&r));
+            }
+            let mut joined = Ranger::new();
+            for r in frozen.ranges() {
+                joined.insert_range(r.into());
+            }
+            assert_eq!(joined, ranger);
+            for i in 0..=ranger.range_count() {
+                assert_eq!(frozen.nth_range(i), ranger.nth_range(i));
+                let page = frozen.ranges_page(i, 7);
+                assert!(page.eq(ranger.ranges_page(i, 7)));
+            }
+            assert_eq!(frozen.ranges_page(usize::MAX, usize::MAX).count(), 0);
+            assert_eq!(frozen.thaw(), ranger);
+        }
+        let full = Ranger::<u8>::from_sorted_iter(0..=255).unwrap().freeze();
+        assert_eq!(full.len(), 256);
+        assert_eq!(full.rank(&255), 255);
+        assert_eq!(core::mem::size_of_val(&*full.0), 2);
+        assert_eq!(full.memory_usage(), 2);
+    }
+}
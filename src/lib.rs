@@ -1,241 +1,3844 @@
 #![no_std]
+mod alpha;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+#[cfg(feature = "bincode")]
+mod bincode_impl;
+#[cfg(feature = "alloc")]
+mod bitmask;
+#[cfg(feature = "borsh")]
+mod borsh_impl;
+mod byte;
+#[cfg(feature = "alloc")]
+mod codec;
+#[cfg(feature = "critical-section")]
+mod critical;
+#[cfg(feature = "alloc")]
+mod cursor;
+mod discrete;
+#[cfg(feature = "alloc")]
+mod encoder;
+mod error;
+mod fixed;
+#[cfg(feature = "alloc")]
+mod frozen;
+#[cfg(all(feature = "libc", any(target_os = "linux", target_os = "android")))]
+mod libc_impl;
+#[cfg(feature = "alloc")]
+mod macros;
+#[cfg(feature = "alloc")]
+mod map;
+#[cfg(feature = "alloc")]
+mod multiset;
+#[cfg(feature = "alloc")]
+mod ops;
+#[cfg(feature = "alloc")]
+#[allow(dead_code)]
 mod parse;
+#[cfg(feature = "alloc")]
+mod pattern;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impl;
+#[cfg(feature = "rand")]
+mod rand_impl;
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "roaring")]
+mod roaring_impl;
+#[cfg(feature = "alloc")]
+mod scale;
+#[cfg(feature = "alloc")]
+mod select;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+mod shared;
+#[cfg(feature = "alloc")]
+mod small;
+mod span;
+#[cfg(feature = "alloc")]
+mod sql;
+#[cfg(feature = "alloc")]
+mod stats;
+#[cfg(feature = "alloc")]
+mod stepped;
+#[cfg(feature = "alloc")]
+mod sysfs;
+#[cfg(feature = "test-util")]
+mod test_util;
+#[cfg(feature = "time")]
+mod time_impl;
+#[cfg(feature = "alloc")]
+mod watched;
+#[cfg(feature = "alloc")]
+mod wrapping;
+#[cfg(feature = "alloc")]
 extern crate alloc;
-use alloc::collections::BTreeSet;
+#[cfg(feature = "std")]
+extern crate std;
+#[cfg(feature = "alloc")]
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+/// The allocator traits taken by [`SmallRanger::new_in`]
+#[cfg(feature = "alloc")]
+pub use allocator_api2;
+pub use alpha::AlphaIndex;
+pub use byte::ByteRanger;
+use core::fmt::{self, Display};
+#[cfg(feature = "alloc")]
 use core::{
+    borrow::Borrow,
     cmp::Ordering,
-    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    ops::{Bound, RangeBounds, RangeInclusive},
+};
+#[cfg(feature = "critical-section")]
+pub use critical::CriticalRanger;
+#[cfg(feature = "alloc")]
+pub use cursor::RangerCursor;
+pub use discrete::Discrete;
+#[cfg(feature = "alloc")]
+use discrete::{added_len, run_len, step_down, step_up};
+#[cfg(feature = "alloc")]
+pub use encoder::{LatePolicy, RangeEncoder};
+pub use error::{
+    AmbiguousSpan, ApplyError, CapacityExceeded, DecodeError, IndexOutOfBounds, InvariantViolation,
+    NotSorted, OutOfRange, OverflowError, ParseAlphaError, ReversedPair,
 };
-use num_traits::{Num, SaturatingSub};
+pub use fixed::FixedRanger;
+#[cfg(feature = "alloc")]
+pub use frozen::FrozenRanger;
+#[cfg(all(feature = "libc", any(target_os = "linux", target_os = "android")))]
+pub use libc_impl::parse_cpulist;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub use macros::RangerElement;
+#[cfg(feature = "alloc")]
+pub use map::{MapDisplayWith, RangerMap};
+#[cfg(feature = "alloc")]
+pub use multiset::RangerMultiset;
+#[cfg(feature = "alloc")]
+use num_traits::{Bounded, CheckedAdd, CheckedSub, Num, ToPrimitive, Zero};
+#[cfg(feature = "alloc")]
+pub use ops::{ApplyReport, RangerOp};
+#[cfg(feature = "alloc")]
+pub use parse::ParseRangeError;
+/// Derives [`Discrete`](trait@Discrete) for a newtype by delegating to its
+/// field
+#[cfg(feature = "derive")]
+pub use ranger_derive::Discrete;
+#[cfg(feature = "rkyv")]
+pub use rkyv_impl::ArchivedRanger;
+#[cfg(feature = "alloc")]
+pub use scale::Rounding;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub use shared::{SharedRanger, SharedSnapshot};
+#[cfg(feature = "alloc")]
+pub use small::SmallRanger;
+pub use span::Span;
+#[cfg(feature = "alloc")]
+pub use sql::SqlOptions;
+#[cfg(feature = "alloc")]
+pub use stats::{Coverage, Histogram, RangerSummary};
+#[cfg(feature = "alloc")]
+pub use stepped::DisplayStepped;
+#[cfg(feature = "test-util")]
+pub use test_util::{assert_ranger_eq, explain_difference};
+#[cfg(feature = "alloc")]
+pub use watched::{RangerWatched, RunEvent};
+#[cfg(feature = "alloc")]
+pub use wrapping::WrappingRanger;
 
+#[cfg(feature = "alloc")]
 #[derive(Clone, Eq, PartialEq, Debug)]
 struct Unit<T> {
     l: T,
     h: Option<T>,
 }
 
-enum Merger<T> {
-    Merged,
-    NotMerged(T),
+#[cfg(feature = "alloc")]
+impl<T> Unit<T> {
+    fn high(&self) -> &T {
+        self.h.as_ref().unwrap_or(&self.l)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Clone> Unit<T> {
+    /// Copies the unit out as a public span
+    fn span(&self) -> Span<T> {
+        Span::new_unchecked(self.l.clone(), self.high().clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> Unit<T> {
+    /// Whether `v` lies within the unit
+    fn contains(&self, v: &T) -> bool {
+        &self.l <= v && v <= self.high()
+    }
+    /// Whether the unit shares any value with `low..=high`
+    fn overlaps(&self, low: &T, high: &T) -> bool {
+        &self.l <= high && low <= self.high()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq> Unit<T> {
+    fn new(l: T, h: T) -> Self {
+        if l == h {
+            Unit { l, h: None }
+        } else {
+            Unit { l, h: Some(h) }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Discrete> Unit<T> {
+    /// Lazily yields every value in the unit in ascending order
+    fn values(&self) -> impl Iterator<Item = T> + '_ {
+        let h = self.high();
+        core::iter::successors(Some(self.l.clone()), move |v| {
+            if v < h {
+                v.successor()
+            } else {
+                None
+            }
+        })
+    }
+    /// Splits the unit around `v`, which must lie within it, returning the
+    /// pieces left below and above it
+    fn split_around(self, v: &T) -> (Option<Self>, Option<Self>) {
+        let Unit { l, h } = self;
+        let h = h.unwrap_or_else(|| l.clone());
+        let above = (v < &h).then(|| Unit::new(step_up(v), h));
+        let below = (&l < v).then(|| Unit::new(l, step_down(v)));
+        (below, above)
+    }
+    /// Number of values covered by the unit, saturating at `u128::MAX`
+    fn len(&self) -> u128 {
+        run_len(&self.l, self.high())
+    }
 }
 
-impl<T: Num + Ord + SaturatingSub> Unit<T> {
-    fn merged(&mut self, other: Self) -> Merger<Unit<T>> {
-        if other
-            .l
-            .saturating_sub(self.h.as_ref().unwrap_or(&self.l))
-            .is_one()
-        {
-            self.h = other.h.or(Some(other.l));
-            Merger::Merged
+#[cfg(feature = "alloc")]
+/// Collects the first `n` values of `runs`, walking them in the order given
+/// and cutting the last run needed. Runs must be given in descending order
+/// when `from_top` is set, and are then cut from their upper end.
+fn take_values<T, I>(runs: I, n: u128, from_top: bool) -> BTreeSet<Unit<T>>
+where
+    T: Discrete,
+    I: Iterator<Item = (T, T)>,
+{
+    let mut taken = BTreeSet::new();
+    let mut remaining = n;
+    for (l, h) in runs {
+        if remaining == 0 {
+            break;
+        }
+        // compare widths rather than lengths, which overflow on the full domain
+        let w = T::distance(&l, &h);
+        if let Some(w) = w.filter(|w| *w < remaining) {
+            remaining -= w + 1;
+            taken.insert(Unit::new(l, h));
+        } else if from_top {
+            let l = h.backward(remaining - 1).unwrap();
+            taken.insert(Unit::new(l, h));
+            break;
         } else {
-            Merger::NotMerged(other)
+            let h = l.forward(remaining - 1).unwrap();
+            taken.insert(Unit::new(l, h));
+            break;
         }
     }
+    taken
+}
+
+#[cfg(feature = "alloc")]
+/// Number of absent values between a run ending at `high` and the next one
+/// starting at `low`, saturating at `u128::MAX`
+fn gap<T: Discrete>(high: &T, low: &T) -> u128 {
+    T::distance(high, low).map_or(u128::MAX, |w| w - 1)
+}
+
+// Stored units never overlap, so their high ends are distinct and a bare
+// value can look up the units ending at or above it. Should that ever slip,
+// lookups go wrong but the set stays sound.
+#[cfg(feature = "alloc")]
+impl<T> Borrow<T> for Unit<T> {
+    fn borrow(&self) -> &T {
+        self.high()
+    }
 }
 
+/// Orders by high end, then low end, which agrees with `Borrow` and, for
+/// the disjoint units of a set, with their order along the line
+#[cfg(feature = "alloc")]
 impl<T: Ord> Ord for Unit<T> {
     fn cmp(&self, other: &Self) -> Ordering {
-        unsafe { self.partial_cmp(other).unwrap_unchecked() }
+        self.high()
+            .cmp(other.high())
+            .then_with(|| self.l.cmp(&other.l))
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T: Ord> PartialOrd for Unit<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let sh = self.h.as_ref().unwrap_or(&self.l);
-        let oh = other.h.as_ref().unwrap_or(&other.l);
-        if &self.l > oh {
-            Some(Ordering::Greater)
-        } else if sh < &other.l {
-            Some(Ordering::Less)
-        } else {
-            Some(sh.cmp(oh))
-        }
+        Some(self.cmp(other))
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<T: Eq + Display> Display for Unit<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let sh = self.h.as_ref().unwrap_or(&self.l);
-        if &self.l == sh {
-            write!(f, "{}", self.l)
+        write_runs(f, [(&self.l, self.high())], &FormatOptions::new())
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Per-instance behaviour chosen at construction
+#[derive(Clone, Copy, Debug, Default)]
+struct Config {
+    /// Number of absent values between two runs that insertion will absorb
+    /// when merging them
+    gap_tolerance: u128,
+    /// Number of runs the fallible insertions may not exceed
+    max_ranges: Option<usize>,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct Ranger<T>(BTreeSet<Unit<T>>, Config);
+
+#[cfg(feature = "alloc")]
+impl<T> Default for Ranger<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq + Display> Display for Ranger<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f, &FormatOptions::new())
+    }
+}
+
+/// How [`Ranger::write_to`] writes a set. The defaults give the `Display`
+/// form, such as `0-2,4,6-8`.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct FormatOptions<'a> {
+    /// Joins the ends of each run, `-` by default
+    pub separator: &'a str,
+    /// Comes between consecutive runs, `,` by default
+    pub delimiter: &'a str,
+}
+
+impl FormatOptions<'_> {
+    /// Returns the options giving the `Display` form
+    pub const fn new() -> Self {
+        FormatOptions {
+            separator: "-",
+            delimiter: ",",
+        }
+    }
+}
+
+impl Default for FormatOptions<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes runs, given by their ends in ascending order, laid out by `opts`.
+/// Every set type's text form goes through this, so they all read alike.
+fn write_runs<T: Eq + Display, W: fmt::Write + ?Sized>(
+    w: &mut W,
+    runs: impl IntoIterator<Item = (T, T)>,
+    opts: &FormatOptions<'_>,
+) -> fmt::Result {
+    for (i, (l, h)) in runs.into_iter().enumerate() {
+        if i > 0 {
+            w.write_str(opts.delimiter)?;
+        }
+        if l == h {
+            write!(w, "{}", l)?;
         } else {
-            write!(f, "{}-{}", self.l, sh)
+            write!(w, "{}{}{}", l, opts.separator, h)?;
         }
     }
+    Ok(())
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct Ranger<T>(BTreeSet<Unit<T>>);
+#[cfg(feature = "alloc")]
+/// Writes a set like its `Display` form, but joining the ends of each run
+/// with a chosen separator. Returned by [`Ranger::display_with`].
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayWith<'a, T> {
+    ranger: &'a Ranger<T>,
+    separator: &'a str,
+}
 
-impl<T: Eq + Display> Display for Ranger<T> {
+#[cfg(feature = "alloc")]
+impl<T: Eq + Display> Display for DisplayWith<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opts = FormatOptions {
+            separator: self.separator,
+            ..FormatOptions::new()
+        };
+        self.ranger.write_to(f, &opts)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Writes at most a given number of a set's runs in its `Display` form,
+/// followed by a count of those left out, as in `0-2,4,… (+3 ranges, 20
+/// values)`. Returned by [`Ranger::display_truncated`].
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayTruncated<'a, T> {
+    ranger: &'a Ranger<T>,
+    max_elements: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Discrete + Display> Display for DisplayTruncated<'_, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for u in self.0.iter().take(self.0.len().saturating_sub(1)) {
-            write!(f, "{},", u)?
+        let mut runs = self.ranger.0.iter();
+        let shown = runs.by_ref().take(self.max_elements);
+        write_runs(f, shown.map(|u| (&u.l, u.high())), &FormatOptions::new())?;
+        let left = runs.len();
+        if left == 0 {
+            return Ok(());
         }
-        if let Some(last) = self.0.iter().last() {
-            write!(f, "{}", last)?
+        let values = runs.fold(0u128, |n, u| n.saturating_add(u.len()));
+        let plural = |n: u128| if n == 1 { "" } else { "s" };
+        if self.max_elements > 0 {
+            f.write_str(",")?;
         }
-        Ok(())
+        write!(
+            f,
+            "\u{2026} (+{} range{}, {} value{})",
+            left,
+            plural(left as u128),
+            values,
+            plural(values)
+        )
     }
 }
 
-/// Pops the element immediately before the specified value
-pub fn pop_before<K: Ord>(set: &mut BTreeSet<K>, value: &K) -> Option<K> {
-    let key_ref = {
-        if let Some(key_ref) = set.range(..=value).next_back() {
-            /* must hide the origin of this borrow ... */
-            unsafe { &*(key_ref as *const _) }
+#[cfg(feature = "alloc")]
+impl<T: Eq + Display> Ranger<T> {
+    /// Writes the set into `w` as laid out by `opts`, without building a
+    /// string first. With the default options this writes exactly the
+    /// `Display` form, which goes through here too.
+    ///
+    /// ```
+    /// use ranger::{FormatOptions, Ranger};
+    ///
+    /// let ranger: Ranger<i32> = "-5--3,0,2-4".parse().unwrap();
+    /// let mut out = String::from("ids: ");
+    /// let opts = FormatOptions { separator: "..", delimiter: ", " };
+    /// ranger.write_to(&mut out, &opts).unwrap();
+    /// assert_eq!(out, "ids: -5..-3, 0, 2..4");
+    /// ```
+    pub fn write_to<W: fmt::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        opts: &FormatOptions<'_>,
+    ) -> fmt::Result {
+        write_runs(w, self.0.iter().map(|u| (&u.l, u.high())), opts)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq + Display + ToPrimitive> Ranger<T> {
+    /// Returns the number of bytes the `Display` form takes, worked out from
+    /// the digits of each run's ends without writing anything, so that a
+    /// buffer can be sized or refused before [`write_to`](Ranger::write_to)
+    /// fills it.
+    ///
+    /// Assumes values are displayed in plain decimal, as the primitive
+    /// integers are. Values beyond 128 bits are measured by writing them
+    /// into a counter instead.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let ranger: Ranger<i32> = "-120--5,0,7-1000".parse().unwrap();
+    /// assert_eq!(ranger.display_len(), "-120--5,0,7-1000".len());
+    /// ```
+    pub fn display_len(&self) -> usize {
+        self.display_len_with(&FormatOptions::new())
+    }
+    /// Returns the number of bytes [`write_to`](Ranger::write_to) writes
+    /// when laying the set out by `opts`, as
+    /// [`display_len`](Ranger::display_len) does for the `Display` form
+    pub fn display_len_with(&self, opts: &FormatOptions<'_>) -> usize {
+        let mut len = opts.delimiter.len() * self.0.len().saturating_sub(1);
+        for u in self.0.iter() {
+            len += decimal_len(&u.l);
+            if let Some(h) = u.h.as_ref().filter(|h| *h != &u.l) {
+                len += opts.separator.len() + decimal_len(h);
+            }
+        }
+        len
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Number of bytes in the decimal form of `v`
+fn decimal_len<T: Display + ToPrimitive>(v: &T) -> usize {
+    let digits = |n: u128| n.checked_ilog10().map_or(1, |d| d as usize + 1);
+    if let Some(n) = v.to_u128() {
+        return digits(n);
+    }
+    if let Some(n) = v.to_i128() {
+        return 1 + digits(n.unsigned_abs());
+    }
+    use fmt::Write as _;
+    struct Counter(usize);
+    impl fmt::Write for Counter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    write!(counter, "{}", v).expect("counting never fails");
+    counter.0
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Ranger<T> {
+    pub const fn new() -> Self {
+        Self(
+            BTreeSet::new(),
+            Config {
+                gap_tolerance: 0,
+                max_ranges: None,
+            },
+        )
+    }
+    /// Returns an estimate of the heap bytes the set's tree of runs takes
+    /// up, for planning rather than accounting: it assumes the standard
+    /// library's node layout with nodes about two thirds full, and leaves out
+    /// anything the elements themselves own on the heap. An empty set holds
+    /// nothing.
+    pub fn memory_usage(&self) -> usize {
+        // keys per node of the standard B-tree, and its typical fill
+        const CAPACITY: usize = 11;
+        const FILL: usize = 7;
+        let runs = self.0.len();
+        if runs == 0 {
+            return 0;
+        }
+        let ptr = size_of::<usize>();
+        // a leaf holds its keys, a parent pointer and two u16 counters, and
+        // an internal node adds a pointer to each child
+        let leaf = (CAPACITY * size_of::<Unit<T>>() + ptr + 4).next_multiple_of(ptr);
+        let internal = leaf + (CAPACITY + 1) * ptr;
+        let mut nodes = if runs <= CAPACITY {
+            1
         } else {
-            return None;
+            runs.div_ceil(FILL)
+        };
+        let mut bytes = nodes * leaf;
+        while nodes > 1 {
+            nodes = nodes.div_ceil(FILL + 1);
+            bytes += nodes * internal;
         }
-    };
-    /* ... so that we may be able to mutably borrow the set here
-    despite key_ref existence */
-    set.take(key_ref)
+        bytes
+    }
+    /// Returns a value displaying the set with the ends of each run joined by
+    /// `separator` rather than `-`, for element types whose own form
+    /// contains a `-`
+    pub fn display_with<'a>(&'a self, separator: &'a str) -> DisplayWith<'a, T> {
+        DisplayWith {
+            ranger: self,
+            separator,
+        }
+    }
+    /// Returns a value displaying at most the first `max_elements` runs,
+    /// followed by the exact number of runs and values left out, which are
+    /// counted without being written. A set of no more runs than that
+    /// displays exactly as it does through `Display`.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let ranger: Ranger<u32> = "1-3,5,7-9,100-199".parse().unwrap();
+    /// assert_eq!(
+    ///     ranger.display_truncated(2).to_string(),
+    ///     "1-3,5,\u{2026} (+2 ranges, 103 values)"
+    /// );
+    /// assert_eq!(ranger.display_truncated(4).to_string(), ranger.to_string());
+    /// ```
+    pub fn display_truncated(&self, max_elements: usize) -> DisplayTruncated<'_, T> {
+        DisplayTruncated {
+            ranger: self,
+            max_elements,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+// Construction settings such as gap tolerance don't take part in equality,
+// only the stored values do
+impl<T: PartialEq> PartialEq for Ranger<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq> Eq for Ranger<T> {}
+
+#[cfg(feature = "alloc")]
+/// Equal when the set holds exactly the distinct values of the slice. The
+/// slice may be in any order and repeat values; a slice with fewer elements
+/// than the set has values is rejected without looking at them.
+impl<T: Discrete> PartialEq<[T]> for Ranger<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.len() <= other.len() as u128 && self.0 == Ranger::from(other).0
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Compares as the slice of the array's values does
+impl<T: Discrete, const N: usize> PartialEq<[T; N]> for Ranger<T> {
+    fn eq(&self, other: &[T; N]) -> bool {
+        *self == other[..]
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Compares as the set does against the slice
+impl<T: Discrete> PartialEq<Ranger<T>> for [T] {
+    fn eq(&self, other: &Ranger<T>) -> bool {
+        *other == *self
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Compares as the set does against the array
+impl<T: Discrete, const N: usize> PartialEq<Ranger<T>> for [T; N] {
+    fn eq(&self, other: &Ranger<T>) -> bool {
+        *other == *self
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Structural order: runs are compared in sequence by low then high
+/// endpoint, and a set whose runs are a prefix of another's sorts first. This
+/// says nothing about subset inclusion.
+impl<T: Ord> Ord for Ranger<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .iter()
+            .map(|u| (&u.l, u.high()))
+            .cmp(other.0.iter().map(|u| (&u.l, u.high())))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> PartialOrd for Ranger<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Hash> Hash for Ranger<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.len());
+        for u in self.0.iter() {
+            u.l.hash(state);
+            u.high().hash(state);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Pops the element immediately before the specified value
+pub fn pop_before<K, Q>(set: &mut BTreeSet<K>, value: &Q) -> Option<K>
+where
+    K: Borrow<Q> + Ord + Clone,
+    Q: Ord,
+{
+    // the found key borrows the set, so take it out by a copy of itself
+    let key = set.range(..=value).next_back()?.clone();
+    set.take::<K>(&key)
 }
 
+#[cfg(feature = "alloc")]
 /// Pops the element immediately after the specified value
-pub fn pop_after<K: Ord>(set: &mut BTreeSet<K>, value: &K) -> Option<K> {
-    let key_ref = {
-        if let Some(key_ref) = set.range(value..).next() {
-            /* must hide the origin of this borrow ... */
-            unsafe { &*(key_ref as *const _) }
-        } else {
-            return None;
+pub fn pop_after<K, Q>(set: &mut BTreeSet<K>, value: &Q) -> Option<K>
+where
+    K: Borrow<Q> + Ord + Clone,
+    Q: Ord,
+{
+    let key = set.range(value..).next()?.clone();
+    set.take::<K>(&key)
+}
+
+#[cfg(feature = "alloc")]
+/// Pops the element strictly before the specified value
+pub fn pop_before_exclusive<K, Q>(set: &mut BTreeSet<K>, value: &Q) -> Option<K>
+where
+    K: Borrow<Q> + Ord + Clone,
+    Q: Ord,
+{
+    let key = set.range(..value).next_back()?.clone();
+    set.take::<K>(&key)
+}
+
+#[cfg(feature = "alloc")]
+/// Pops the element strictly after the specified value
+pub fn pop_after_exclusive<K, Q>(set: &mut BTreeSet<K>, value: &Q) -> Option<K>
+where
+    K: Borrow<Q> + Ord + Clone,
+    Q: Ord,
+{
+    let key = set
+        .range((Bound::Excluded(value), Bound::Unbounded))
+        .next()?
+        .clone();
+    set.take::<K>(&key)
+}
+
+#[cfg(feature = "alloc")]
+/// Returns the greatest element less than or equal to the specified value
+pub fn peek_before<'a, K, Q>(set: &'a BTreeSet<K>, value: &Q) -> Option<&'a K>
+where
+    K: Borrow<Q> + Ord,
+    Q: Ord,
+{
+    set.range(..=value).next_back()
+}
+
+#[cfg(feature = "alloc")]
+/// Returns the least element greater than or equal to the specified value
+pub fn peek_after<'a, K, Q>(set: &'a BTreeSet<K>, value: &Q) -> Option<&'a K>
+where
+    K: Borrow<Q> + Ord,
+    Q: Ord,
+{
+    set.range(value..).next()
+}
+
+#[cfg(feature = "alloc")]
+/// Removes and returns every element within `bounds`. An `Included` bound
+/// takes elements equal to it and an `Excluded` one leaves them behind.
+/// Unlike `BTreeSet::range`, bounds that cross simply select nothing.
+pub fn pop_range<K, Q, R>(set: &mut BTreeSet<K>, bounds: R) -> BTreeSet<K>
+where
+    K: Borrow<Q> + Ord,
+    Q: Ord,
+    R: RangeBounds<Q>,
+{
+    let mut taken = match bounds.start_bound() {
+        Bound::Unbounded => core::mem::take(set),
+        Bound::Included(s) => set.split_off(s),
+        Bound::Excluded(s) => {
+            let mut taken = set.split_off(s);
+            if taken.first().is_some_and(|k| k.borrow() == s) {
+                set.extend(taken.pop_first());
+            }
+            taken
         }
     };
-    /* ... so that we may be able to mutably borrow the set here
-    despite key_ref existence */
-    set.take(key_ref)
+    let mut rest = match bounds.end_bound() {
+        Bound::Unbounded => BTreeSet::new(),
+        Bound::Included(e) => {
+            let mut rest = taken.split_off(e);
+            if rest.first().is_some_and(|k| k.borrow() == e) {
+                taken.extend(rest.pop_first());
+            }
+            rest
+        }
+        Bound::Excluded(e) => taken.split_off(e),
+    };
+    set.append(&mut rest);
+    taken
 }
 
+#[cfg(feature = "alloc")]
 /// Pops the element equal to the value specified
-pub fn pop<K>(set: &mut BTreeSet<K>, value: &K) -> Option<K>
+pub fn pop<K, Q>(set: &mut BTreeSet<K>, value: &Q) -> Option<K>
 where
-    K: Ord,
+    K: Borrow<Q> + Ord,
+    Q: Ord + ?Sized,
 {
-    let key_ref = {
-        if let Some(key_ref) = set.range(..=value).next() {
-            /* must hide the origin of this borrow ... */
-            unsafe { &*(key_ref as *const _) }
-        } else {
-            return None;
-        }
-    };
-    /* ... so that we may be able to mutably borrow the set here
-    despite key_ref existence */
-    set.take(key_ref)
+    set.take(value)
+}
+
+#[cfg(feature = "alloc")]
+/// Pops the entry whose key is the greatest less than or equal to the
+/// specified key.
+///
+/// This suits maps keyed by the start of a segment, where the entry at or
+/// before a position is the segment that may cover it:
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use ranger::pop_before_entry;
+///
+/// // segments keyed by start, holding their exclusive end
+/// let mut segments = BTreeMap::from([(0u64, 10u64), (20, 30)]);
+/// let (start, end) = (10, 20);
+/// // absorb the segment ending where the new one starts
+/// let (start, end) = match pop_before_entry(&mut segments, &start) {
+///     Some((s, e)) if e >= start => (s, end.max(e)),
+///     Some((s, e)) => {
+///         segments.insert(s, e);
+///         (start, end)
+///     }
+///     None => (start, end),
+/// };
+/// // and the one starting where it ends
+/// let end = match segments.remove(&end) {
+///     Some(e) => e,
+///     None => end,
+/// };
+/// segments.insert(start, end);
+/// assert_eq!(segments, BTreeMap::from([(0, 30)]));
+/// ```
+pub fn pop_before_entry<K, V, Q>(map: &mut BTreeMap<K, V>, key: &Q) -> Option<(K, V)>
+where
+    K: Borrow<Q> + Ord + Clone,
+    Q: Ord,
+{
+    let found = map.range(..=key).next_back()?.0.clone();
+    map.remove_entry::<K>(&found)
+}
+
+#[cfg(feature = "alloc")]
+/// Pops the entry whose key is the least greater than or equal to the
+/// specified key
+pub fn pop_after_entry<K, V, Q>(map: &mut BTreeMap<K, V>, key: &Q) -> Option<(K, V)>
+where
+    K: Borrow<Q> + Ord + Clone,
+    Q: Ord,
+{
+    let found = map.range(key..).next()?.0.clone();
+    map.remove_entry::<K>(&found)
+}
+
+#[cfg(feature = "alloc")]
+/// Pops the entry whose key is equal to the specified key
+pub fn pop_entry<K, V, Q>(map: &mut BTreeMap<K, V>, key: &Q) -> Option<(K, V)>
+where
+    K: Borrow<Q> + Ord,
+    Q: Ord,
+{
+    map.remove_entry(key)
+}
+
+#[cfg(feature = "alloc")]
+/// How [`Ranger::insert_detailed`] changed the runs. Each run is given as
+/// it stands once the value is inserted, or as it stood before for the runs
+/// a bridging value joined.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum InsertOutcome<T> {
+    /// The value was already stored, and nothing changed
+    AlreadyPresent,
+    /// The value is a run of its own, touching no other
+    NewRun,
+    /// The value lengthened the run above it downwards
+    ExtendedLow(Span<T>),
+    /// The value lengthened the run below it upwards
+    ExtendedHigh(Span<T>),
+    /// The value joined the runs either side of it into one
+    Bridged {
+        run: Span<T>,
+        below: Span<T>,
+        above: Span<T>,
+    },
 }
 
-impl<T: Num + SaturatingSub + Ord + Display> Ranger<T> {
-    pub fn new() -> Self {
-        Self(BTreeSet::new())
+#[cfg(feature = "alloc")]
+/// Which runs [`Ranger::merge_in`] joined a value to, carrying only the
+/// ends it already held, from which `insert_detailed` builds its outcome
+enum Merge<T> {
+    Present,
+    Alone,
+    /// Lengthened the run below the value
+    Below,
+    /// Lengthened the run above the value
+    Above,
+    /// Joined two runs, which ended at `below_high` and began at `above_low`
+    Both {
+        below_high: T,
+        above_low: T,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl<T> InsertOutcome<T> {
+    /// Returns whether the value was not already present, as
+    /// [`Ranger::insert`] does
+    pub fn inserted(&self) -> bool {
+        !matches!(self, InsertOutcome::AlreadyPresent)
     }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Discrete> Ranger<T> {
     pub fn contains(&self, value: &T) -> bool {
-        let mut contained = false;
-        let v = unsafe {core::ptr::read(value)};
-        let u = Unit { l: v, h: None };
-        if let Some(v) = self.0.range(&u..).next() {
-            if v.l <= u.l && &u.l <= v.h.as_ref().unwrap_or(&v.l) {
-                contained = true;
-            }
-        }
-        core::mem::forget(u.l);
-        contained
+        self.unit_containing(value).is_some()
     }
     pub fn insert(&mut self, value: T) -> bool {
-        if self.contains(&value) {
-            return false
-        }
-        let u = Unit {
-            l: value,
-            h: None,
+        !matches!(self.merge_in(value), Merge::Present)
+    }
+    /// Inserts `value` as [`insert`](Ranger::insert) does, returning how it
+    /// changed the runs: whether it started one, lengthened one at either
+    /// end, joined two or was already present. With a gap tolerance, a run
+    /// is lengthened up to the value across the values missing before it.
+    ///
+    /// ```
+    /// use ranger::{InsertOutcome, Ranger, Span};
+    ///
+    /// let mut ranger: Ranger<u32> = "1-3,5-9".parse().unwrap();
+    /// assert_eq!(
+    ///     ranger.insert_detailed(4),
+    ///     InsertOutcome::Bridged {
+    ///         run: Span::new(1, 9).unwrap(),
+    ///         below: Span::new(1, 3).unwrap(),
+    ///         above: Span::new(5, 9).unwrap(),
+    ///     }
+    /// );
+    /// assert_eq!(ranger.insert_detailed(4), InsertOutcome::AlreadyPresent);
+    /// ```
+    pub fn insert_detailed(&mut self, value: T) -> InsertOutcome<T> {
+        let probe = value.clone();
+        let merge = self.merge_in(value);
+        let run = |ranger: &Self| {
+            ranger
+                .unit_containing(&probe)
+                .expect("inserted just now")
+                .span()
         };
-        let v = if let Some(mut low) = pop_before(&mut self.0, &u) {
-            match low.merged(u) {
-                Merger::Merged => low,
-                Merger::NotMerged(value) => {
-                    self.0.insert(low);
-                    value
+        match merge {
+            Merge::Present => InsertOutcome::AlreadyPresent,
+            Merge::Alone => InsertOutcome::NewRun,
+            Merge::Below => InsertOutcome::ExtendedHigh(run(self)),
+            Merge::Above => InsertOutcome::ExtendedLow(run(self)),
+            Merge::Both {
+                below_high,
+                above_low,
+            } => {
+                let run = run(self);
+                InsertOutcome::Bridged {
+                    below: Span::new_unchecked(run.low().clone(), below_high),
+                    above: Span::new_unchecked(above_low, run.high().clone()),
+                    run,
                 }
             }
-        } else {
-            u
-        };
-        let mut holster = Some(v);
-        while let Some(mut low) = holster.take() {
-            if let Some(high) = pop_after(&mut self.0, &low) {
-                match low.merged(high) {
-                    Merger::Merged => {
-                        holster = Some(low);
-                    }
-                    Merger::NotMerged(high) => {
-                        self.0.insert(low);
-                        self.0.insert(high);
-                    }
+        }
+    }
+    /// Inserts `value`, reporting which runs it merged with but building
+    /// nothing [`insert`](Ranger::insert) would throw away
+    fn merge_in(&mut self, value: T) -> Merge<T> {
+        // appending above every run is the common case for streams of
+        // ascending values, and needs no lookup
+        if let Some(last) = self.0.last() {
+            if &value > last.high() {
+                if touches(last.high(), &value, &self.1) {
+                    let mut last = self.0.pop_last().unwrap();
+                    last.h = Some(value);
+                    self.0.insert(last);
+                    return Merge::Below;
                 }
-            } else {
-                self.0.insert(low);
+                self.0.insert(Unit { l: value, h: None });
+                return Merge::Alone;
             }
         }
-        true
+        // the runs ending nearest above and below the value
+        let next = self.0.range::<T, _>(&value..).next();
+        if next.is_some_and(|u| u.contains(&value)) {
+            return Merge::Present;
+        }
+        let prev = self.0.range::<T, _>(..&value).next_back();
+        // the high ends of the runs to merge with, which look them up
+        let prev = prev
+            .filter(|u| touches(u.high(), &value, &self.1))
+            .map(|u| u.high().clone());
+        let next = next
+            .filter(|u| touches(&value, &u.l, &self.1))
+            .map(|u| u.high().clone());
+        // the run above hands its high end over to the merged run
+        let above_low = next.as_ref().map(|h| self.0.take(h).unwrap().l);
+        let below = prev.map(|h| (self.0.take(&h).unwrap().l, h));
+        let (unit, merge) = match (below, above_low) {
+            (None, None) => (Unit { l: value, h: None }, Merge::Alone),
+            (None, Some(_)) => (Unit { l: value, h: next }, Merge::Above),
+            (Some((l, _)), None) => (Unit { l, h: Some(value) }, Merge::Below),
+            (Some((l, below_high)), Some(above_low)) => (
+                Unit { l, h: next },
+                Merge::Both {
+                    below_high,
+                    above_low,
+                },
+            ),
+        };
+        self.0.insert(unit);
+        merge
+    }
+    /// Inserts every value yielded by `values`, returning how many distinct
+    /// values were not already present
+    pub fn insert_all(&mut self, values: impl IntoIterator<Item = T>) -> u128 {
+        values
+            .into_iter()
+            .fold(0, |added, v| added + self.insert(v) as u128)
+    }
+    /// Builds a set from values given in ascending order, in linear time and
+    /// with one tree insertion per resulting run. Repeated values are
+    /// skipped, while a value below its predecessor fails with the position
+    /// of that value.
+    pub fn from_sorted_iter(iter: impl IntoIterator<Item = T>) -> Result<Self, NotSorted> {
+        let mut runs = Vec::new();
+        let mut run: Option<(T, T)> = None;
+        for (i, v) in iter.into_iter().enumerate() {
+            run = Some(match run {
+                None => (v.clone(), v),
+                Some((l, h)) => match v.cmp(&h) {
+                    Ordering::Less => return Err(NotSorted(i)),
+                    Ordering::Equal => (l, h),
+                    Ordering::Greater if T::adjacent(&h, &v) => (l, v),
+                    Ordering::Greater => {
+                        runs.push(Unit::new(l, h));
+                        (v.clone(), v)
+                    }
+                },
+            });
+        }
+        runs.extend(run.map(|(l, h)| Unit::new(l, h)));
+        Ok(Self(runs.into_iter().collect(), Config::default()))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloc::string::ToString;
-    use libc_print::std_name::println;
-    use rand::{seq::SliceRandom, thread_rng};
-
-    #[test]
-    fn it_works() {
-        let input_numbers: &mut [u8] = &mut [
-            0, 1, 2, 4, 6, 7, 8, 11, 12, 14, 15, 16, 17, 18, 19, 20, 21, 0, 22, 23, 24, 25, 27, 28,
-            29, 30, 31, 32, 33, 35, 36, 37, 38, 39,
-        ];
-        let mut ranger = Ranger::new();
-        for num in input_numbers.iter() {
-            ranger.insert(*num);
+#[cfg(feature = "alloc")]
+impl<T: Ord + Clone> Ranger<T> {
+    /// Returns the unit containing `value`, if any
+    fn unit_containing(&self, value: &T) -> Option<&Unit<T>> {
+        self.0
+            .range::<T, _>(value..)
+            .next()
+            .filter(|u| u.contains(value))
+    }
+    /// Returns the run containing `value`, if any
+    pub fn get_covering(&self, value: &T) -> Option<RangeInclusive<T>> {
+        self.unit_containing(value)
+            .map(|u| u.l.clone()..=u.high().clone())
+    }
+    /// Returns the lowest run, or `None` if the set is empty
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let acked: Ranger<u64> = "0-41,44,47-50".parse().unwrap();
+    /// assert_eq!(acked.first_range(), Some(0..=41));
+    /// assert_eq!(acked.last_range(), Some(47..=50));
+    /// assert_eq!(Ranger::<u64>::new().last_range(), None);
+    /// ```
+    pub fn first_range(&self) -> Option<RangeInclusive<T>> {
+        self.0.first().map(|u| u.l.clone()..=u.high().clone())
+    }
+    /// Returns the highest run, or `None` if the set is empty
+    pub fn last_range(&self) -> Option<RangeInclusive<T>> {
+        self.0.last().map(|u| u.l.clone()..=u.high().clone())
+    }
+    /// Returns the run at `index` counting from the lowest, or `None` past
+    /// the last. The tree has no positional lookup, so this steps over the
+    /// `index` runs before it; [`FrozenRanger::nth_range`] answers in
+    /// constant time.
+    pub fn nth_range(&self, index: usize) -> Option<RangeInclusive<T>> {
+        self.0
+            .iter()
+            .nth(index)
+            .map(|u| u.l.clone()..=u.high().clone())
+    }
+    /// Iterates over up to `len` runs in ascending order, starting from the
+    /// one at index `start`, so that a page of runs costs one skip rather
+    /// than one per run
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let ranger: Ranger<u32> = "0,2,4,6,8,10,12".parse().unwrap();
+    /// let page: Vec<_> = ranger.ranges_page(3, 3).collect();
+    /// assert_eq!(page, [6..=6, 8..=8, 10..=10]);
+    /// assert_eq!(ranger.ranges_page(6, 3).count(), 1);
+    /// ```
+    pub fn ranges_page(
+        &self,
+        start: usize,
+        len: usize,
+    ) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.0
+            .iter()
+            .skip(start)
+            .take(len)
+            .map(|u| u.l.clone()..=u.high().clone())
+    }
+    /// Iterates over the runs in ascending order
+    pub fn ranges(&self) -> impl Iterator<Item = Span<T>> + '_ {
+        self.0.iter().map(Unit::span)
+    }
+    /// Returns whether every value in `range` is stored. An empty range is
+    /// trivially contained.
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return true;
         }
-        assert_eq!(ranger.to_string(), "0-2,4,6-8,11-12,14-25,27-33,35-39");
-        drop(ranger);
-        input_numbers.reverse();
-        let mut ranger = Ranger::new();
-        for num in input_numbers {
-            ranger.insert(*num);
+        self.unit_containing(range.start())
+            .is_some_and(|u| u.high() >= range.end())
+    }
+    /// Returns whether any value in `range` is stored
+    pub fn overlaps_range(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return false;
         }
-        assert_eq!(ranger.to_string(), "0-2,4,6-8,11-12,14-25,27-33,35-39");
-        drop(ranger);
-        let input_numbers: &[i8] = &[
-            -1, 33, 35, 23, 20, -128, 28, 0, 19, 18, 14, 25, 21, 127, 38, 6, 39, 27, 11, 17, 7, 12,
-            126, -126, 31, 15, 32, 4, 29, 36, 22, 1, 0, 37, 30, 8, 24, 16, 2, -127, 125,
-        ];
-        let mut ranger = Ranger::new();
-        for num in input_numbers {
-            ranger.insert(*num);
+        self.0
+            .range::<T, _>(range.start()..)
+            .next()
+            .is_some_and(|u| u.overlaps(range.start(), range.end()))
+    }
+    /// Returns whether every value yielded by `values` is stored, stopping at
+    /// the first one that isn't.
+    ///
+    /// Consecutive values falling in the same run are answered without
+    /// another lookup, so sorted or clustered input is cheap.
+    pub fn contains_all(&self, values: impl IntoIterator<Item = T>) -> bool {
+        let mut last: Option<&Unit<T>> = None;
+        values.into_iter().all(|v| {
+            if last.is_some_and(|u| u.l <= v && &v <= u.high()) {
+                return true;
+            }
+            last = self.unit_containing(&v);
+            last.is_some()
+        })
+    }
+    /// Returns whether any value yielded by `values` is stored, stopping at
+    /// the first one that is
+    pub fn contains_any(&self, values: impl IntoIterator<Item = T>) -> bool {
+        values
+            .into_iter()
+            .any(|v| self.unit_containing(&v).is_some())
+    }
+    /// Returns whether every range yielded by `ranges` is entirely stored,
+    /// stopping at the first one that isn't
+    pub fn contains_all_ranges(&self, ranges: impl IntoIterator<Item = RangeInclusive<T>>) -> bool {
+        ranges.into_iter().all(|r| self.contains_range(&r))
+    }
+    /// Returns whether any range yielded by `ranges` has a value stored,
+    /// stopping at the first one that does
+    pub fn contains_any_ranges(&self, ranges: impl IntoIterator<Item = RangeInclusive<T>>) -> bool {
+        ranges.into_iter().any(|r| self.overlaps_range(&r))
+    }
+    /// Removes every value strictly less than `threshold`, truncating the run
+    /// that straddles it so that `threshold` itself is kept
+    pub fn remove_below(&mut self, threshold: &T) {
+        let mut kept = self.0.split_off(threshold);
+        if let Some(first) = kept.pop_first() {
+            if &first.l < threshold {
+                let h = first.h.unwrap_or(first.l);
+                kept.insert(Unit::new(threshold.clone(), h));
+            } else {
+                kept.insert(first);
+            }
         }
-        assert_eq!(
-            ranger.to_string(),
-            "-128--126,-1-2,4,6-8,11-12,14-25,27-33,35-39,125-127"
-        );
-        println!("{:?} -> {ranger}", input_numbers);
-        drop(ranger);
-
-        // Rule out edge cases
-        for _ in 0..10_000 {
-            let mut myvec = input_numbers.to_vec();
-            myvec.shuffle(&mut thread_rng());
-            let mut ranger = Ranger::new();
-            for num in myvec {
-                ranger.insert(num);
+        self.0 = kept;
+    }
+    /// Removes every value strictly greater than `threshold`, truncating the
+    /// run that straddles it so that `threshold` itself is kept
+    pub fn remove_above(&mut self, threshold: &T) {
+        let mut above = self.0.split_off(threshold);
+        if let Some(first) = above.pop_first() {
+            if &first.l <= threshold {
+                self.0.insert(Unit::new(first.l, threshold.clone()));
             }
-            assert_eq!(
-                ranger.to_string(),
-                "-128--126,-1-2,4,6-8,11-12,14-25,27-33,35-39,125-127"
-            );
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Resolves `bounds` to inclusive endpoints, with `None` standing for an
+/// unbounded end. Returns `None` if the bounds contain no values.
+fn inclusive_bounds<T, R>(bounds: &R) -> Option<(Option<T>, Option<T>)>
+where
+    T: Discrete,
+    R: RangeBounds<T>,
+{
+    let low = match bounds.start_bound() {
+        Bound::Included(l) => Some(l.clone()),
+        Bound::Excluded(l) => Some(l.successor()?),
+        Bound::Unbounded => None,
+    };
+    let high = match bounds.end_bound() {
+        Bound::Included(h) => Some(h.clone()),
+        Bound::Excluded(h) => Some(h.predecessor()?),
+        Bound::Unbounded => None,
+    };
+    match (&low, &high) {
+        (Some(l), Some(h)) if l > h => None,
+        _ => Some((low, high)),
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Whether a run ending at `high` should merge with one starting at `low`
+/// under the given configuration
+fn touches<T: Discrete>(high: &T, low: &T, config: &Config) -> bool {
+    T::adjacent(high, low)
+        || (config.gap_tolerance > 0 && high < low && gap(high, low) <= config.gap_tolerance)
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Discrete> Ranger<T> {
+    /// Creates an empty set whose insertions merge runs separated by up to
+    /// `gap_tolerance` absent values, treating the values in between as
+    /// stored.
+    ///
+    /// This changes what the set contains: the absorbed values are reported
+    /// by [`Ranger::contains`], counted by [`Ranger::len`] and displayed like
+    /// any other. A tolerance of zero (or below) gives the usual behaviour.
+    /// Only merges made by insertion are affected; removal still splits runs
+    /// as usual.
+    pub fn with_gap_tolerance(gap_tolerance: T) -> Self
+    where
+        T: ToPrimitive + Zero,
+    {
+        let gap_tolerance = match gap_tolerance.to_u128() {
+            Some(g) => g,
+            None if gap_tolerance > T::zero() => u128::MAX,
+            None => 0,
+        };
+        Ranger(
+            BTreeSet::new(),
+            Config {
+                gap_tolerance,
+                ..Config::default()
+            },
+        )
+    }
+    /// Creates an empty set whose fallible insertions ([`Ranger::try_insert`],
+    /// [`Ranger::try_insert_range`] and [`Ranger::try_extend`]) refuse to grow
+    /// it beyond `max_ranges` runs.
+    ///
+    /// Insertions that extend or merge existing runs always succeed. The
+    /// infallible insertion methods do not consult the limit.
+    pub fn with_max_ranges(max_ranges: usize) -> Self {
+        Ranger(
+            BTreeSet::new(),
+            Config {
+                max_ranges: Some(max_ranges),
+                ..Config::default()
+            },
+        )
+    }
+    /// Returns the number of runs stored
+    pub fn range_count(&self) -> usize {
+        self.0.len()
+    }
+    /// Whether inserting `low..=high` would start a new run rather than
+    /// overlap or touch an existing one
+    fn starts_new_run(&self, low: &T, high: &T) -> bool {
+        let below = self.0.range::<T, _>(..low).next_back();
+        let above = self.0.range::<T, _>(low..).next();
+        !below.is_some_and(|u| touches(u.high(), low, &self.1))
+            && !above.is_some_and(|u| u.overlaps(low, high) || touches(high, &u.l, &self.1))
+    }
+    /// Inserts `value` unless doing so would exceed the configured maximum
+    /// number of runs. Returns whether the value was not already present.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, CapacityExceeded> {
+        self.try_insert_range(value.clone()..=value)
+            .map(|added| added > 0)
+    }
+    /// Inserts every value in `range` unless doing so would exceed the
+    /// configured maximum number of runs. Returns the number of values that
+    /// were not already present.
+    pub fn try_insert_range(&mut self, range: RangeInclusive<T>) -> Result<u128, CapacityExceeded> {
+        if let Some(max_ranges) = self.1.max_ranges {
+            if self.0.len() >= max_ranges
+                && !range.is_empty()
+                && self.starts_new_run(range.start(), range.end())
+            {
+                return Err(CapacityExceeded);
+            }
+        }
+        Ok(self.insert_range(range))
+    }
+    /// Inserts the values yielded by `values` in order, stopping at the first
+    /// one that would exceed the configured maximum number of runs. Values
+    /// inserted before that point are kept. Returns how many distinct values
+    /// were not already present.
+    pub fn try_extend(
+        &mut self,
+        values: impl IntoIterator<Item = T>,
+    ) -> Result<u128, CapacityExceeded> {
+        let mut added = 0;
+        for v in values {
+            added += self.try_insert(v)? as u128;
+        }
+        Ok(added)
+    }
+    /// Returns the number of values stored, saturating at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        self.0
+            .iter()
+            .fold(0u128, |acc, u| acc.saturating_add(u.len()))
+    }
+    /// Returns whether no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Checks that every run is well formed and lies clear of the next, as
+    /// every operation assumes, reporting the first run that is not. Useful
+    /// on sets built from untrusted data; debug builds also check after each
+    /// operation that rebuilds runs.
+    ///
+    /// Runs separated by no more than the gap tolerance are not a violation,
+    /// as removal may leave them so.
+    pub fn validate(&self) -> Result<(), InvariantViolation> {
+        let mut prev: Option<&Unit<T>> = None;
+        for (i, u) in self.0.iter().enumerate() {
+            if &u.l > u.high() {
+                return Err(InvariantViolation::Reversed(i));
+            }
+            if let Some(p) = prev {
+                if &u.l <= p.high() {
+                    return Err(InvariantViolation::Overlapping(i));
+                }
+                if T::adjacent(p.high(), &u.l) {
+                    return Err(InvariantViolation::Adjacent(i));
+                }
+            }
+            prev = Some(u);
+        }
+        Ok(())
+    }
+    /// Inserts every value in `range`, merging with any runs it overlaps or
+    /// touches. Returns the number of values that were not already present.
+    pub fn insert_range(&mut self, range: RangeInclusive<T>) -> u128 {
+        self.merge_range(range).0
+    }
+    /// Ensures every value in `range` is stored, merging with neighbouring
+    /// runs as [`insert_range`](Self::insert_range) does, and returns the
+    /// whole run that then covers it. A range already covered leaves the set
+    /// untouched, and an empty range is handed back as it is.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let mut segments: Ranger<u32> = "7-9,20-29".parse().unwrap();
+    /// assert_eq!(segments.get_or_insert_range(5..=6), 5..=9);
+    /// assert_eq!(segments.get_or_insert_range(22..=24), 20..=29);
+    /// assert_eq!(segments.to_string(), "5-9,20-29");
+    /// ```
+    pub fn get_or_insert_range(&mut self, range: RangeInclusive<T>) -> RangeInclusive<T> {
+        self.merge_range(range).1
+    }
+    /// Inserts `range` as [`insert_range`](Self::insert_range) does, also
+    /// returning the run that covers it afterwards, or the range itself if
+    /// empty
+    fn merge_range(&mut self, range: RangeInclusive<T>) -> (u128, RangeInclusive<T>) {
+        if range.is_empty() {
+            return (0, range);
+        }
+        let (mut l, mut h) = range.into_inner();
+        if let Some(u) = self.0.range::<T, _>(&l..).next() {
+            if u.contains(&l) && u.high() >= &h {
+                return (0, u.l.clone()..=u.high().clone());
+            }
+        }
+        let mut absorbed = 0u128;
+        if let Some(low) = pop_before_exclusive(&mut self.0, &l) {
+            if touches(low.high(), &l, &self.1) {
+                absorbed = absorbed.saturating_add(low.len());
+                l = low.l;
+            } else {
+                self.0.insert(low);
+            }
+        }
+        while let Some(high) = pop_after(&mut self.0, &l) {
+            if high.overlaps(&l, &h) || touches(&h, &high.l, &self.1) {
+                absorbed = absorbed.saturating_add(high.len());
+                h = core::cmp::max(h, high.high().clone());
+                l = core::cmp::min(l, high.l);
+            } else {
+                self.0.insert(high);
+                break;
+            }
+        }
+        let added = added_len(&l, &h, absorbed);
+        let run = l.clone()..=h.clone();
+        self.0.insert(Unit::new(l, h));
+        debug_assert_eq!(self.validate(), Ok(()));
+        (added, run)
+    }
+    /// Inserts every value within `bounds`, which may have exclusive or
+    /// unbounded ends, returning the number of values that were not already
+    /// present
+    pub fn insert_bounds<R: RangeBounds<T>>(&mut self, bounds: R) -> u128
+    where
+        T: Bounded,
+    {
+        let Some((low, high)) = inclusive_bounds(&bounds) else {
+            return 0;
+        };
+        let low = low.unwrap_or_else(T::min_value);
+        let high = high.unwrap_or_else(T::max_value);
+        self.insert_range(low..=high)
+    }
+    /// Removes every value within `bounds`, which may have exclusive or
+    /// unbounded ends, returning the number of values that were present
+    pub fn remove_range<R: RangeBounds<T>>(&mut self, bounds: R) -> u128 {
+        let Some((low, high)) = inclusive_bounds(&bounds) else {
+            return 0;
+        };
+        let mut removed = 0u128;
+        loop {
+            let u = match &low {
+                Some(low) => pop_after(&mut self.0, low),
+                None => self.0.pop_first(),
+            };
+            let Some(u) = u else {
+                break;
+            };
+            if high.as_ref().is_some_and(|h| &u.l > h) {
+                self.0.insert(u);
+                break;
+            }
+            let Unit { l, h } = u;
+            let h = h.unwrap_or_else(|| l.clone());
+            let from = match &low {
+                Some(low) if &l < low => {
+                    self.0.insert(Unit::new(l, step_down(low)));
+                    low.clone()
+                }
+                _ => l,
+            };
+            match &high {
+                Some(high) if &h > high => {
+                    removed = removed.saturating_add(Unit::new(from, high.clone()).len());
+                    self.0.insert(Unit::new(step_up(high), h));
+                    break;
+                }
+                _ => removed = removed.saturating_add(Unit::new(from, h).len()),
+            }
+        }
+        debug_assert_eq!(self.validate(), Ok(()));
+        removed
+    }
+    /// Removes every value outside of `bounds`, which may have exclusive or
+    /// unbounded ends, truncating the runs that straddle either end. An empty
+    /// window clears the set.
+    pub fn clamp_to<R: RangeBounds<T>>(&mut self, bounds: R) {
+        let Some((low, high)) = inclusive_bounds(&bounds) else {
+            self.0.clear();
+            return;
+        };
+        if let Some(low) = low {
+            self.remove_below(&low);
+        }
+        if let Some(high) = high {
+            self.remove_above(&high);
+        }
+    }
+    /// Removes `value`, splitting the run containing it if necessary. Returns
+    /// whether the value was present.
+    pub fn remove(&mut self, value: &T) -> bool {
+        let Some(u) = pop_after(&mut self.0, value) else {
+            return false;
+        };
+        if value < &u.l {
+            self.0.insert(u);
+            return false;
+        }
+        let (below, above) = u.split_around(value);
+        self.0.extend(below.into_iter().chain(above));
+        true
+    }
+    /// Removes every value yielded by `values`, returning how many distinct
+    /// values were actually present.
+    ///
+    /// The values are sorted first so that each run is looked up once no
+    /// matter how many of the values fall within it.
+    pub fn remove_all(&mut self, values: impl IntoIterator<Item = T>) -> u128 {
+        let mut values: Vec<T> = values.into_iter().collect();
+        values.sort_unstable();
+        values.dedup();
+        let mut removed = 0;
+        let mut pending = values.into_iter().peekable();
+        while let Some(v) = pending.peek() {
+            let Some(u) = pop_after(&mut self.0, v) else {
+                break;
+            };
+            let h = u.high().clone();
+            let mut rest = Some(u);
+            while let Some(v) = pending.next_if(|v| v <= &h) {
+                match rest.take() {
+                    Some(r) if r.l <= v => {
+                        removed += 1;
+                        let (below, above) = r.split_around(&v);
+                        self.0.extend(below);
+                        rest = above;
+                    }
+                    other => rest = other,
+                }
+            }
+            self.0.extend(rest);
+        }
+        debug_assert_eq!(self.validate(), Ok(()));
+        removed
+    }
+    /// Merges every pair of consecutive runs separated by at most `max_gap`
+    /// absent values, returning the total number of values absorbed
+    pub fn merge_gaps_smaller_than(&mut self, max_gap: T) -> u128
+    where
+        T: ToPrimitive + Zero,
+    {
+        let max_gap = match max_gap.to_u128() {
+            Some(max_gap) => max_gap,
+            None if max_gap > T::zero() => u128::MAX,
+            None => return 0,
+        };
+        let mut units = core::mem::take(&mut self.0).into_iter();
+        let Some(mut current) = units.next() else {
+            return 0;
+        };
+        let mut absorbed = 0u128;
+        for u in units {
+            let gap = gap(current.high(), &u.l);
+            if gap <= max_gap {
+                absorbed = absorbed.saturating_add(gap);
+                current.h = Some(u.h.unwrap_or(u.l));
+            } else {
+                self.0.insert(core::mem::replace(&mut current, u));
+            }
+        }
+        self.0.insert(current);
+        debug_assert_eq!(self.validate(), Ok(()));
+        absorbed
+    }
+    /// Groups the runs into clusters, starting a new one wherever more than
+    /// `min_gap` absent values separate consecutive runs, and returns them in
+    /// ascending order. A `min_gap` of zero (or below) puts every run in a
+    /// cluster of its own, while the clusters together always hold exactly
+    /// the stored values.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let coverage: Ranger<u32> = "0-9,12-15,17,100-120,125,900".parse().unwrap();
+    /// let regions: Vec<String> = coverage.cluster(10).iter().map(|c| c.to_string()).collect();
+    /// assert_eq!(regions, ["0-9,12-15,17", "100-120,125", "900"]);
+    /// ```
+    pub fn cluster(&self, min_gap: T) -> Vec<Ranger<T>>
+    where
+        T: ToPrimitive + Zero,
+    {
+        let min_gap = match min_gap.to_u128() {
+            Some(min_gap) => min_gap,
+            None if min_gap > T::zero() => u128::MAX,
+            None => 0,
+        };
+        let mut clusters = Vec::new();
+        let mut current = BTreeSet::new();
+        let mut prev: Option<&Unit<T>> = None;
+        for u in self.0.iter() {
+            if prev.is_some_and(|p| gap(p.high(), &u.l) > min_gap) {
+                clusters.push(Ranger(core::mem::take(&mut current), self.1));
+            }
+            current.insert(u.clone());
+            prev = Some(u);
+        }
+        if !current.is_empty() {
+            clusters.push(Ranger(current, self.1));
+        }
+        clusters
+    }
+    /// Returns the `n` smallest stored values, or a copy of the whole set if
+    /// it holds fewer than `n`
+    pub fn first_n(&self, n: u128) -> Ranger<T> {
+        let runs = self.0.iter().map(|u| (u.l.clone(), u.high().clone()));
+        Ranger(take_values(runs, n, false), self.1)
+    }
+    /// Returns the `n` largest stored values, or a copy of the whole set if
+    /// it holds fewer than `n`
+    pub fn last_n(&self, n: u128) -> Ranger<T> {
+        let runs = self.0.iter().rev().map(|u| (u.l.clone(), u.high().clone()));
+        Ranger(take_values(runs, n, true), self.1)
+    }
+    /// Partitions the stored values into `k` sets whose sizes differ by at
+    /// most one, in ascending order so that the first shard holds the lowest
+    /// values. Runs are split across shard boundaries as needed.
+    ///
+    /// A `k` of zero yields no shards. If `k` exceeds the number of values,
+    /// the trailing shards are empty.
+    pub fn split_evenly(&self, k: usize) -> Vec<Ranger<T>> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let total = self.len();
+        let (base, extra) = (total / k as u128, total % k as u128);
+        let mut runs = self.0.iter().map(|u| (u.l.clone(), u.high().clone()));
+        let mut current = runs.next();
+        (0..k)
+            .map(|i| {
+                let mut need = base + ((i as u128) < extra) as u128;
+                let mut shard = BTreeSet::new();
+                while need > 0 {
+                    let Some((l, h)) = current.take() else {
+                        break;
+                    };
+                    match T::distance(&l, &h) {
+                        Some(w) if w < need => {
+                            need -= w + 1;
+                            shard.insert(Unit::new(l, h));
+                            current = runs.next();
+                        }
+                        _ => {
+                            let end = l.forward(need - 1).unwrap();
+                            current = Some((step_up(&end), h));
+                            shard.insert(Unit::new(l, end));
+                            need = 0;
+                        }
+                    }
+                }
+                Ranger(shard, self.1)
+            })
+            .collect()
+    }
+    /// Returns a copy of the set reduced to at most `max_ranges` runs by
+    /// merging across the narrowest gaps, see [`Ranger::summarize_in_place`]
+    pub fn summarize(&self, max_ranges: usize) -> Ranger<T> {
+        let mut summary = self.clone();
+        summary.summarize_in_place(max_ranges);
+        summary
+    }
+    /// Reduces the set to at most `max_ranges` runs by repeatedly merging
+    /// across the narrowest remaining gap, returning the total number of
+    /// values absorbed.
+    ///
+    /// Gaps of equal width are merged lowest first. A `max_ranges` of zero is
+    /// treated as one, as merging can never remove the last run.
+    pub fn summarize_in_place(&mut self, max_ranges: usize) -> u128 {
+        let max_ranges = max_ranges.max(1);
+        if self.0.len() <= max_ranges {
+            return 0;
+        }
+        let units: Vec<Unit<T>> = core::mem::take(&mut self.0).into_iter().collect();
+        let mut gaps: Vec<(u128, usize)> = units
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| (gap(pair[0].high(), &pair[1].l), i))
+            .collect();
+        gaps.sort_unstable();
+        let mut bridged = vec![false; gaps.len()];
+        let mut absorbed = 0u128;
+        for &(width, i) in &gaps[..units.len() - max_ranges] {
+            bridged[i] = true;
+            absorbed = absorbed.saturating_add(width);
+        }
+        let mut units = units.into_iter();
+        let mut current = units.next().unwrap();
+        for (u, bridged) in units.zip(bridged) {
+            if bridged {
+                current.h = Some(u.h.unwrap_or(u.l));
+            } else {
+                self.0.insert(core::mem::replace(&mut current, u));
+            }
+        }
+        self.0.insert(current);
+        absorbed
+    }
+    /// Applies `f` to the endpoints of every run and collects the results,
+    /// merging any runs that end up overlapping or adjacent. Endpoints
+    /// returned in reverse order are swapped.
+    pub fn map_ranges<F: FnMut(T, T) -> (T, T)>(self, mut f: F) -> Ranger<T> {
+        let mut mapped = Ranger(BTreeSet::new(), Config::default());
+        for u in self.0 {
+            let h = u.high().clone();
+            let (l, h) = f(u.l, h);
+            if l <= h {
+                mapped.insert_range(l..=h);
+            } else {
+                mapped.insert_range(h..=l);
+            }
+        }
+        mapped
+    }
+    /// Applies `f` to every stored value and collects the results into a new
+    /// set.
+    ///
+    /// Unlike [`Ranger::map_ranges`] this is correct for any function, but
+    /// visits each value individually and so runs in O(values) rather than
+    /// O(runs). Prefer `map_ranges` for monotonic, affine mappings.
+    pub fn map_values<F: FnMut(&T) -> T>(&self, mut f: F) -> Ranger<T> {
+        let mut mapped = Ranger(BTreeSet::new(), Config::default());
+        for u in self.0.iter() {
+            for v in u.values() {
+                let m = f(&v);
+                mapped.insert_range(m.clone()..=m);
+            }
+        }
+        mapped
+    }
+    /// Returns the values within `bounds` that are not stored in this set
+    pub fn invert_within(&self, bounds: RangeInclusive<T>) -> Ranger<T> {
+        let mut inverted = BTreeSet::new();
+        let (low, high) = bounds.into_inner();
+        if low > high {
+            return Ranger(inverted, Config::default());
+        }
+        let mut cursor = Some(low.clone());
+        for u in self.0.range::<T, _>(&low..) {
+            if u.l > high {
+                break;
+            }
+            let Some(free) = cursor.take() else {
+                break;
+            };
+            if free < u.l {
+                inverted.insert(Unit::new(free, step_down(&u.l)));
+            }
+            if u.high() < &high {
+                cursor = Some(step_up(u.high()));
+            }
+        }
+        if let Some(free) = cursor {
+            inverted.insert(Unit::new(free, high));
+        }
+        Ranger(inverted, Config::default())
+    }
+    /// Adds `offset` to every stored value. Fails without modifying the set
+    /// if any value would overflow.
+    pub fn shift_by(&mut self, offset: T) -> Result<(), OverflowError>
+    where
+        T: Num + CheckedAdd,
+    {
+        let (Some(first), Some(last)) = (self.0.first(), self.0.last()) else {
+            return Ok(());
+        };
+        // shifting preserves order, so only the extremes can overflow
+        if first.l.checked_add(&offset).is_none() || last.high().checked_add(&offset).is_none() {
+            return Err(OverflowError);
+        }
+        self.0 = core::mem::take(&mut self.0)
+            .into_iter()
+            .map(|u| Unit {
+                l: u.l + offset.clone(),
+                h: u.h.map(|h| h + offset.clone()),
+            })
+            .collect();
+        Ok(())
+    }
+    /// Moves every stored value `delta` values up, which unlike
+    /// [`shift_by`](Ranger::shift_by) needs no signed offset, so works for
+    /// any element type. Fails without modifying the set if any value would
+    /// pass the top of the type.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let mut blocks: Ranger<u64> = "0-3,8".parse().unwrap();
+    /// blocks.shift_up(1000).unwrap();
+    /// assert_eq!(blocks.to_string(), "1000-1003,1008");
+    /// blocks.shift_down(1000).unwrap();
+    /// assert_eq!(blocks.to_string(), "0-3,8");
+    /// ```
+    pub fn shift_up(&mut self, delta: u128) -> Result<(), OverflowError> {
+        self.shift_with(|v| v.forward(delta))
+    }
+    /// Moves every stored value `delta` values down. Fails without modifying
+    /// the set if any value would pass the bottom of the type.
+    pub fn shift_down(&mut self, delta: u128) -> Result<(), OverflowError> {
+        self.shift_with(|v| v.backward(delta))
+    }
+    /// Replaces each run's ends by `step` of them, which must preserve order
+    /// and gaps, as moving by a fixed number of values does
+    fn shift_with(&mut self, step: impl Fn(&T) -> Option<T>) -> Result<(), OverflowError> {
+        let (Some(first), Some(last)) = (self.0.first(), self.0.last()) else {
+            return Ok(());
+        };
+        // shifting preserves order, so only the extremes can overflow
+        if step(&first.l).is_none() || step(last.high()).is_none() {
+            return Err(OverflowError);
+        }
+        let step = |v: &T| step(v).expect("between the shifted extremes");
+        self.0 = core::mem::take(&mut self.0)
+            .into_iter()
+            .map(|u| Unit {
+                l: step(&u.l),
+                h: u.h.as_ref().map(step),
+            })
+            .collect();
+        Ok(())
+    }
+    /// Returns each run as its low end and its number of values, in
+    /// ascending order, as formats giving runs by start and length expect.
+    ///
+    /// Fails before yielding anything if any run holds more values than `T`
+    /// can count, such as `0-255` in a `Ranger<u8>` or `-100-100` in a
+    /// `Ranger<i8>`.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let ranger: Ranger<u32> = "3,10-14".parse().unwrap();
+    /// let runs: Vec<_> = ranger.run_lengths().unwrap().collect();
+    /// assert_eq!(runs, [(3, 1), (10, 5)]);
+    /// assert_eq!(Ranger::from_run_lengths(runs), Ok(ranger));
+    /// ```
+    pub fn run_lengths(&self) -> Result<impl Iterator<Item = (T, T)> + '_, OverflowError>
+    where
+        T: Num + CheckedAdd + CheckedSub,
+    {
+        let length = |u: &Unit<T>| u.high().checked_sub(&u.l)?.checked_add(&T::one());
+        if !self.0.iter().all(|u| length(u).is_some()) {
+            return Err(OverflowError);
+        }
+        Ok(self
+            .0
+            .iter()
+            .map(move |u| (u.l.clone(), length(u).expect("checked above"))))
+    }
+    /// Builds a set from runs given by their low end and number of values,
+    /// in any order and possibly overlapping. A run of length zero adds
+    /// nothing. Fails if a run would end past the bounds of `T` or has a
+    /// negative length.
+    pub fn from_run_lengths(iter: impl IntoIterator<Item = (T, T)>) -> Result<Self, OverflowError>
+    where
+        T: Num + CheckedAdd,
+    {
+        let mut ranger = Ranger::new();
+        for (start, length) in iter {
+            if length.is_zero() {
+                continue;
+            }
+            if length < T::zero() {
+                return Err(OverflowError);
+            }
+            let end = start
+                .checked_add(&(length - T::one()))
+                .ok_or(OverflowError)?;
+            ranger.insert_range(start..=end);
+        }
+        Ok(ranger)
+    }
+    /// Returns the widest contiguous block of values stored in both sets,
+    /// preferring the lowest on ties. Walks both sets' runs once without
+    /// building their intersection.
+    pub fn longest_common_run(&self, other: &Ranger<T>) -> Option<Span<T>> {
+        let mut best: Option<(u128, &T, &T)> = None;
+        let (mut a, mut b) = (self.0.iter().peekable(), other.0.iter().peekable());
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let l = core::cmp::max(&x.l, &y.l);
+            let h = core::cmp::min(x.high(), y.high());
+            if l <= h {
+                let w = T::distance(l, h).unwrap_or(u128::MAX);
+                if best.is_none_or(|(bw, _, _)| w > bw) {
+                    best = Some((w, l, h));
+                }
+            }
+            // the run ending first cannot overlap anything further along
+            if x.high() < y.high() {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        best.map(|(_, l, h)| Span::new_unchecked(l.clone(), h.clone()))
+    }
+    /// Expands the set into a `BTreeSet` of its values, or returns `None`
+    /// without allocating if it holds more than `max_len` of them
+    pub fn checked_to_btree_set(&self, max_len: u128) -> Option<BTreeSet<T>> {
+        (self.len() <= max_len).then(|| self.0.iter().flat_map(Unit::values).collect())
+    }
+}
+
+/// Builds the runs in one pass over the already sorted values, without
+/// searching the tree for each
+#[cfg(feature = "alloc")]
+impl<T: Discrete> From<BTreeSet<T>> for Ranger<T> {
+    fn from(set: BTreeSet<T>) -> Self {
+        Ranger::from_sorted_iter(set).expect("a BTreeSet iterates in ascending order")
+    }
+}
+
+/// Expands every run into its values, so a set of a few wide runs can need
+/// far more memory than the `Ranger` did. See
+/// [`checked_to_btree_set`](Ranger::checked_to_btree_set) to cap that.
+#[cfg(feature = "alloc")]
+impl<T: Discrete> From<Ranger<T>> for BTreeSet<T> {
+    fn from(ranger: Ranger<T>) -> Self {
+        BTreeSet::from(&ranger)
+    }
+}
+
+/// Expands every run into its values, as the owned conversion does
+#[cfg(feature = "alloc")]
+impl<T: Discrete> From<&Ranger<T>> for BTreeSet<T> {
+    fn from(ranger: &Ranger<T>) -> Self {
+        ranger.0.iter().flat_map(Unit::values).collect()
+    }
+}
+
+/// Lists the runs in ascending order
+#[cfg(feature = "alloc")]
+impl<T: Clone> From<Ranger<T>> for Vec<RangeInclusive<T>> {
+    fn from(ranger: Ranger<T>) -> Self {
+        ranger
+            .0
+            .into_iter()
+            .map(|u| {
+                let h = u.h.unwrap_or_else(|| u.l.clone());
+                u.l..=h
+            })
+            .collect()
+    }
+}
+
+/// Lists the runs in ascending order as `(low, high)` pairs
+#[cfg(feature = "alloc")]
+impl<T: Clone> From<&Ranger<T>> for Vec<(T, T)> {
+    fn from(ranger: &Ranger<T>) -> Self {
+        ranger
+            .0
+            .iter()
+            .map(|u| (u.l.clone(), u.high().clone()))
+            .collect()
+    }
+}
+
+/// Reads `(low, high)` pairs in any order, joining those that overlap or
+/// touch. Fails with the index of the first pair whose low end lies above
+/// its high end.
+#[cfg(feature = "alloc")]
+impl<T: Discrete> TryFrom<Vec<(T, T)>> for Ranger<T> {
+    type Error = ReversedPair;
+
+    fn try_from(pairs: Vec<(T, T)>) -> Result<Self, Self::Error> {
+        if let Some(i) = pairs.iter().position(|(l, h)| l > h) {
+            return Err(ReversedPair(i));
+        }
+        let mut ranger = Ranger::new();
+        for (l, h) in pairs {
+            ranger.insert_range(l..=h);
+        }
+        Ok(ranger)
+    }
+}
+
+/// Builds a set of the one run `range` covers, or an empty set if `range` is
+/// empty because its start lies above its end.
+///
+/// ```
+/// use ranger::Ranger;
+///
+/// assert_eq!(Ranger::from(1..=100u32).to_string(), "1-100");
+/// assert!(Ranger::from(9..=3u8).is_empty());
+/// ```
+#[cfg(feature = "alloc")]
+impl<T: Discrete> From<RangeInclusive<T>> for Ranger<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut ranger = Ranger::new();
+        ranger.insert_range(range);
+        ranger
+    }
+}
+
+/// Builds a set of the values in the slice, in any order and with repeats
+/// allowed. Input that is already ascending takes the one-pass path of
+/// [`from_sorted_iter`](Ranger::from_sorted_iter).
+///
+/// ```
+/// use ranger::Ranger;
+///
+/// let values = vec![9, 3, 5, 4, 3];
+/// assert_eq!(Ranger::from(&values[..]).to_string(), "3-5,9");
+/// ```
+#[cfg(feature = "alloc")]
+impl<T: Discrete> From<&[T]> for Ranger<T> {
+    fn from(values: &[T]) -> Self {
+        if values.is_sorted() {
+            return Ranger::from_sorted_iter(values.iter().cloned())
+                .expect("the values were checked to be ascending");
+        }
+        let mut ranger = Ranger::new();
+        ranger.insert_all(values.iter().cloned());
+        ranger
+    }
+}
+
+/// Builds a set of the values in the array, as the slice conversion does.
+///
+/// ```
+/// use ranger::Ranger;
+///
+/// assert_eq!(Ranger::from([3, 5, 9]).to_string(), "3,5,9");
+/// assert_eq!(Ranger::from([2, 1, 0]).to_string(), "0-2");
+/// ```
+#[cfg(feature = "alloc")]
+impl<T: Discrete, const N: usize> From<[T; N]> for Ranger<T> {
+    fn from(values: [T; N]) -> Self {
+        Ranger::from(values.as_slice())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::{
+        format,
+        string::{String, ToString},
+    };
+    use libc_print::std_name::println;
+    use rand::{seq::SliceRandom, thread_rng, Rng, RngCore};
+
+    fn ranger_of<T: Discrete + Copy>(values: &[T]) -> Ranger<T> {
+        let mut ranger = Ranger::new();
+        for v in values {
+            ranger.insert(*v);
+        }
+        ranger
+    }
+
+    #[test]
+    fn it_works() {
+        let input_numbers: &mut [u8] = &mut [
+            0, 1, 2, 4, 6, 7, 8, 11, 12, 14, 15, 16, 17, 18, 19, 20, 21, 0, 22, 23, 24, 25, 27, 28,
+            29, 30, 31, 32, 33, 35, 36, 37, 38, 39,
+        ];
+        let mut ranger = Ranger::new();
+        for num in input_numbers.iter() {
+            ranger.insert(*num);
+        }
+        assert_eq!(ranger.to_string(), "0-2,4,6-8,11-12,14-25,27-33,35-39");
+        drop(ranger);
+        input_numbers.reverse();
+        let mut ranger = Ranger::new();
+        for num in input_numbers {
+            ranger.insert(*num);
+        }
+        assert_eq!(ranger.to_string(), "0-2,4,6-8,11-12,14-25,27-33,35-39");
+        drop(ranger);
+        let input_numbers: &[i8] = &[
+            -1, 33, 35, 23, 20, -128, 28, 0, 19, 18, 14, 25, 21, 127, 38, 6, 39, 27, 11, 17, 7, 12,
+            126, -126, 31, 15, 32, 4, 29, 36, 22, 1, 0, 37, 30, 8, 24, 16, 2, -127, 125,
+        ];
+        let mut ranger = Ranger::new();
+        for num in input_numbers {
+            ranger.insert(*num);
+        }
+        assert_eq!(
+            ranger.to_string(),
+            "-128--126,-1-2,4,6-8,11-12,14-25,27-33,35-39,125-127"
+        );
+        println!("{:?} -> {ranger}", input_numbers);
+        drop(ranger);
+
+        // Rule out edge cases
+        for _ in 0..10_000 {
+            let mut myvec = input_numbers.to_vec();
+            myvec.shuffle(&mut thread_rng());
+            let mut ranger = Ranger::new();
+            for num in myvec {
+                ranger.insert(num);
+            }
+            assert_eq!(
+                ranger.to_string(),
+                "-128--126,-1-2,4,6-8,11-12,14-25,27-33,35-39,125-127"
+            );
+        }
+    }
+
+    #[test]
+    fn remove_below_and_above() {
+        let values: &[i32] = &[0, 1, 2, 4, 6, 7, 8, 11, 12, 14, 15, 16];
+        let mut ranger = ranger_of(values);
+        ranger.remove_below(&7);
+        assert_eq!(ranger.to_string(), "7-8,11-12,14-16");
+        ranger.remove_below(&11);
+        assert_eq!(ranger.to_string(), "11-12,14-16");
+        ranger.remove_below(&-5);
+        assert_eq!(ranger.to_string(), "11-12,14-16");
+        ranger.remove_below(&100);
+        assert_eq!(ranger.to_string(), "");
+
+        let mut ranger = ranger_of(values);
+        ranger.remove_above(&7);
+        assert_eq!(ranger.to_string(), "0-2,4,6-7");
+        ranger.remove_above(&6);
+        assert_eq!(ranger.to_string(), "0-2,4,6");
+        ranger.remove_above(&100);
+        assert_eq!(ranger.to_string(), "0-2,4,6");
+        ranger.remove_above(&-1);
+        assert_eq!(ranger.to_string(), "");
+    }
+
+    #[test]
+    fn clamp_to() {
+        let values: &[u8] = &[0, 1, 2, 4, 6, 7, 8, 11, 12, 14, 15, 16];
+        let mut ranger = ranger_of(values);
+        ranger.clamp_to(1..=15);
+        assert_eq!(ranger.to_string(), "1-2,4,6-8,11-12,14-15");
+        ranger.clamp_to(0..=255);
+        assert_eq!(ranger.to_string(), "1-2,4,6-8,11-12,14-15");
+        ranger.clamp_to(6..=12);
+        assert_eq!(ranger.to_string(), "6-8,11-12");
+        ranger.clamp_to(7..=7);
+        assert_eq!(ranger.to_string(), "7");
+
+        let mut ranger = ranger_of(values);
+        ranger.clamp_to(9..=10);
+        assert_eq!(ranger.to_string(), "");
+
+        let mut ranger = ranger_of(&[10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20]);
+        ranger.clamp_to(13..=17);
+        assert_eq!(ranger.to_string(), "13-17");
+        #[allow(clippy::reversed_empty_ranges)]
+        ranger.clamp_to(15..=14);
+        assert_eq!(ranger.to_string(), "");
+    }
+
+    #[test]
+    fn invert_within() {
+        let values: &[u16] = &[2, 3, 4, 10, 11, 4000, 4094, 4095];
+        let ranger = ranger_of(values);
+        let free = ranger.invert_within(1..=4094);
+        assert_eq!(free.to_string(), "1,5-9,12-3999,4001-4093");
+        assert_eq!(ranger.invert_within(0..=1).to_string(), "0-1");
+        assert_eq!(ranger.invert_within(3..=4).to_string(), "");
+        assert_eq!(ranger.invert_within(5..=9).to_string(), "5-9");
+        assert_eq!(ranger.invert_within(4..=10).to_string(), "5-9");
+        assert_eq!(
+            ranger.invert_within(0..=u16::MAX).to_string(),
+            "0-1,5-9,12-3999,4001-4093,4096-65535"
+        );
+        assert_eq!(Ranger::<u16>::new().invert_within(7..=9).to_string(), "7-9");
+
+        let input_numbers: &[i8] = &[-128, -127, -1, 0, 1, 2, 4, 6, 7, 8, 11, 12, 14, 125, 127];
+        let ranger = ranger_of(input_numbers);
+        for (low, high) in [(-128, 127), (-127, 126), (3, 13), (5, 5), (6, 6)] {
+            let mut clamped = ranger.clone();
+            clamped.clamp_to(low..=high);
+            let twice = ranger.invert_within(low..=high).invert_within(low..=high);
+            assert_eq!(twice.to_string(), clamped.to_string());
+        }
+    }
+
+    #[test]
+    fn shift_by() {
+        let values: &[i8] = &[-100, -99, -1, 0, 1, 4, 6, 7, 8, 120];
+        let mut ranger = ranger_of(values);
+        ranger.shift_by(5).unwrap();
+        assert_eq!(ranger.to_string(), "-95--94,4-6,9,11-13,125");
+        ranger.shift_by(-5).unwrap();
+        assert_eq!(ranger.to_string(), "-100--99,-1-1,4,6-8,120");
+        ranger.shift_by(-28).unwrap();
+        assert_eq!(ranger.to_string(), "-128--127,-29--27,-24,-22--20,92");
+        assert_eq!(ranger.shift_by(-1), Err(OverflowError));
+        assert_eq!(ranger.to_string(), "-128--127,-29--27,-24,-22--20,92");
+
+        let mut ranger = ranger_of(&[0u16, 1, 2, 10, 65530, 65531, 65532]);
+        assert_eq!(ranger.shift_by(4), Err(OverflowError));
+        assert_eq!(ranger.to_string(), "0-2,10,65530-65532");
+        ranger.shift_by(3).unwrap();
+        assert_eq!(ranger.to_string(), "3-5,13,65533-65535");
+
+        let mut ranger = Ranger::<u8>::new();
+        ranger.shift_by(u8::MAX).unwrap();
+        assert_eq!(ranger.to_string(), "");
+    }
+
+    #[test]
+    fn shift_up_and_down() {
+        // unsigned sets move down too
+        let mut ranger = ranger_of(&[5u8, 6, 7, 20, 250]);
+        ranger.shift_down(5).unwrap();
+        assert_eq!(ranger.to_string(), "0-2,15,245");
+        assert_eq!(ranger.shift_down(1), Err(OverflowError));
+        assert_eq!(ranger.to_string(), "0-2,15,245");
+        // only the last high end overflows, and nothing moves
+        assert_eq!(ranger.shift_up(11), Err(OverflowError));
+        assert_eq!(ranger.to_string(), "0-2,15,245");
+        ranger.shift_up(10).unwrap();
+        assert_eq!(ranger.to_string(), "10-12,25,255");
+        // a round trip reaching the top of the type
+        let mut ranger: Ranger<i64> = "-9223372036854775808,-5-5,100".parse().unwrap();
+        let original = ranger.clone();
+        ranger.shift_up(i64::MAX as u128 - 100).unwrap();
+        assert_eq!(ranger.last_range(), Some(i64::MAX..=i64::MAX));
+        ranger.shift_down(i64::MAX as u128 - 100).unwrap();
+        assert_eq!(ranger, original);
+        assert_eq!(ranger.shift_up(u128::MAX), Err(OverflowError));
+        // chars step over the surrogates, and runs stay whole
+        let mut chars: Ranger<char> = Ranger::new();
+        chars.insert_range('\u{D7FE}'..='\u{D7FF}');
+        chars.shift_up(1).unwrap();
+        assert_eq!(chars.range_count(), 1);
+        assert!(chars.contains(&'\u{E000}'));
+        let mut empty = Ranger::<u8>::new();
+        assert_eq!(empty.shift_down(u128::MAX), Ok(()));
+    }
+
+    #[test]
+    fn run_lengths() {
+        let ranger: Ranger<u8> = "0,2-5,200-254".parse().unwrap();
+        let runs: Vec<_> = ranger.run_lengths().unwrap().collect();
+        assert_eq!(runs, [(0, 1), (2, 4), (200, 55)]);
+        assert_eq!(Ranger::from_run_lengths(runs).unwrap(), ranger);
+        // the widest run a u8 can count, and one wider
+        let ranger: Ranger<u8> = "1-255".parse().unwrap();
+        assert!(ranger.run_lengths().unwrap().eq([(1, 255)]));
+        let ranger: Ranger<u8> = "0-255".parse().unwrap();
+        assert!(ranger.run_lengths().is_err());
+        let ranger: Ranger<i8> = "-128--100,-1-125".parse().unwrap();
+        assert!(ranger.run_lengths().unwrap().eq([(-128, 29), (-1, 127)]));
+        let ranger: Ranger<i8> = "-1-126".parse().unwrap();
+        assert!(ranger.run_lengths().is_err());
+        assert!(Ranger::<u64>::new().run_lengths().unwrap().eq([]));
+
+        // unordered, overlapping and empty runs
+        let ranger = Ranger::from_run_lengths([(50u16, 10), (3, 0), (55, 20), (1, 2)]).unwrap();
+        assert_eq!(ranger.to_string(), "1-2,50-74");
+        let ranger = Ranger::from_run_lengths([(255u8, 1), (0, 255)]).unwrap();
+        assert_eq!(ranger.to_string(), "0-255");
+        assert_eq!(Ranger::from_run_lengths([(255u8, 2)]), Err(OverflowError));
+        assert_eq!(Ranger::from_run_lengths([(127i8, 0)]), Ok(Ranger::new()));
+        assert_eq!(Ranger::from_run_lengths([(5i8, -1)]), Err(OverflowError));
+        assert_eq!(
+            Ranger::from_run_lengths([(-128i8, 127), (0, 127)]).map(|r| r.to_string()),
+            Ok("-128--2,0-126".to_string())
+        );
+
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let mut ranger = Ranger::<i32>::new();
+            for _ in 0..rng.gen_range(0..20) {
+                let l = rng.gen_range(-1000..1000);
+                ranger.insert_range(l..=l + rng.gen_range(0..50));
+            }
+            let runs = ranger.run_lengths().unwrap();
+            assert_eq!(Ranger::from_run_lengths(runs).unwrap(), ranger);
+        }
+    }
+
+    #[test]
+    fn insert_range() {
+        let mut ranger = ranger_of(&[0u8, 1, 2, 4, 6, 7, 8, 11, 12, 14, 20, 255]);
+        assert_eq!(ranger.insert_range(6..=8), 0);
+        assert_eq!(ranger.insert_range(7..=7), 0);
+        assert_eq!(ranger.insert_range(16..=17), 2);
+        assert_eq!(ranger.to_string(), "0-2,4,6-8,11-12,14,16-17,20,255");
+        assert_eq!(ranger.insert_range(3..=3), 1);
+        assert_eq!(ranger.to_string(), "0-4,6-8,11-12,14,16-17,20,255");
+        assert_eq!(ranger.insert_range(10..=15), 3);
+        assert_eq!(ranger.to_string(), "0-4,6-8,10-17,20,255");
+        assert_eq!(ranger.insert_range(7..=19), 3);
+        assert_eq!(ranger.to_string(), "0-4,6-20,255");
+        assert_eq!(ranger.insert_range(250..=254), 5);
+        assert_eq!(ranger.to_string(), "0-4,6-20,250-255");
+        assert_eq!(ranger.insert_range(0..=255), 230);
+        assert_eq!(ranger.to_string(), "0-255");
+        assert_eq!(ranger.len(), 256);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 9..=3;
+        assert_eq!(Ranger::<u8>::new().insert_range(reversed), 0);
+
+        let mut ranger = Ranger::<i128>::new();
+        assert_eq!(ranger.insert_range(i128::MIN..=-1), 1 << 127);
+        assert_eq!(ranger.insert_range(1..=i128::MAX), (1 << 127) - 1);
+        assert_eq!(ranger.len(), u128::MAX);
+        assert_eq!(ranger.insert_range(0..=0), 1);
+        assert_eq!(ranger.len(), u128::MAX);
+    }
+
+    /// Holds at most `N` bytes, failing any write that would go past them
+    struct Fixed<const N: usize> {
+        buf: [u8; N],
+        len: usize,
+    }
+
+    impl<const N: usize> fmt::Write for Fixed<N> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > N {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    impl<const N: usize> Fixed<N> {
+        fn new() -> Self {
+            Fixed {
+                buf: [0; N],
+                len: 0,
+            }
+        }
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.buf[..self.len]).unwrap()
+        }
+    }
+
+    #[test]
+    fn display_truncated() {
+        let ranger: Ranger<i32> = "-10--1,3,5-6,10-19,30".parse().unwrap();
+        let shown = |max| ranger.display_truncated(max).to_string();
+        assert_eq!(shown(0), "\u{2026} (+5 ranges, 24 values)");
+        assert_eq!(shown(1), "-10--1,\u{2026} (+4 ranges, 14 values)");
+        assert_eq!(shown(3), "-10--1,3,5-6,\u{2026} (+2 ranges, 11 values)");
+        assert_eq!(shown(4), "-10--1,3,5-6,10-19,\u{2026} (+1 range, 1 value)");
+        // exactly as many runs as allowed, or fewer
+        for max in [5, 6, usize::MAX] {
+            assert_eq!(shown(max), ranger.to_string());
+        }
+        let empty = Ranger::<u8>::new();
+        assert_eq!(empty.display_truncated(0).to_string(), "");
+
+        // counts of wide runs, saturating past u128
+        let wide: Ranger<u128> = "0,2-340282366920938463463374607431768211455"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            wide.display_truncated(1).to_string(),
+            "0,\u{2026} (+1 range, 340282366920938463463374607431768211454 values)"
+        );
+        let mut widest = Ranger::from(i128::MIN..=-1);
+        widest.insert_range(1..=i128::MAX);
+        assert_eq!(
+            widest.display_truncated(0).to_string(),
+            "\u{2026} (+2 ranges, 340282366920938463463374607431768211455 values)"
+        );
+        let full = Ranger::from(i128::MIN..=i128::MAX);
+        assert_eq!(
+            full.display_truncated(0).to_string(),
+            "\u{2026} (+1 range, 340282366920938463463374607431768211455 values)"
+        );
+    }
+
+    #[test]
+    fn display_len() {
+        fn check<T: Discrete + Display + ToPrimitive>(ranger: &Ranger<T>) {
+            let shown = ranger.to_string();
+            assert_eq!(ranger.display_len(), shown.len(), "{shown}");
+            let opts = FormatOptions {
+                separator: "..",
+                delimiter: ", ",
+            };
+            let mut custom = String::new();
+            ranger.write_to(&mut custom, &opts).unwrap();
+            assert_eq!(ranger.display_len_with(&opts), custom.len(), "{custom}");
+        }
+        fn random<T: Discrete + Display + ToPrimitive>(
+            rng: &mut impl Rng,
+            value: impl Fn(&mut dyn RngCore) -> T,
+        ) {
+            for _ in 0..200 {
+                let mut ranger = Ranger::new();
+                for _ in 0..rng.gen_range(0..30) {
+                    let (a, b) = (value(rng), value(rng));
+                    let (l, h) = if a <= b { (a, b) } else { (b, a) };
+                    if rng.gen() {
+                        ranger.insert(l);
+                    } else {
+                        ranger.insert_range(l..=h);
+                    }
+                }
+                check(&ranger);
+            }
+        }
+        let mut rng = thread_rng();
+        random(&mut rng, |r| r.gen::<u8>());
+        random(&mut rng, |r| r.gen::<i16>() >> r.gen_range(0..16));
+        random(&mut rng, |r| r.gen::<i64>() >> r.gen_range(0..64));
+        random(&mut rng, |r| r.gen::<u128>() >> r.gen_range(0..128));
+        random(&mut rng, |r| r.gen::<i128>() >> r.gen_range(0..128));
+        check(&Ranger::<i32>::new());
+        check(&Ranger::from(i128::MIN..=i128::MAX));
+        check(&Ranger::from(0..=u128::MAX));
+        check(&"-9--1,0,9-10,99-100,-10".parse::<Ranger<i8>>().unwrap());
+    }
+
+    #[test]
+    fn write_to() {
+        let ranger: Ranger<u16> = "0-2,4,6-8,1000-65535".parse().unwrap();
+        let mut out = Fixed::<32>::new();
+        ranger.write_to(&mut out, &FormatOptions::new()).unwrap();
+        assert_eq!(out.as_str(), ranger.to_string());
+        // one byte short of the whole form
+        let mut out = Fixed::<19>::new();
+        assert_eq!(
+            ranger.write_to(&mut out, &FormatOptions::default()),
+            Err(fmt::Error)
+        );
+        assert!(ranger.to_string().starts_with(out.as_str()));
+        let mut out = Fixed::<0>::new();
+        Ranger::<u16>::new()
+            .write_to(&mut out, &FormatOptions::new())
+            .unwrap();
+        assert_eq!(out.as_str(), "");
+        // a reused buffer, and the options display_with shares
+        let mut out = alloc::string::String::new();
+        for separator in ["-", "..", " to "] {
+            out.clear();
+            let opts = FormatOptions {
+                separator,
+                ..FormatOptions::new()
+            };
+            ranger.write_to(&mut out, &opts).unwrap();
+            assert_eq!(out, ranger.display_with(separator).to_string());
+        }
+        let opts = FormatOptions {
+            separator: "..",
+            delimiter: " | ",
+        };
+        let mut out = Fixed::<64>::new();
+        ranger.write_to(&mut out, &opts).unwrap();
+        assert_eq!(out.as_str(), "0..2 | 4 | 6..8 | 1000..65535");
+    }
+
+    #[test]
+    fn first_and_last_range() {
+        let mut ranger = Ranger::<i8>::new();
+        assert_eq!((ranger.first_range(), ranger.last_range()), (None, None));
+        ranger.insert(-3);
+        assert_eq!(ranger.first_range(), Some(-3..=-3));
+        assert_eq!(ranger.last_range(), Some(-3..=-3));
+        ranger.insert_range(-2..=5);
+        assert_eq!(ranger.first_range(), Some(-3..=5));
+        assert_eq!(ranger.last_range(), ranger.first_range());
+        ranger.insert_range(10..=127);
+        ranger.insert(-128);
+        assert_eq!(ranger.first_range(), Some(-128..=-128));
+        assert_eq!(ranger.last_range(), Some(10..=127));
+        assert_eq!(ranger.range_count(), 3);
+    }
+
+    #[test]
+    fn nth_range() {
+        let ranger: Ranger<u16> = "1-3,5,9-12,20,40-49".parse().unwrap();
+        let all: Vec<_> = ranger.ranges().map(RangeInclusive::from).collect();
+        assert_eq!(ranger.nth_range(0), Some(1..=3));
+        assert_eq!(ranger.nth_range(4), Some(40..=49));
+        assert_eq!(ranger.nth_range(5), None);
+        assert_eq!(ranger.nth_range(usize::MAX), None);
+        for (i, r) in all.iter().enumerate() {
+            assert_eq!(ranger.nth_range(i).as_ref(), Some(r));
+        }
+        for start in 0..7 {
+            for len in 0..7 {
+                let page: Vec<_> = ranger.ranges_page(start, len).collect();
+                let end = all.len().min(start + len);
+                assert_eq!(page, all.get(start..end).unwrap_or_default());
+            }
+        }
+        assert_eq!(ranger.ranges_page(usize::MAX, usize::MAX).count(), 0);
+        assert_eq!(Ranger::<u16>::new().nth_range(0), None);
+    }
+
+    #[test]
+    fn get_or_insert_range() {
+        let mut ranger: Ranger<u8> = "2-4,7-9,20-29".parse().unwrap();
+        // already covered, so nothing changes
+        assert_eq!(ranger.get_or_insert_range(21..=23), 20..=29);
+        assert_eq!(ranger.get_or_insert_range(7..=7), 7..=9);
+        assert_eq!(ranger.to_string(), "2-4,7-9,20-29");
+        // touching and partly overlapping
+        assert_eq!(ranger.get_or_insert_range(5..=6), 2..=9);
+        assert_eq!(ranger.get_or_insert_range(25..=35), 20..=35);
+        assert_eq!(ranger.to_string(), "2-9,20-35");
+        // bridging two runs
+        assert_eq!(ranger.get_or_insert_range(10..=19), 2..=35);
+        assert_eq!(ranger.get_or_insert_range(50..=50), 50..=50);
+        assert_eq!(ranger.to_string(), "2-35,50");
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 60..=40;
+        assert_eq!(ranger.get_or_insert_range(reversed.clone()), reversed);
+        assert_eq!(ranger.to_string(), "2-35,50");
+
+        assert_eq!(ranger.get_covering(&2), Some(2..=35));
+        assert_eq!(ranger.get_covering(&50), Some(50..=50));
+        assert_eq!(ranger.get_covering(&36), None);
+        assert_eq!(Ranger::<u8>::new().get_covering(&0), None);
+        let mut tolerant = Ranger::with_gap_tolerance(2u8);
+        tolerant.insert_range(0..=3);
+        assert_eq!(tolerant.get_or_insert_range(6..=8), 0..=8);
+    }
+
+    #[test]
+    fn map_ranges() {
+        let ranger = ranger_of(&[0u32, 1, 3, 4, 6, 10]);
+        assert_eq!(
+            ranger
+                .clone()
+                .map_ranges(|l, h| (l * 2, h * 2 + 3))
+                .to_string(),
+            "0-15,20-23"
+        );
+        assert_eq!(
+            ranger.clone().map_ranges(|l, h| (l, h)).to_string(),
+            ranger.to_string()
+        );
+        assert_eq!(
+            ranger.clone().map_ranges(|l, h| (h, l)).to_string(),
+            ranger.to_string()
+        );
+        assert_eq!(ranger.map_ranges(|_, _| (7, 7)).to_string(), "7");
+    }
+
+    #[test]
+    fn map_values() {
+        let ranger = ranger_of(&[995u32, 996, 997, 998, 999, 1000, 1001, 1002, 1998, 2003]);
+        assert_eq!(ranger.map_values(|v| v % 1000).to_string(), "0-3,995-999");
+        assert_eq!(
+            ranger.map_values(|v| v + 7).to_string(),
+            ranger.clone().map_ranges(|l, h| (l + 7, h + 7)).to_string()
+        );
+        assert_eq!(ranger.map_values(|v| v / 4).to_string(), "248-250,499-500");
+        let ranger = ranger_of(&[u8::MAX - 1, u8::MAX]);
+        assert_eq!(ranger.map_values(|v| v - 10).to_string(), "244-245");
+        assert_eq!(Ranger::<u8>::new().map_values(|v| *v).to_string(), "");
+    }
+
+    #[test]
+    fn insert_all() {
+        let mut ranger = ranger_of(&[0i16, 1, 2, 4, 6, 7, 8]);
+        assert_eq!(ranger.insert_all([0, 2, 4, 6, 8]), 0);
+        assert_eq!(ranger.len(), 7);
+        assert_eq!(ranger.insert_all(100..110), 10);
+        assert_eq!(ranger.len(), 17);
+        assert_eq!(ranger.insert_all([-5, 3, -5, 3, 4, 9, 200, 9]), 4);
+        assert_eq!(ranger.len(), 21);
+        assert_eq!(ranger.to_string(), "-5,0-4,6-9,100-109,200");
+
+        let input_numbers: &mut [i16] = &mut [-300, 5, 5, 17, 18, 900, -301, 5, 16, 20, 1000];
+        for _ in 0..100 {
+            input_numbers.shuffle(&mut thread_rng());
+            let mut ranger = ranger_of(&[-299i16, 15, 16, 17, 19]);
+            let before = ranger.len();
+            let added = ranger.insert_all(input_numbers.iter().copied());
+            assert_eq!(added, 7);
+            assert_eq!(ranger.len() - before, added);
+        }
+        assert!(Ranger::<u8>::new().is_empty());
+    }
+
+    #[test]
+    fn remove_all() {
+        let mut ranger = ranger_of(&[0u32, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 20, 21, 22, 40]);
+        assert!(ranger.remove(&5));
+        assert!(!ranger.remove(&5));
+        assert!(!ranger.remove(&100));
+        assert!(ranger.remove(&40));
+        assert!(ranger.remove(&20));
+        assert_eq!(ranger.to_string(), "0-4,6-10,21-22");
+        assert_eq!(ranger.remove_all([1, 3, 3, 8, 15, 16, 22, 1, 50, 0]), 5);
+        assert_eq!(ranger.to_string(), "2,4,6-7,9-10,21");
+        assert_eq!(ranger.remove_all([5, 11, 12, 20, 22]), 0);
+        assert_eq!(ranger.to_string(), "2,4,6-7,9-10,21");
+        assert_eq!(ranger.remove_all([21, 10, 9, 7, 6, 4, 2]), 7);
+        assert!(ranger.is_empty());
+
+        let input_numbers: &mut [u32] = &mut [0, 1, 2, 4, 6, 7, 8, 11, 12, 14, 15, 16];
+        for _ in 0..100 {
+            input_numbers.shuffle(&mut thread_rng());
+            let mut batch = ranger_of(&[0u32, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+            let mut single = batch.clone();
+            let removed = batch.remove_all(input_numbers.iter().copied());
+            let expected = input_numbers.iter().filter(|v| single.remove(v)).count();
+            assert_eq!(removed, expected as u128);
+            assert_eq!(batch.to_string(), "3,5,9-10");
+            assert_eq!(single.to_string(), "3,5,9-10");
+        }
+    }
+
+    #[test]
+    fn contains_all_and_any() {
+        let ranger = ranger_of(&[0i64, 1, 2, 4, 6, 7, 8, 11, 12]);
+        let mut pulled = 0;
+        let counted = [0, 1, 3, 4, 5].iter().copied().inspect(|_| pulled += 1);
+        assert!(!ranger.contains_all(counted));
+        assert_eq!(pulled, 3);
+        let mut pulled = 0;
+        let counted = [3, 5, 6, 9, 10].iter().copied().inspect(|_| pulled += 1);
+        assert!(ranger.contains_any(counted));
+        assert_eq!(pulled, 3);
+        assert!(ranger.contains_all([12, 11, 8, 7, 6, 0]));
+        assert!(!ranger.contains_any([-1, 3, 5, 9, 10, 13]));
+        assert!(ranger.contains_all([]));
+        assert!(!ranger.contains_any([]));
+
+        assert!(ranger.contains_all_ranges([0..=2, 4..=4, 6..=8, 11..=12]));
+        assert!(!ranger.contains_all_ranges([0..=2, 6..=9]));
+        assert!(!ranger.contains_all_ranges([-1..=2]));
+        assert!(ranger.contains_any_ranges([9..=10, 12..=20]));
+        assert!(ranger.contains_any_ranges([-5..=0]));
+        assert!(!ranger.contains_any_ranges([3..=3, 5..=5, 9..=10, 13..=100]));
+        assert!(ranger.contains_all_ranges([]));
+        assert!(!ranger.contains_any_ranges([]));
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 8..=6;
+        assert!(ranger.contains_range(&empty));
+        assert!(!ranger.overlaps_range(&empty));
+    }
+
+    #[test]
+    fn merge_gaps_smaller_than() {
+        let mut ranger = Ranger::<u16>::new();
+        ranger.insert_range(100..=149);
+        ranger.insert_range(151..=200);
+        ranger.insert_range(203..=210);
+        ranger.insert_range(213..=213);
+        ranger.insert_range(217..=220);
+        ranger.insert_range(300..=300);
+        let original = ranger.clone();
+        assert_eq!(ranger.merge_gaps_smaller_than(0), 0);
+        assert_eq!(ranger.to_string(), original.to_string());
+        assert_eq!(ranger.merge_gaps_smaller_than(1), 1);
+        assert_eq!(ranger.to_string(), "100-200,203-210,213,217-220,300");
+        assert_eq!(ranger.merge_gaps_smaller_than(2), 4);
+        assert_eq!(ranger.to_string(), "100-213,217-220,300");
+
+        let mut ranger = original.clone();
+        assert_eq!(ranger.merge_gaps_smaller_than(3), 8);
+        assert_eq!(ranger.to_string(), "100-220,300");
+        let mut ranger = original;
+        assert_eq!(ranger.merge_gaps_smaller_than(u16::MAX), 87);
+        assert_eq!(ranger.to_string(), "100-300");
+
+        let mut ranger = Ranger::<i8>::new();
+        ranger.insert_range(-128..=-5);
+        ranger.insert_range(120..=127);
+        assert_eq!(ranger.merge_gaps_smaller_than(-1), 0);
+        assert_eq!(ranger.merge_gaps_smaller_than(123), 0);
+        assert_eq!(ranger.merge_gaps_smaller_than(124), 124);
+        assert_eq!(ranger.to_string(), "-128-127");
+        assert_eq!(Ranger::<i8>::new().merge_gaps_smaller_than(5), 0);
+    }
+
+    #[test]
+    fn cluster() {
+        let ranger: Ranger<u8> = "0-9,12-15,17,30-40,45,200,255".parse().unwrap();
+        let strings = |clusters: Vec<Ranger<u8>>| -> Vec<alloc::string::String> {
+            clusters.iter().map(|c| c.to_string()).collect()
+        };
+        assert_eq!(
+            strings(ranger.cluster(4)),
+            ["0-9,12-15,17", "30-40,45", "200", "255"]
+        );
+        // gaps of exactly min_gap stay together
+        assert_eq!(
+            strings(ranger.cluster(12)),
+            ["0-9,12-15,17,30-40,45", "200", "255"]
+        );
+        assert_eq!(ranger.cluster(0).len(), ranger.range_count());
+        assert_eq!(ranger.cluster(u8::MAX), core::slice::from_ref(&ranger));
+        for min_gap in 0..=u8::MAX {
+            let mut joined = Ranger::new();
+            for c in ranger.cluster(min_gap) {
+                assert!(!c.is_empty());
+                for s in c.ranges() {
+                    joined.insert_range(s.into());
+                }
+            }
+            assert_eq!(joined, ranger);
+        }
+        let single: Ranger<i8> = "-128-127".parse().unwrap();
+        assert_eq!(single.cluster(0), core::slice::from_ref(&single));
+        assert_eq!(single.cluster(-5), core::slice::from_ref(&single));
+        assert!(Ranger::<u8>::new().cluster(3).is_empty());
+        // widest possible gap, between the ends of the type
+        let ends = Ranger::from([0, u128::MAX]);
+        assert_eq!(ends.cluster(u128::MAX - 1).len(), 1);
+        assert_eq!(ends.cluster(u128::MAX - 2).len(), 2);
+        let ends = Ranger::from([i128::MIN, i128::MAX]);
+        assert_eq!(ends.cluster(i128::MAX).len(), 2);
+    }
+
+    #[test]
+    fn gap_tolerance() {
+        let input_numbers: &mut [u32] =
+            &mut [0, 1, 2, 4, 6, 7, 8, 11, 12, 14, 18, 19, 20, 24, 28, 31];
+        for _ in 0..1000 {
+            input_numbers.shuffle(&mut thread_rng());
+            let mut exact = Ranger::with_gap_tolerance(0);
+            let mut tolerant = Ranger::with_gap_tolerance(2);
+            for num in input_numbers.iter() {
+                exact.insert(*num);
+                tolerant.insert(*num);
+            }
+            assert_eq!(exact.to_string(), "0-2,4,6-8,11-12,14,18-20,24,28,31");
+            assert_eq!(tolerant.to_string(), "0-14,18-20,24,28-31");
+            assert_eq!(tolerant.len(), 23);
+            assert!(tolerant.contains(&13) && tolerant.contains(&29));
+            assert!(!tolerant.contains(&16) && !tolerant.contains(&25));
+        }
+
+        let mut tolerant = Ranger::with_gap_tolerance(2);
+        assert_eq!(tolerant.insert_range(10..=20), 11);
+        assert_eq!(tolerant.insert_range(23..=30), 10);
+        assert_eq!(tolerant.to_string(), "10-30");
+        assert_eq!(tolerant.insert_range(0..=6), 7);
+        assert_eq!(tolerant.to_string(), "0-6,10-30");
+        assert_eq!(tolerant.insert_range(33..=33), 3);
+        assert_eq!(tolerant.insert_range(8..=8), 3);
+        assert_eq!(tolerant.to_string(), "0-33");
+        assert!(tolerant.remove(&15));
+        assert_eq!(tolerant.to_string(), "0-14,16-33");
+    }
+
+    #[test]
+    fn summarize() {
+        let mut ranger = Ranger::<i32>::new();
+        ranger.insert_range(0..=9);
+        ranger.insert_range(15..=19);
+        ranger.insert_range(22..=30);
+        ranger.insert_range(40..=40);
+        // gaps of 5, 2 and 9 values
+        assert_eq!(ranger.summarize(4).to_string(), ranger.to_string());
+        assert_eq!(ranger.summarize(10).to_string(), ranger.to_string());
+        assert_eq!(ranger.summarize(3).to_string(), "0-9,15-30,40");
+        assert_eq!(ranger.summarize(2).to_string(), "0-30,40");
+        assert_eq!(ranger.summarize(1).to_string(), "0-40");
+        assert_eq!(ranger.summarize(0).to_string(), "0-40");
+
+        let mut summary = ranger.clone();
+        assert_eq!(summary.summarize_in_place(2), 7);
+        assert_eq!(summary.summarize_in_place(2), 0);
+        assert_eq!(summary.summarize_in_place(1), 9);
+        assert_eq!(summary.len(), 41);
+
+        // equal gaps are merged lowest first
+        let ranger = ranger_of(&[0i32, 2, 4, 6]);
+        assert_eq!(ranger.summarize(3).to_string(), "0-2,4,6");
+        assert_eq!(ranger.summarize(2).to_string(), "0-4,6");
+        assert_eq!(Ranger::<i32>::new().summarize(1).to_string(), "");
+    }
+
+    #[test]
+    fn range_bounds() {
+        let bounds = |v: i8| [Bound::Included(v), Bound::Excluded(v), Bound::Unbounded];
+        let within = |b: &(Bound<i8>, Bound<i8>), v: i8| b.contains(&v);
+        let mut full = Ranger::<i8>::new();
+        full.insert_range(-128..=127);
+        let mut sparse = Ranger::<i8>::new();
+        for v in (-128..=127).step_by(3) {
+            sparse.insert(v);
+        }
+        for (low, high) in [
+            (-3, 4),
+            (-128, 127),
+            (5, 5),
+            (5, 6),
+            (127, -128),
+            (-128, -128),
+            (127, 127),
+        ] {
+            for start in bounds(low) {
+                for end in bounds(high) {
+                    let b = (start, end);
+                    let mut inserted = Ranger::<i8>::new();
+                    let added = inserted.insert_bounds(b);
+                    let mut removed = full.clone();
+                    let taken = removed.remove_range(b);
+                    let mut thinned = sparse.clone();
+                    let thinned_count = thinned.remove_range(b);
+                    let mut clamped = sparse.clone();
+                    clamped.clamp_to(b);
+                    let expected = (-128..=127).filter(|v| within(&b, *v)).count() as u128;
+                    assert_eq!(added, expected, "{b:?}");
+                    assert_eq!(taken, expected, "{b:?}");
+                    assert_eq!(inserted.len(), expected, "{b:?}");
+                    assert_eq!(removed.len(), 256 - expected, "{b:?}");
+                    for v in -128..=127 {
+                        assert_eq!(inserted.contains(&v), within(&b, v), "{b:?} {v}");
+                        assert_eq!(removed.contains(&v), !within(&b, v), "{b:?} {v}");
+                        assert_eq!(
+                            clamped.contains(&v),
+                            sparse.contains(&v) && within(&b, v),
+                            "{b:?} {v}"
+                        );
+                        assert_eq!(
+                            thinned.contains(&v),
+                            sparse.contains(&v) && !within(&b, v),
+                            "{b:?} {v}"
+                        );
+                    }
+                    assert_eq!(thinned.len() + thinned_count, sparse.len());
+                }
+            }
+        }
+
+        let mut ranger = ranger_of(&[0i8, 1, 2, 4, 6, 7, 8, 11, 12]);
+        assert_eq!(ranger.remove_range(1..8), 5);
+        assert_eq!(ranger.to_string(), "0,8,11-12");
+        assert_eq!(ranger.remove_range(12..), 1);
+        assert_eq!(ranger.remove_range(..=0), 1);
+        assert_eq!(ranger.to_string(), "8,11");
+        assert_eq!(ranger.remove_range(..), 2);
+        assert!(ranger.is_empty());
+    }
+
+    #[test]
+    fn max_ranges() {
+        let mut ranger = Ranger::<u64>::with_max_ranges(4);
+        assert_eq!(ranger.try_extend([0, 2, 4, 6]), Ok(4));
+        assert_eq!(ranger.range_count(), 4);
+        assert_eq!(ranger.try_insert(8), Err(CapacityExceeded));
+        assert_eq!(ranger.try_insert_range(10..=20), Err(CapacityExceeded));
+        assert_eq!(ranger.to_string(), "0,2,4,6");
+        assert_eq!(ranger.try_insert(6), Ok(false));
+        assert_eq!(ranger.try_insert(7), Ok(true));
+        assert_eq!(ranger.try_insert_range(9..=12), Err(CapacityExceeded));
+        assert_eq!(ranger.try_insert_range(8..=12), Ok(5));
+        assert_eq!(ranger.range_count(), 4);
+        assert_eq!(ranger.try_insert(1), Ok(true));
+        assert_eq!(ranger.range_count(), 3);
+        assert_eq!(ranger.to_string(), "0-2,4,6-12");
+        assert_eq!(ranger.try_extend([20, 30, 3]), Err(CapacityExceeded));
+        assert_eq!(ranger.to_string(), "0-2,4,6-12,20");
+
+        let mut ranger = Ranger::<u64>::with_max_ranges(0);
+        assert_eq!(ranger.try_insert(1), Err(CapacityExceeded));
+        assert!(ranger.is_empty());
+        let mut ranger = Ranger::<u64>::new();
+        assert_eq!(ranger.try_extend((0..100).map(|v| v * 2)), Ok(100));
+    }
+
+    #[test]
+    fn first_n_and_last_n() {
+        let mut ranger = Ranger::<i16>::new();
+        ranger.insert_range(-300..=-291);
+        ranger.insert_range(0..=2);
+        ranger.insert_range(5..=5);
+        ranger.insert_range(10..=1000);
+        assert_eq!(ranger.first_n(0).to_string(), "");
+        assert_eq!(ranger.last_n(0).to_string(), "");
+        assert_eq!(ranger.first_n(1).to_string(), "-300");
+        assert_eq!(ranger.last_n(1).to_string(), "1000");
+        assert_eq!(ranger.first_n(10).to_string(), "-300--291");
+        assert_eq!(ranger.first_n(13).to_string(), "-300--291,0-2");
+        assert_eq!(ranger.first_n(15).to_string(), "-300--291,0-2,5,10");
+        assert_eq!(ranger.last_n(991).to_string(), "10-1000");
+        assert_eq!(ranger.last_n(993).to_string(), "2,5,10-1000");
+        assert_eq!(ranger.last_n(1000).to_string(), "-295--291,0-2,5,10-1000");
+        assert_eq!(ranger.first_n(5000).to_string(), ranger.to_string());
+        assert_eq!(ranger.last_n(u128::MAX).to_string(), ranger.to_string());
+
+        let mut ranger = Ranger::<i128>::new();
+        ranger.insert_range(i128::MIN..=i128::MAX);
+        assert_eq!(
+            ranger.first_n(2).to_string(),
+            format!("{}-{}", i128::MIN, i128::MIN + 1)
+        );
+        assert_eq!(
+            ranger.last_n(1 << 127).to_string(),
+            format!("0-{}", i128::MAX)
+        );
+        assert_eq!(
+            ranger.first_n((1 << 127) + 1).to_string(),
+            format!("{}-0", i128::MIN)
+        );
+        assert_eq!(
+            ranger.first_n(u128::MAX).to_string(),
+            format!("{}-{}", i128::MIN, i128::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn split_evenly() {
+        let input_numbers: &[i8] = &[
+            -1, 33, 35, 23, 20, -128, 28, 0, 19, 18, 14, 25, 21, 127, 38, 6, 39, 27, 11, 17, 7, 12,
+            126, -126, 31, 15, 32, 4, 29, 36, 22, 1, 0, 37, 30, 8, 24, 16, 2, -127, 125,
+        ];
+        let ranger = ranger_of(input_numbers);
+        assert!(ranger.split_evenly(0).is_empty());
+        for k in [1, 2, 3, 7, 40, 41, 50] {
+            let shards = ranger.split_evenly(k);
+            assert_eq!(shards.len(), k);
+            let mut union = Ranger::new();
+            let mut previous_max = None;
+            for shard in shards.iter() {
+                let len = shard.len();
+                assert!(len == ranger.len() / k as u128 || len == ranger.len() / k as u128 + 1);
+                if let Some(first) = shard.0.first() {
+                    assert!(previous_max < Some(first.l));
+                    previous_max = Some(*shard.0.last().unwrap().high());
+                }
+                for u in shard.0.iter() {
+                    union.insert_range(u.l..=*u.high());
+                }
+            }
+            assert_eq!(union.to_string(), ranger.to_string());
+        }
+        let shards = ranger.split_evenly(3);
+        assert_eq!(shards[0].to_string(), "-128--126,-1-2,4,6-8,11-12,14");
+        assert_eq!(shards[1].to_string(), "15-25,27-28");
+        assert_eq!(shards[2].to_string(), "29-33,35-39,125-127");
+
+        let mut ranger = Ranger::<u64>::new();
+        ranger.insert_range(0..=u64::MAX);
+        let shards = ranger.split_evenly(3);
+        let third = (u64::MAX / 3) as u128;
+        assert_eq!(shards[0].to_string(), format!("0-{}", third));
+        assert_eq!(
+            shards[1].to_string(),
+            format!("{}-{}", third + 1, third * 2)
+        );
+        assert_eq!(
+            shards[2].to_string(),
+            format!("{}-{}", third * 2 + 1, u64::MAX)
+        );
+        assert_eq!(
+            ranger.split_evenly(4).iter().map(|s| s.len()).sum::<u128>(),
+            1 << 64
+        );
+    }
+
+    #[test]
+    fn longest_common_run() {
+        let mut a = Ranger::<i32>::new();
+        let mut b = Ranger::<i32>::new();
+        a.insert_range(0..=1000);
+        b.insert_range(-50..=10);
+        b.insert_range(400..=700);
+        b.insert_range(990..=2000);
+        assert_eq!(a.longest_common_run(&b), Span::new(400, 700));
+        assert_eq!(b.longest_common_run(&a), Span::new(400, 700));
+
+        let a = ranger_of(&[1, 2, 3, 7, 8, 9, 20]);
+        let b = ranger_of(&[2, 3, 4, 8, 9, 10, 20]);
+        assert_eq!(a.longest_common_run(&b), Span::new(2, 3));
+        let c = ranger_of(&[5, 6, 11, 12, 13]);
+        assert_eq!(a.longest_common_run(&c), None);
+        assert_eq!(a.longest_common_run(&Ranger::new()), None);
+
+        let mut rng = thread_rng();
+        let mut values: Vec<i8> = (-60..60).collect();
+        for _ in 0..50 {
+            values.shuffle(&mut rng);
+            let a = ranger_of(&values[..70]);
+            values.shuffle(&mut rng);
+            let b = ranger_of(&values[..70]);
+            // brute force: widest run of consecutive values found in both
+            let mut expected: Option<RangeInclusive<i8>> = None;
+            let mut start = None;
+            for v in -60i8..=60 {
+                if v < 60 && a.contains(&v) && b.contains(&v) {
+                    start.get_or_insert(v);
+                } else if let Some(s) = start.take() {
+                    let w = (v - 1 - s) as u128;
+                    if expected
+                        .as_ref()
+                        .is_none_or(|e| w > (e.end() - e.start()) as u128)
+                    {
+                        expected = Some(s..=v - 1);
+                    }
+                }
+            }
+            assert_eq!(a.longest_common_run(&b).map(RangeInclusive::from), expected);
+        }
+    }
+
+    #[test]
+    fn equality() {
+        let input_numbers: &[i8] = &[
+            -1, 33, 35, 23, 20, -128, 28, 0, 19, 18, 14, 25, 21, 127, 38, 6, 39, 27, 11, 17, 7, 12,
+            126, -126, 31, 15, 32, 4, 29, 36, 22, 1, 0, 37, 30, 8, 24, 16, 2, -127, 125,
+        ];
+        let expected = ranger_of(input_numbers);
+        let mut myvec = input_numbers.to_vec();
+        for _ in 0..100 {
+            myvec.shuffle(&mut thread_rng());
+            assert_eq!(ranger_of(&myvec), expected);
+        }
+        let mut other = expected.clone();
+        other.remove(&39);
+        assert_ne!(other, expected);
+        other.insert(40);
+        assert_ne!(other, expected);
+        other.remove(&40);
+        other.insert(39);
+        assert_eq!(other, expected);
+        let mut tolerant = Ranger::with_gap_tolerance(0);
+        tolerant.insert_all(input_numbers.iter().copied());
+        assert_eq!(tolerant, expected);
+        assert_ne!(Ranger::new(), expected);
+    }
+
+    #[test]
+    fn hash() {
+        let mut set = hashbrown::HashSet::new();
+        set.insert(ranger_of(&[1, 2, 3, 5, 9, 8, 7]));
+        set.insert(ranger_of(&[9, 8, 7, 5, 3, 2, 1]));
+        let mut built = Ranger::new();
+        built.insert_range(1..=3);
+        built.insert_range(7..=9);
+        built.insert(5);
+        set.insert(built);
+        let mut trimmed = ranger_of(&[0, 1, 2, 3, 5, 7, 8, 9, 10]);
+        trimmed.clamp_to(1..=9);
+        set.insert(trimmed);
+        assert_eq!(set.len(), 1);
+        set.insert(ranger_of(&[1, 2, 3, 5, 7, 8]));
+        set.insert(ranger_of(&[1, 2, 3, 4, 5, 7, 8, 9]));
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn ordering() {
+        let empty = Ranger::<i32>::new();
+        let a = ranger_of(&[1, 2, 3, 7]);
+        assert!(empty < a);
+        assert!(empty < ranger_of(&[i32::MIN]));
+        // shared prefix of runs, decided by the first differing run
+        assert!(ranger_of(&[1, 2, 3, 7]) < ranger_of(&[1, 2, 3, 8]));
+        assert!(ranger_of(&[1, 2, 3, 7]) < ranger_of(&[1, 2, 3, 7, 8]));
+        assert!(ranger_of(&[1, 2, 3, 7]) < ranger_of(&[1, 2, 3, 7, 9]));
+        assert!(ranger_of(&[1, 2, 3, 7, 9]) < ranger_of(&[1, 2, 3, 7, 8]));
+        assert!(ranger_of(&[1, 2]) < ranger_of(&[1, 2, 3]));
+        assert!(ranger_of(&[1, 2, 3]) < ranger_of(&[2]));
+        // not subset order: a superset may sort before
+        assert!(ranger_of(&[0, 5]) < ranger_of(&[5]));
+
+        let sets = [
+            empty.clone(),
+            a.clone(),
+            ranger_of(&[3, 2, 1, 7]),
+            ranger_of(&[1, 2, 3]),
+            ranger_of(&[-4, 9]),
+        ];
+        for x in sets.iter() {
+            for y in sets.iter() {
+                assert_eq!(x.cmp(y) == Ordering::Equal, x == y);
+                assert_eq!(x.cmp(y), y.cmp(x).reverse());
+            }
+        }
+        let mut map = alloc::collections::BTreeMap::new();
+        for (i, set) in sets.iter().enumerate() {
+            map.insert(set.clone(), i);
+        }
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.keys().next(), Some(&empty));
+    }
+
+    #[test]
+    fn minimal_bounds() {
+        // neither Display nor Default
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Ticket(u16);
+
+        impl Discrete for Ticket {
+            fn successor(&self) -> Option<Self> {
+                self.0.successor().map(Ticket)
+            }
+            fn predecessor(&self) -> Option<Self> {
+                self.0.predecessor().map(Ticket)
+            }
+            fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+                u16::distance(&lower.0, &upper.0)
+            }
+            fn forward(&self, steps: u128) -> Option<Self> {
+                self.0.forward(steps).map(Ticket)
+            }
+            fn backward(&self, steps: u128) -> Option<Self> {
+                self.0.backward(steps).map(Ticket)
+            }
+        }
+
+        let mut tickets = Ranger::default();
+        assert!(tickets.insert(Ticket(4)));
+        assert!(tickets.insert(Ticket(3)));
+        assert!(!tickets.insert(Ticket(4)));
+        tickets.insert_range(Ticket(9)..=Ticket(12));
+        assert!(tickets.contains(&Ticket(3)) && tickets.contains(&Ticket(10)));
+        assert!(!tickets.contains(&Ticket(5)));
+        assert_eq!(tickets.range_count(), 2);
+        assert!(tickets.remove(&Ticket(3)));
+        assert_eq!(
+            tickets,
+            ranger_of(&[Ticket(4), Ticket(9), Ticket(10), Ticket(11), Ticket(12)])
+        );
+    }
+
+    #[test]
+    fn from_sorted_iter() {
+        let values = (0..300_000u32).filter(|v| v % 7 != 3 && v % 1000 < 900);
+        let mut naive = Ranger::new();
+        for v in values.clone() {
+            naive.insert(v);
+        }
+        let bulk = Ranger::from_sorted_iter(values.flat_map(|v| [v, v])).unwrap();
+        assert_eq!(bulk, naive);
+        assert_eq!(bulk.range_count(), naive.range_count());
+        assert_eq!(Ranger::<u8>::from_sorted_iter([]), Ok(Ranger::new()));
+        assert_eq!(
+            Ranger::from_sorted_iter([1u8, 2, 2, 5, 4]),
+            Err(NotSorted(4))
+        );
+    }
+
+    #[test]
+    fn btree_set_conversions() {
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let set: BTreeSet<u16> = (0..rng.gen_range(0..500))
+                .map(|_| rng.gen_range(0..1000))
+                .collect();
+            let mut naive = Ranger::new();
+            for v in set.iter() {
+                naive.insert(*v);
+            }
+            let ranger = Ranger::from(set.clone());
+            assert_eq!(ranger, naive);
+            assert_eq!(ranger.len(), set.len() as u128);
+            assert_eq!(BTreeSet::from(&ranger), set);
+            assert_eq!(BTreeSet::from(ranger), set);
+        }
+        let ranger = Ranger::from(BTreeSet::from([-3i8, -2, -1, 5, 7, 8]));
+        assert_eq!(ranger.to_string(), "-3--1,5,7-8");
+    }
+
+    #[test]
+    fn from_ranges_and_slices() {
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = Ranger::from(9..=3i32);
+        assert!(reversed.is_empty());
+        assert_eq!(Ranger::from(3..=3i32).to_string(), "3");
+        assert_eq!(Ranger::from(u8::MIN..=u8::MAX).len(), 256);
+        let empty: [u8; 0] = [];
+        assert!(Ranger::from(empty).is_empty());
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let mut values: Vec<u8> = (0..rng.gen_range(0..200)).map(|_| rng.gen()).collect();
+            let mut naive = Ranger::new();
+            naive.insert_all(values.iter().copied());
+            assert_eq!(Ranger::from(&values[..]), naive);
+            values.sort();
+            assert_eq!(Ranger::from(&values[..]), naive);
+        }
+    }
+
+    #[test]
+    fn pair_lists() {
+        let ranger: Ranger<i32> = "-5--1,3,10-19".parse().unwrap();
+        let pairs = Vec::<(i32, i32)>::from(&ranger);
+        assert_eq!(pairs, [(-5, -1), (3, 3), (10, 19)]);
+        assert_eq!(Ranger::try_from(pairs), Ok(ranger.clone()));
+        let ranges = Vec::<RangeInclusive<i32>>::from(ranger);
+        assert_eq!(ranges, [-5..=-1, 3..=3, 10..=19]);
+        // unsorted, overlapping and touching pairs are normalized
+        let ranger = Ranger::try_from(vec![(20u8, 30), (0, 4), (5, 5), (25, 40), (2, 3)]);
+        assert_eq!(ranger.unwrap().to_string(), "0-5,20-40");
+        assert_eq!(Ranger::<u8>::try_from(vec![]), Ok(Ranger::new()));
+        assert_eq!(
+            Ranger::try_from(vec![(1u8, 2), (4, 4), (9, 3), (8, 7)]),
+            Err(ReversedPair(2))
+        );
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let mut ranger = Ranger::new();
+            for _ in 0..rng.gen_range(0..20) {
+                let (a, b) = (rng.gen::<u16>(), rng.gen::<u16>());
+                ranger.insert_range(a.min(b)..=a.max(b));
+            }
+            let pairs = Vec::<(u16, u16)>::from(&ranger);
+            assert_eq!(pairs.len(), ranger.range_count());
+            assert_eq!(Ranger::try_from(pairs), Ok(ranger));
+        }
+    }
+
+    #[test]
+    fn equals_value_slices() {
+        let ranger: Ranger<u8> = "1-3,7".parse().unwrap();
+        assert_eq!(ranger, [1, 2, 3, 7]);
+        assert_eq!(ranger, [7, 3, 1, 2]);
+        assert_eq!(ranger, [2, 7, 1, 2, 3, 7, 7]);
+        assert_eq!([3, 2, 1, 7], ranger);
+        assert_eq!(ranger, *vec![7, 1, 3, 2].as_slice());
+        assert_eq!(*vec![7, 1, 3, 2].as_slice(), ranger);
+        // one value missing, extra or swapped
+        assert_ne!(ranger, [1, 2, 3]);
+        assert_ne!(ranger, [1, 2, 3, 7, 8]);
+        assert_ne!(ranger, [1, 2, 3, 6]);
+        assert_ne!(ranger, [1, 1, 2, 3]);
+        assert_ne!(ranger, [0; 0]);
+        assert_eq!(Ranger::<u8>::new(), [0; 0]);
+        assert_ne!(Ranger::<u8>::new(), [0]);
+        // a full set is too large for any slice to match
+        assert_ne!(Ranger::from(0..=u64::MAX), [0, 1, 2]);
+    }
+
+    #[test]
+    fn memory_usage_grows_with_runs() {
+        let mut ranger = Ranger::<u32>::new();
+        assert_eq!(ranger.memory_usage(), 0);
+        let mut last = 0;
+        for v in 0..5000 {
+            ranger.insert(v * 2);
+            let usage = ranger.memory_usage();
+            assert!(usage >= last, "{v}");
+            last = usage;
+        }
+        // enough to hold the runs themselves, and not wildly more
+        let runs = ranger.range_count() * size_of::<Unit<u32>>();
+        assert!(last > runs && last < runs * 3, "{last}");
+        // joining runs gives the memory back
+        ranger.insert_range(0..=10_000);
+        assert!(ranger.memory_usage() < last);
+        let wide = Ranger::from(0..=u128::MAX);
+        assert!(wide.memory_usage() > Ranger::from(0..=u8::MAX).memory_usage());
+    }
+
+    #[test]
+    fn validate_reports_broken_runs() {
+        // built around the public operations, which never produce these
+        let broken = |runs: &[(u8, u8)]| {
+            let units = runs.iter().map(|&(l, h)| Unit { l, h: Some(h) });
+            Ranger(units.collect(), Config::default())
+        };
+        assert_eq!(broken(&[(0, 2), (5, 9)]).validate(), Ok(()));
+        assert_eq!(
+            broken(&[(0, 2), (9, 5)]).validate(),
+            Err(InvariantViolation::Reversed(1))
+        );
+        assert_eq!(
+            broken(&[(0, 2), (5, 9), (7, 12)]).validate(),
+            Err(InvariantViolation::Overlapping(2))
+        );
+        assert_eq!(
+            broken(&[(0, 2), (2, 4)]).validate(),
+            Err(InvariantViolation::Overlapping(1))
+        );
+        assert_eq!(
+            broken(&[(0, 2), (5, 9), (10, 12)]).validate(),
+            Err(InvariantViolation::Adjacent(2))
+        );
+        assert_eq!(
+            InvariantViolation::Adjacent(2).to_string(),
+            "Run 2 touches run 1 without being joined"
+        );
+        // runs within the gap tolerance are left apart by removal
+        let mut ranger = Ranger::with_gap_tolerance(3);
+        ranger.insert_range(0..=20);
+        ranger.remove(&10);
+        assert_eq!(ranger.validate(), Ok(()));
+        assert_eq!(Ranger::<u8>::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn checked_expansion() {
+        let ranger: Ranger<u64> = "0-9,100-199".parse().unwrap();
+        let set = ranger.checked_to_btree_set(110).unwrap();
+        assert_eq!(set.len(), 110);
+        assert_eq!(set.first(), Some(&0));
+        assert_eq!(set.last(), Some(&199));
+        assert_eq!(ranger.checked_to_btree_set(109), None);
+        // far too many values to ever expand
+        let huge: Ranger<u64> = "0-18446744073709551615".parse().unwrap();
+        assert_eq!(huge.checked_to_btree_set(1 << 20), None);
+        assert_eq!(
+            Ranger::<u8>::new().checked_to_btree_set(0),
+            Some(BTreeSet::new())
+        );
+    }
+
+    #[test]
+    fn mostly_ascending() {
+        let mut rng = thread_rng();
+        let mut ranger = Ranger::new();
+        let mut present = vec![false; 1 << 16];
+        let mut next = 0u16;
+        for _ in 0..50_000 {
+            let v = if rng.gen_ratio(3, 4) {
+                next = next.saturating_add(rng.gen_range(1..4));
+                next
+            } else {
+                rng.gen_range(0..=next.saturating_add(10))
+            };
+            assert_eq!(ranger.insert(v), !present[v as usize]);
+            present[v as usize] = true;
+        }
+        let values = (0..=u16::MAX).filter(|v| present[*v as usize]);
+        let expected = Ranger::from_sorted_iter(values).unwrap();
+        assert_eq!(ranger.to_string(), expected.to_string());
+        assert_eq!(ranger.len(), expected.len());
+
+        // tolerant merging takes the same shortcut
+        let mut tolerant = Ranger::with_gap_tolerance(2);
+        for v in [1u8, 2, 5, 9, 3, 10, 13] {
+            tolerant.insert(v);
+        }
+        assert_eq!(tolerant.to_string(), "1-5,9-13");
+    }
+
+    #[test]
+    fn insert_cases() {
+        let mut ranger = ranger_of(&[100u8]);
+        // (value, newly inserted, runs afterwards)
+        let script = [
+            (10, true, 2),  // standalone
+            (11, true, 2),  // extends the run below
+            (8, true, 3),   // standalone
+            (9, true, 2),   // bridges both
+            (10, false, 2), // contained
+            (7, true, 2),   // extends the run above
+            (99, true, 2),  // extends the run above
+            (13, true, 3),  // standalone
+            (12, true, 2),  // bridges both
+        ];
+        for (v, inserted, runs) in script {
+            assert_eq!(ranger.insert(v), inserted, "inserting {v}");
+            assert_eq!(ranger.range_count(), runs, "after inserting {v}");
+        }
+        assert_eq!(ranger.to_string(), "7-13,99-100");
+    }
+
+    #[test]
+    fn insert_detailed() {
+        use InsertOutcome::*;
+        let span = |l: u8, h: u8| Span::new(l, h).unwrap();
+        let mut ranger = ranger_of(&[100u8]);
+        let script = [
+            (10, NewRun),
+            (11, ExtendedHigh(span(10, 11))),
+            (8, NewRun),
+            (
+                9,
+                Bridged {
+                    run: span(8, 11),
+                    below: span(8, 8),
+                    above: span(10, 11),
+                },
+            ),
+            (10, AlreadyPresent),
+            (7, ExtendedLow(span(7, 11))),
+            (99, ExtendedLow(span(99, 100))),
+            (101, ExtendedHigh(span(99, 101))),
+            (255, NewRun),
+            (101, AlreadyPresent),
+            (0, NewRun),
+        ];
+        for (v, outcome) in script {
+            assert_eq!(ranger.insert_detailed(v), outcome, "inserting {v}");
+        }
+        assert_eq!(ranger.to_string(), "0,7-11,99-101,255");
+        // runs lengthen across the gaps a tolerance allows
+        let mut tolerant = Ranger::with_gap_tolerance(2);
+        assert_eq!(tolerant.insert_detailed(10u8), NewRun);
+        assert_eq!(tolerant.insert_detailed(13), ExtendedHigh(span(10, 13)));
+        assert_eq!(tolerant.insert_detailed(7), ExtendedLow(span(7, 13)));
+        assert_eq!(tolerant.insert_detailed(19), NewRun);
+        assert_eq!(
+            tolerant.insert_detailed(16),
+            Bridged {
+                run: span(7, 19),
+                below: span(7, 13),
+                above: span(19, 19),
+            }
+        );
+        assert_eq!(tolerant.insert_detailed(15), AlreadyPresent);
+        // outcomes agree with insert and the extents with the set afterwards
+        let mut rng = thread_rng();
+        let (mut plain, mut detailed) = (Ranger::new(), Ranger::new());
+        for _ in 0..500 {
+            let v = rng.gen_range(0u8..120);
+            let outcome = detailed.insert_detailed(v);
+            assert_eq!(plain.insert(v), outcome.inserted());
+            assert_eq!(plain, detailed);
+            match outcome {
+                ExtendedLow(run) | ExtendedHigh(run) | Bridged { run, .. } => {
+                    assert!(detailed.ranges().any(|r| r == run), "{run}");
+                }
+                NewRun => assert!(detailed.ranges().any(|r| r == span(v, v))),
+                AlreadyPresent => {}
+            }
+        }
+    }
+
+    // small enough to run under Miri, which checks that lookups neither copy
+    // nor drop the caller's value
+    #[test]
+    fn non_copy_elements() {
+        #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Boxed(alloc::boxed::Box<u16>);
+
+        impl Discrete for Boxed {
+            fn successor(&self) -> Option<Self> {
+                self.0.successor().map(|v| Boxed(v.into()))
+            }
+            fn predecessor(&self) -> Option<Self> {
+                self.0.predecessor().map(|v| Boxed(v.into()))
+            }
+            fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+                u16::distance(&lower.0, &upper.0)
+            }
+            fn forward(&self, steps: u128) -> Option<Self> {
+                self.0.forward(steps).map(|v| Boxed(v.into()))
+            }
+            fn backward(&self, steps: u128) -> Option<Self> {
+                self.0.backward(steps).map(|v| Boxed(v.into()))
+            }
+        }
+
+        let b = |v: u16| Boxed(v.into());
+        let mut ranger = Ranger::new();
+        for v in [5, 1, 3, 2, 9, 4] {
+            assert!(ranger.insert(b(v)));
+        }
+        assert!(!ranger.insert(b(3)));
+        let probe = b(4);
+        assert!(ranger.contains(&probe) && ranger.contains(&probe));
+        assert_eq!(*probe.0, 4);
+        assert!(!ranger.contains(&b(0)) && !ranger.contains(&b(7)));
+        assert!(ranger.contains_range(&(b(1)..=b(5))));
+        assert!(ranger.remove(&b(3)));
+        assert!(!ranger.contains(&b(3)));
+        assert_eq!(ranger.range_count(), 3);
+        assert_eq!(ranger.len(), 5);
+    }
+
+    /// Formats a 256-slot membership table the way `Display` formats runs
+    fn oracle_string(present: &[bool; 256]) -> alloc::string::String {
+        let mut runs = Vec::new();
+        let mut v = 0;
+        while v < 256 {
+            if present[v] {
+                let start = v;
+                while v < 255 && present[v + 1] {
+                    v += 1;
+                }
+                runs.push(if start == v {
+                    format!("{start}")
+                } else {
+                    format!("{start}-{v}")
+                });
+            }
+            v += 1;
+        }
+        runs.join(",")
+    }
+
+    #[test]
+    fn random_range_operations_match_oracle() {
+        let mut rng = thread_rng();
+        for _ in 0..500 {
+            let mut ranger = Ranger::<u8>::new();
+            let mut present = [false; 256];
+            for _ in 0..40 {
+                let (a, b) = (rng.gen::<u8>(), rng.gen::<u8>());
+                let (l, h) = (a.min(b), a.max(b));
+                let span = l as usize..=h as usize;
+                match rng.gen_range(0..3) {
+                    0 => {
+                        let added = span.clone().filter(|&v| !present[v]).count();
+                        assert_eq!(ranger.insert_range(l..=h), added as u128);
+                        span.for_each(|v| present[v] = true);
+                    }
+                    1 => {
+                        let removed = span.clone().filter(|&v| present[v]).count();
+                        assert_eq!(ranger.remove_range(l..=h), removed as u128);
+                        span.for_each(|v| present[v] = false);
+                    }
+                    _ => {
+                        assert_eq!(ranger.insert(a), !present[a as usize]);
+                        present[a as usize] = true;
+                    }
+                }
+                assert_eq!(ranger.to_string(), oracle_string(&present));
+                // stored runs stay disjoint and separated by a gap
+                for (x, y) in ranger.0.iter().zip(ranger.0.iter().skip(1)) {
+                    assert!(x.high() < &y.l && gap(x.high(), &y.l) > 0);
+                }
+            }
+            for v in 0..=255u8 {
+                assert_eq!(ranger.contains(&v), present[v as usize]);
+            }
+        }
+    }
+
+    fn letters() -> BTreeSet<char> {
+        ['b', 'd', 'f'].into_iter().collect()
+    }
+
+    #[test]
+    fn pop_before_is_inclusive() {
+        let mut set = letters();
+        assert_eq!(pop_before(&mut set, &'e'), Some('d'));
+        assert_eq!(pop_before(&mut set, &'f'), Some('f'));
+        assert_eq!(pop_before(&mut set, &'a'), None);
+        assert_eq!(set, ['b'].into_iter().collect());
+        assert_eq!(pop_before(&mut BTreeSet::<char>::new(), &'a'), None);
+    }
+
+    #[test]
+    fn pop_after_is_inclusive() {
+        let mut set = letters();
+        assert_eq!(pop_after(&mut set, &'c'), Some('d'));
+        assert_eq!(pop_after(&mut set, &'b'), Some('b'));
+        assert_eq!(pop_after(&mut set, &'g'), None);
+        assert_eq!(set, ['f'].into_iter().collect());
+        assert_eq!(pop_after(&mut BTreeSet::<char>::new(), &'a'), None);
+    }
+
+    #[test]
+    fn pop_before_exclusive_skips_equal() {
+        let mut set = letters();
+        assert_eq!(pop_before_exclusive(&mut set, &'d'), Some('b'));
+        assert_eq!(pop_before_exclusive(&mut set, &'e'), Some('d'));
+        assert_eq!(pop_before_exclusive(&mut set, &'f'), None);
+        assert_eq!(set, ['f'].into_iter().collect());
+    }
+
+    #[test]
+    fn pop_after_exclusive_skips_equal() {
+        let mut set = letters();
+        assert_eq!(pop_after_exclusive(&mut set, &'d'), Some('f'));
+        assert_eq!(pop_after_exclusive(&mut set, &'a'), Some('b'));
+        assert_eq!(pop_after_exclusive(&mut set, &'d'), None);
+        assert_eq!(set, ['d'].into_iter().collect());
+    }
+
+    fn letter_map() -> BTreeMap<char, u8> {
+        [('b', 1), ('d', 2), ('f', 3)].into_iter().collect()
+    }
+
+    #[test]
+    fn pop_before_entry_is_inclusive() {
+        let mut map = letter_map();
+        assert_eq!(pop_before_entry(&mut map, &'e'), Some(('d', 2)));
+        assert_eq!(pop_before_entry(&mut map, &'f'), Some(('f', 3)));
+        assert_eq!(pop_before_entry(&mut map, &'a'), None);
+        assert_eq!(map, [('b', 1)].into_iter().collect());
+        assert_eq!(
+            pop_before_entry(&mut BTreeMap::<char, u8>::new(), &'a'),
+            None
+        );
+    }
+
+    #[test]
+    fn pop_after_entry_is_inclusive() {
+        let mut map = letter_map();
+        assert_eq!(pop_after_entry(&mut map, &'c'), Some(('d', 2)));
+        assert_eq!(pop_after_entry(&mut map, &'b'), Some(('b', 1)));
+        assert_eq!(pop_after_entry(&mut map, &'g'), None);
+        assert_eq!(map, [('f', 3)].into_iter().collect());
+        assert_eq!(
+            pop_after_entry(&mut BTreeMap::<char, u8>::new(), &'a'),
+            None
+        );
+    }
+
+    #[test]
+    fn pop_entry_takes_only_equal() {
+        let mut map = letter_map();
+        assert_eq!(pop_entry(&mut map, &'c'), None);
+        assert_eq!(pop_entry(&mut map, &'a'), None);
+        assert_eq!(pop_entry(&mut map, &'d'), Some(('d', 2)));
+        assert_eq!(pop_entry(&mut map, &'d'), None);
+        assert_eq!(map, [('b', 1), ('f', 3)].into_iter().collect());
+        assert_eq!(pop_entry(&mut BTreeMap::<char, u8>::new(), &'a'), None);
+    }
+
+    #[test]
+    fn peek_neighbours() {
+        let set = letters();
+        assert_eq!(peek_before(&set, &'e'), Some(&'d'));
+        assert_eq!(peek_before(&set, &'d'), Some(&'d'));
+        assert_eq!(peek_before(&set, &'a'), None);
+        assert_eq!(peek_after(&set, &'c'), Some(&'d'));
+        assert_eq!(peek_after(&set, &'f'), Some(&'f'));
+        assert_eq!(peek_after(&set, &'g'), None);
+        assert_eq!(set, letters());
+        assert_eq!(peek_before(&BTreeSet::<char>::new(), &'a'), None);
+        assert_eq!(peek_after(&BTreeSet::<char>::new(), &'a'), None);
+    }
+
+    #[test]
+    fn pop_range_inclusive_bounds() {
+        let mut set = letters();
+        assert_eq!(
+            pop_range(&mut set, 'b'..='d'),
+            ['b', 'd'].into_iter().collect()
+        );
+        assert_eq!(set, ['f'].into_iter().collect());
+        let mut set = letters();
+        assert_eq!(pop_range(&mut set, 'c'..='e'), ['d'].into_iter().collect());
+        assert_eq!(set, ['b', 'f'].into_iter().collect());
+    }
+
+    #[test]
+    fn pop_range_excluded_bounds() {
+        let mut set = letters();
+        let bounds = (Bound::Excluded('b'), Bound::Excluded('f'));
+        assert_eq!(pop_range(&mut set, bounds), ['d'].into_iter().collect());
+        assert_eq!(set, ['b', 'f'].into_iter().collect());
+        let mut set = letters();
+        assert_eq!(
+            pop_range(&mut set, 'b'..'f'),
+            ['b', 'd'].into_iter().collect()
+        );
+        assert_eq!(set, ['f'].into_iter().collect());
+        let bounds = (Bound::Excluded('d'), Bound::Excluded('d'));
+        assert!(pop_range(&mut letters(), bounds).is_empty());
+    }
+
+    #[test]
+    fn pop_range_edges() {
+        let mut set = letters();
+        assert!(pop_range(&mut set, 'g'..).is_empty());
+        assert!(pop_range(&mut set, ..'b').is_empty());
+        assert!(pop_range(&mut set, 'c'..='c').is_empty());
+        #[allow(clippy::reversed_empty_ranges)]
+        let crossed = pop_range(&mut set, 'e'..='c');
+        assert!(crossed.is_empty());
+        assert_eq!(set, letters());
+        assert_eq!(pop_range(&mut set, ..), letters());
+        assert!(set.is_empty());
+        assert!(pop_range(&mut set, ..).is_empty());
+        let mut set = letters();
+        assert_eq!(pop_range(&mut set, 'a'..='z'), letters());
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn pop_takes_only_equal() {
+        let mut set = letters();
+        assert_eq!(pop(&mut set, &'c'), None);
+        assert_eq!(pop(&mut set, &'a'), None);
+        assert_eq!(pop(&mut set, &'g'), None);
+        assert_eq!(pop(&mut set, &'d'), Some('d'));
+        assert_eq!(pop(&mut set, &'d'), None);
+        assert_eq!(set, ['b', 'f'].into_iter().collect());
+    }
+
+    /// Exhaustive checks for `u8` and `i8` against a table of all 256 values,
+    /// concentrating on runs that reach either end of the type
+    mod extremes {
+        use super::*;
+        use alloc::string::String;
+
+        fn nth<T: Discrete + Bounded>(i: usize) -> T {
+            T::min_value().forward(i as u128).unwrap()
+        }
+
+        fn render<T: Discrete + Bounded + Display>(present: &[bool; 256]) -> String {
+            let mut runs = Vec::new();
+            let mut i = 0;
+            while i < 256 {
+                if present[i] {
+                    let start = i;
+                    while i < 255 && present[i + 1] {
+                        i += 1;
+                    }
+                    let (l, h) = (nth::<T>(start), nth::<T>(i));
+                    runs.push(if start == i {
+                        format!("{l}")
+                    } else {
+                        format!("{l}-{h}")
+                    });
+                }
+                i += 1;
+            }
+            runs.join(",")
+        }
+
+        fn check<T: Discrete + Bounded + Display>(ranger: &Ranger<T>, present: &[bool; 256]) {
+            for (i, &p) in present.iter().enumerate() {
+                assert_eq!(ranger.contains(&nth(i)), p, "{ranger} at {}", nth::<T>(i));
+            }
+            let count = present.iter().filter(|&&p| p).count();
+            assert_eq!(ranger.len(), count as u128);
+            assert_eq!(ranger.is_empty(), count == 0);
+            assert_eq!(ranger.to_string(), render::<T>(present));
+            let inverted = ranger.invert_within(T::min_value()..=T::max_value());
+            assert_eq!(inverted.to_string(), render::<T>(&present.map(|p| !p)));
+        }
+
+        /// Indices of the values at and next to either end of the type, and
+        /// either side of its midpoint
+        const EDGES: [usize; 8] = [0, 1, 2, 127, 128, 253, 254, 255];
+
+        fn all_orders_of_edges<T: Discrete + Bounded + Display>() {
+            let n = EDGES.len();
+            // a rotation and a reversal of every prefix covers each edge
+            // arriving first, last and between its neighbours
+            for len in 1..=n {
+                for rot in 0..len {
+                    for rev in [false, true] {
+                        let mut order: Vec<usize> = EDGES[..len].to_vec();
+                        order.rotate_left(rot);
+                        if rev {
+                            order.reverse();
+                        }
+                        let mut ranger = Ranger::<T>::new();
+                        let mut present = [false; 256];
+                        for i in order {
+                            assert_eq!(ranger.insert(nth(i)), !present[i]);
+                            present[i] = true;
+                            check(&ranger, &present);
+                        }
+                        assert!(!ranger.insert(nth(EDGES[0])));
+                        for &i in &EDGES[..len] {
+                            assert!(ranger.remove(&nth(i)));
+                            present[i] = false;
+                            check(&ranger, &present);
+                        }
+                    }
+                }
+            }
+        }
+
+        fn random_near_edges<T: Discrete + Bounded + Display>() {
+            let mut rng = thread_rng();
+            // picks mostly from the edges so that runs keep reaching them
+            let pick = |rng: &mut rand::rngs::ThreadRng| {
+                if rng.gen_bool(0.6) {
+                    *EDGES.choose(rng).unwrap()
+                } else {
+                    rng.gen_range(0..256)
+                }
+            };
+            for _ in 0..300 {
+                let mut ranger = Ranger::<T>::new();
+                let mut present = [false; 256];
+                for _ in 0..30 {
+                    let (a, b) = (pick(&mut rng), pick(&mut rng));
+                    let (l, h) = (a.min(b), a.max(b));
+                    match rng.gen_range(0..5) {
+                        0 => {
+                            let added = (l..=h).filter(|&i| !present[i]).count();
+                            assert_eq!(ranger.insert_range(nth(l)..=nth(h)), added as u128);
+                            present[l..=h].fill(true);
+                        }
+                        1 => {
+                            let removed = (l..=h).filter(|&i| present[i]).count();
+                            assert_eq!(ranger.remove_range(nth::<T>(l)..=nth(h)), removed as u128);
+                            present[l..=h].fill(false);
+                        }
+                        2 => {
+                            ranger.clamp_to(nth::<T>(l)..=nth(h));
+                            present[..l].fill(false);
+                            present[h + 1..].fill(false);
+                        }
+                        3 => {
+                            assert_eq!(ranger.remove(&nth(a)), present[a]);
+                            present[a] = false;
+                        }
+                        _ => {
+                            assert_eq!(ranger.insert(nth(a)), !present[a]);
+                            present[a] = true;
+                        }
+                    }
+                    check(&ranger, &present);
+                }
+            }
+        }
+
+        fn whole_domain<T: Discrete + Bounded + Display>() {
+            let mut ranger = Ranger::<T>::new();
+            assert_eq!(ranger.insert_bounds(..), 256);
+            check(&ranger, &[true; 256]);
+            assert_eq!(ranger.insert_bounds(..), 0);
+            assert!(ranger.contains_range(&(T::min_value()..=T::max_value())));
+            ranger.remove_below(&T::min_value());
+            ranger.remove_above(&T::max_value());
+            assert_eq!(ranger.len(), 256);
+            assert_eq!(ranger.first_n(1).to_string(), T::min_value().to_string());
+            assert_eq!(ranger.last_n(1).to_string(), T::max_value().to_string());
+            assert!(ranger.remove(&T::max_value()) && ranger.remove(&T::min_value()));
+            let mut present = [true; 256];
+            present[0] = false;
+            present[255] = false;
+            check(&ranger, &present);
+            assert_eq!(ranger.remove_range(..), 254);
+            check(&ranger, &[false; 256]);
+        }
+
+        fn gaps_at_edges<T: Discrete + Bounded + Display + ToPrimitive + Zero>() {
+            let mut ends = [false; 256];
+            ends[0] = true;
+            ends[255] = true;
+            // the 254 values between the ends only fit a tolerance in u8
+            let spans = T::max_value().to_u128() >= Some(254);
+
+            let mut ranger = Ranger::<T>::new();
+            ranger.insert(T::min_value());
+            ranger.insert(T::max_value());
+            check(&ranger, &ends);
+            assert_eq!(ranger.merge_gaps_smaller_than(nth::<T>(253)), 0);
+            check(&ranger, &ends);
+            let absorbed = ranger.merge_gaps_smaller_than(T::max_value());
+            assert_eq!(absorbed, if spans { 254 } else { 0 });
+            check(&ranger, if spans { &[true; 256] } else { &ends });
+
+            let mut tolerant = Ranger::<T>::with_gap_tolerance(T::max_value());
+            tolerant.insert(T::max_value());
+            tolerant.insert(T::min_value());
+            check(&tolerant, if spans { &[true; 256] } else { &ends });
+
+            let mut tolerant = Ranger::<T>::with_gap_tolerance(T::zero().forward(1).unwrap());
+            tolerant.insert(nth(253));
+            tolerant.insert(T::max_value());
+            tolerant.insert(nth(2));
+            tolerant.insert(T::min_value());
+            let mut present = [false; 256];
+            present[..=2].fill(true);
+            present[253..].fill(true);
+            check(&tolerant, &present);
+        }
+
+        #[test]
+        fn edge_insertion_orders() {
+            all_orders_of_edges::<u8>();
+            all_orders_of_edges::<i8>();
+        }
+
+        #[test]
+        fn random_operations_near_edges() {
+            random_near_edges::<u8>();
+            random_near_edges::<i8>();
+        }
+
+        #[test]
+        fn whole_domain_runs() {
+            whole_domain::<u8>();
+            whole_domain::<i8>();
+        }
+
+        #[test]
+        fn gaps_reaching_the_edges() {
+            gaps_at_edges::<u8>();
+            gaps_at_edges::<i8>();
+        }
+
+        #[test]
+        fn adjacency_at_the_minimum() {
+            assert!(!u8::adjacent(&0, &0));
+            assert!(!i8::adjacent(&i8::MIN, &i8::MIN));
+            assert!(!u8::adjacent(&u8::MAX, &0));
+            assert!(!i8::adjacent(&i8::MAX, &i8::MIN));
+            let mut ranger = Ranger::new();
+            ranger.insert(i8::MIN);
+            ranger.insert(i8::MIN);
+            ranger.insert(i8::MAX);
+            assert_eq!(ranger.to_string(), "-128,127");
         }
     }
 }
@@ -0,0 +1,88 @@
+use crate::{OutOfRange, ParseRangeError, Ranger};
+use libc::{cpu_set_t, CPU_ISSET, CPU_SET, CPU_SETSIZE, CPU_ZERO};
+
+impl Ranger<u32> {
+    /// Returns a `cpu_set_t` holding each stored CPU, ready for
+    /// `sched_setaffinity`. Fails if any CPU is at or above `CPU_SETSIZE`.
+    pub fn to_cpu_set(&self) -> Result<cpu_set_t, OutOfRange> {
+        if self
+            .0
+            .last()
+            .is_some_and(|u| *u.high() >= CPU_SETSIZE as u32)
+        {
+            return Err(OutOfRange);
+        }
+        // SAFETY: cpu_set_t is a plain array of integers, for which all zero
+        // bytes is a valid value
+        let mut set: cpu_set_t = unsafe { core::mem::zeroed() };
+        // SAFETY: the macros only touch the bits of `set`, and every CPU was
+        // checked to be below CPU_SETSIZE
+        unsafe {
+            CPU_ZERO(&mut set);
+            for u in self.0.iter() {
+                for cpu in u.l..=*u.high() {
+                    CPU_SET(cpu as usize, &mut set);
+                }
+            }
+        }
+        Ok(set)
+    }
+    /// Returns the set of the CPUs held in `set`
+    pub fn from_cpu_set(set: &cpu_set_t) -> Ranger<u32> {
+        // SAFETY: CPU_ISSET only reads the bits of `set`, and every CPU asked
+        // about is below CPU_SETSIZE
+        let held = (0..CPU_SETSIZE as u32).filter(|&cpu| unsafe { CPU_ISSET(cpu as usize, set) });
+        Ranger::from_sorted_iter(held).expect("CPUs are visited in ascending order")
+    }
+}
+
+/// Parses a CPU list such as the contents of `/sys/devices/system/cpu/online`,
+/// which is the `Display` form of a [`Ranger`] followed by a newline. As with
+/// any parse, whitespace around each entry, that newline included, is
+/// ignored.
+pub fn parse_cpulist(s: &str) -> Result<Ranger<u32>, ParseRangeError> {
+    s.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn cpu_set_round_trips() {
+        let online = parse_cpulist("0-3,8-11,63-64\n").unwrap();
+        let set = online.to_cpu_set().unwrap();
+        for cpu in 0..CPU_SETSIZE as u32 {
+            let held = unsafe { CPU_ISSET(cpu as usize, &set) };
+            assert_eq!(held, online.contains(&cpu), "{cpu}");
+        }
+        assert_eq!(Ranger::from_cpu_set(&set), online);
+        let mut all = Ranger::new();
+        all.insert_range(0..=CPU_SETSIZE as u32 - 1);
+        assert_eq!(Ranger::from_cpu_set(&all.to_cpu_set().unwrap()), all);
+        let none = Ranger::<u32>::new().to_cpu_set().unwrap();
+        assert!(Ranger::from_cpu_set(&none).is_empty());
+    }
+
+    #[test]
+    fn built_by_hand() {
+        let mut set: cpu_set_t = unsafe { core::mem::zeroed() };
+        unsafe {
+            CPU_SET(1, &mut set);
+            CPU_SET(2, &mut set);
+            CPU_SET(CPU_SETSIZE as usize - 1, &mut set);
+        }
+        let cpus = Ranger::from_cpu_set(&set);
+        assert_eq!(cpus.to_string(), alloc::format!("1-2,{}", CPU_SETSIZE - 1));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut cpus = Ranger::new();
+        cpus.insert_range(0..=CPU_SETSIZE as u32);
+        assert_eq!(cpus.to_cpu_set().err(), Some(OutOfRange));
+        assert_eq!(parse_cpulist("0-3,\n"), Err(ParseRangeError::Empty(4)));
+        assert_eq!(parse_cpulist("\n").unwrap(), Ranger::new());
+    }
+}
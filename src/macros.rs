@@ -0,0 +1,68 @@
+use crate::{Discrete, Ranger};
+use core::ops::{Range, RangeInclusive};
+
+/// Builds a [`Ranger`] from a list of values and ranges, inferring the
+/// element type.
+///
+/// Each element may be a single value or an inclusive range `a..=b`. An
+/// exclusive range `a..b` is accepted too and stores `a` up to but not
+/// including `b`, exactly as it would iterate. Empty ranges add nothing.
+///
+/// ```
+/// use ranger::{ranger, Ranger};
+///
+/// let ranges = ranger![0..=2, 4, 6..=8];
+/// assert_eq!(ranges.to_string(), "0-2,4,6-8");
+///
+/// let values: Ranger<u8> = ranger![9, 3, 4];
+/// assert_eq!(values.to_string(), "3-4,9");
+///
+/// let exclusive = ranger!['a'..'d', 'x'..'x'];
+/// assert_eq!(exclusive.to_string(), "a-c");
+///
+/// let trailing = ranger![
+///     -5..=-3,
+///     -2,
+/// ];
+/// assert_eq!(trailing.to_string(), "-5--2");
+///
+/// let empty: Ranger<u32> = ranger![];
+/// assert!(empty.is_empty());
+/// ```
+#[macro_export]
+macro_rules! ranger {
+    ($($element:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut ranger = $crate::Ranger::new();
+        $($crate::RangerElement::insert_into($element, &mut ranger);)*
+        ranger
+    }};
+}
+
+/// What the [`ranger!`] macro accepts as an element
+#[doc(hidden)]
+pub trait RangerElement<T> {
+    fn insert_into(self, ranger: &mut Ranger<T>);
+}
+
+impl<T: Discrete> RangerElement<T> for T {
+    fn insert_into(self, ranger: &mut Ranger<T>) {
+        ranger.insert(self);
+    }
+}
+
+impl<T: Discrete> RangerElement<T> for RangeInclusive<T> {
+    fn insert_into(self, ranger: &mut Ranger<T>) {
+        ranger.insert_range(self);
+    }
+}
+
+impl<T: Discrete> RangerElement<T> for Range<T> {
+    fn insert_into(self, ranger: &mut Ranger<T>) {
+        if self.start < self.end {
+            if let Some(end) = self.end.predecessor() {
+                ranger.insert_range(self.start..=end);
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    discrete::{step_down, step_up},
+    pop_after_entry, Discrete, Span, Unit,
+};
+use alloc::collections::BTreeMap;
+use core::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
+
+/// A map from runs of keys to values, such as the extent owning each range
+/// of sectors.
+///
+/// Inserting a run overwrites whatever the keys it covers mapped to before,
+/// splitting the runs it partly covers. Touching runs are joined only when
+/// their values compare equal. Writes like a [`Ranger`](crate::Ranger) with
+/// each run followed by `=>` and its value, such as `0-99=>A,100-149=>B`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangerMap<K, V>(BTreeMap<Unit<K>, V>);
+
+impl<K: Discrete, V> Default for RangerMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Discrete, V> RangerMap<K, V> {
+    pub fn new() -> Self {
+        RangerMap(BTreeMap::new())
+    }
+    /// Returns the value mapped to `key`, if any
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.range_value_containing(key).map(|(_, v)| v)
+    }
+    /// Returns the run containing `key` along with its value, if any
+    pub fn range_value_containing(&self, key: &K) -> Option<(Span<K>, &V)> {
+        self.0
+            .range::<K, _>(key..)
+            .next()
+            .filter(|(u, _)| u.contains(key))
+            .map(|(u, v)| (u.span(), v))
+    }
+    /// Iterates over the runs and their values in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = (Span<K>, &V)> + '_ {
+        self.0.iter().map(|(u, v)| (u.span(), v))
+    }
+    /// Returns the number of runs stored
+    pub fn range_count(&self) -> usize {
+        self.0.len()
+    }
+    /// Returns the number of keys mapped, saturating at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        self.0
+            .keys()
+            .fold(0u128, |acc, u| acc.saturating_add(u.len()))
+    }
+    /// Returns whether no keys are mapped
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Returns a value displaying the map with the ends of each run joined by
+    /// `separator` rather than `-`
+    pub fn display_with<'a>(&'a self, separator: &'a str) -> MapDisplayWith<'a, K, V> {
+        MapDisplayWith {
+            map: self,
+            separator,
+        }
+    }
+}
+
+impl<K: Discrete, V: Clone> RangerMap<K, V> {
+    /// Cuts `l..=h` out of the runs overlapping it, keeping the pieces left
+    /// on either side
+    fn cut(&mut self, l: &K, h: &K) {
+        while let Some((u, v)) = pop_after_entry(&mut self.0, l) {
+            if &u.l > h {
+                self.0.insert(u, v);
+                break;
+            }
+            let Unit { l: ul, h: uh } = u;
+            let uh = uh.unwrap_or_else(|| ul.clone());
+            if &ul < l {
+                self.0.insert(Unit::new(ul, step_down(l)), v.clone());
+            }
+            if &uh > h {
+                self.0.insert(Unit::new(step_up(h), uh), v);
+                break;
+            }
+        }
+    }
+    /// Unmaps every key in `range`, splitting the runs it partly covers
+    pub fn remove_range(&mut self, range: RangeInclusive<K>) {
+        let (l, h) = range.into_inner();
+        if l <= h {
+            self.cut(&l, &h);
+        }
+    }
+}
+
+impl<K: Discrete, V: PartialEq + Clone> RangerMap<K, V> {
+    /// Maps every key in `range` to `value`, overwriting what they mapped to
+    /// before and joining with touching runs of an equal value
+    pub fn insert_range(&mut self, range: RangeInclusive<K>, value: V) {
+        let (mut l, mut h) = range.into_inner();
+        if l > h {
+            return;
+        }
+        self.cut(&l, &h);
+        let below = self
+            .0
+            .range::<K, _>(..&l)
+            .next_back()
+            .filter(|(u, v)| K::adjacent(u.high(), &l) && **v == value)
+            .map(|(u, _)| u.high().clone());
+        if let Some(key) = below {
+            l = self.0.remove_entry(&key).unwrap().0.l;
+        }
+        let above = self
+            .0
+            .range::<K, _>(&h..)
+            .next()
+            .filter(|(u, v)| K::adjacent(&h, &u.l) && **v == value)
+            .map(|(u, _)| u.high().clone());
+        if let Some(key) = above {
+            self.0.remove(&key);
+            h = key;
+        }
+        self.0.insert(Unit::new(l, h), value);
+    }
+    /// Maps `key` alone to `value`, as [`insert_range`](Self::insert_range)
+    pub fn insert(&mut self, key: K, value: V) {
+        self.insert_range(key.clone()..=key, value);
+    }
+}
+
+impl<K: Discrete, V: PartialEq + Clone> FromIterator<(RangeInclusive<K>, V)> for RangerMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (RangeInclusive<K>, V)>>(iter: I) -> Self {
+        let mut map = RangerMap::new();
+        for (range, value) in iter {
+            map.insert_range(range, value);
+        }
+        map
+    }
+}
+
+impl<K: Eq + Display, V: Display> RangerMap<K, V> {
+    fn write_with(&self, f: &mut fmt::Formatter<'_>, separator: &str) -> fmt::Result {
+        for (i, (u, v)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            match &u.h {
+                Some(h) if h != &u.l => write!(f, "{}{}{}=>{}", u.l, separator, h, v)?,
+                _ => write!(f, "{}=>{}", u.l, v)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K: Eq + Display, V: Display> Display for RangerMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with(f, "-")
+    }
+}
+
+/// Writes a map like its `Display` form, but joining the ends of each run
+/// with a chosen separator. Returned by [`RangerMap::display_with`].
+#[derive(Clone, Copy, Debug)]
+pub struct MapDisplayWith<'a, K, V> {
+    map: &'a RangerMap<K, V>,
+    separator: &'a str,
+}
+
+impl<K: Eq + Display, V: Display> Display for MapDisplayWith<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.write_with(f, self.separator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+    use rand::{thread_rng, Rng};
+
+    fn extents() -> RangerMap<u32, char> {
+        [(0..=99, 'A'), (100..=149, 'B')].into_iter().collect()
+    }
+
+    #[test]
+    fn overwrite_partial_overlaps() {
+        let mut map = extents();
+        assert_eq!(map.to_string(), "0-99=>A,100-149=>B");
+        // across the boundary, cutting into both sides
+        map.insert_range(90..=109, 'C');
+        assert_eq!(map.to_string(), "0-89=>A,90-109=>C,110-149=>B");
+        // the low side of a run only
+        map.insert_range(80..=95, 'D');
+        assert_eq!(map.to_string(), "0-79=>A,80-95=>D,96-109=>C,110-149=>B");
+        // the high side of a run, reaching past the end of the map
+        map.insert_range(140..=160, 'E');
+        assert_eq!(
+            map.to_string(),
+            "0-79=>A,80-95=>D,96-109=>C,110-139=>B,140-160=>E"
+        );
+        // inside a single run, splitting it in three
+        map.insert_range(10..=19, 'F');
+        assert_eq!(
+            map.to_string(),
+            "0-9=>A,10-19=>F,20-79=>A,80-95=>D,96-109=>C,110-139=>B,140-160=>E"
+        );
+        assert_eq!(map.range_count(), 7);
+        assert_eq!(map.len(), 161);
+    }
+
+    #[test]
+    fn overwrite_full_cover() {
+        let mut map = extents();
+        map.insert_range(0..=149, 'Z');
+        assert_eq!(map.to_string(), "0-149=>Z");
+        let mut map = extents();
+        map.insert_range(100..=149, 'C');
+        assert_eq!(map.to_string(), "0-99=>A,100-149=>C");
+        map.insert_range(200..=300, 'D');
+        map.insert_range(50..=250, 'E');
+        assert_eq!(map.to_string(), "0-49=>A,50-250=>E,251-300=>D");
+    }
+
+    #[test]
+    fn equal_values_coalesce() {
+        let mut map = extents();
+        map.insert_range(150..=159, 'B');
+        assert_eq!(map.to_string(), "0-99=>A,100-159=>B");
+        map.insert_range(100..=120, 'A');
+        assert_eq!(map.to_string(), "0-120=>A,121-159=>B");
+        // bridging two runs of the same value joins all three
+        map.insert_range(170..=180, 'A');
+        map.insert_range(121..=169, 'A');
+        assert_eq!(map.to_string(), "0-180=>A");
+        assert_eq!(map.range_count(), 1);
+        // rewriting a piece with its own value changes nothing
+        map.insert_range(50..=60, 'A');
+        assert_eq!(map.to_string(), "0-180=>A");
+        // touching runs of different values stay apart
+        map.insert(181, 'B');
+        assert_eq!(map.to_string(), "0-180=>A,181=>B");
+        #[allow(clippy::reversed_empty_ranges)]
+        map.insert_range(9..=3, 'C');
+        assert_eq!(map.range_count(), 2);
+    }
+
+    #[test]
+    fn queries_at_split_boundaries() {
+        let mut map = extents();
+        map.insert_range(90..=109, 'C');
+        for (key, value) in [(89, 'A'), (90, 'C'), (109, 'C'), (110, 'B'), (0, 'A')] {
+            assert_eq!(map.get(&key), Some(&value), "{key}");
+        }
+        assert_eq!(map.get(&150), None);
+        let (span, value) = map.range_value_containing(&100).unwrap();
+        assert_eq!((span, *value), (Span::new(90, 109).unwrap(), 'C'));
+        let (span, _) = map.range_value_containing(&89).unwrap();
+        assert_eq!(RangeInclusive::from(span), 0..=89);
+        assert!(map.range_value_containing(&1000).is_none());
+        let runs: Vec<_> = map.iter().map(|(s, v)| (s.into_inner(), *v)).collect();
+        assert_eq!(runs, [((0, 89), 'A'), ((90, 109), 'C'), ((110, 149), 'B')]);
+        let map: RangerMap<i32, u8> = [(-5..=-1, 1), (0..=0, 2)].into_iter().collect();
+        assert_eq!(map.display_with("..").to_string(), "-5..-1=>1,0=>2");
+        assert_eq!(RangerMap::<u8, u8>::new().to_string(), "");
+    }
+
+    #[test]
+    fn remove_splits() {
+        let mut map = extents();
+        map.remove_range(90..=109);
+        assert_eq!(map.to_string(), "0-89=>A,110-149=>B");
+        map.remove_range(10..=19);
+        assert_eq!(map.to_string(), "0-9=>A,20-89=>A,110-149=>B");
+        assert_eq!(map.get(&15), None);
+        map.remove_range(0..=1000);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn random_against_table() {
+        let mut rng = thread_rng();
+        for _ in 0..300 {
+            let mut map = RangerMap::<u8, u8>::new();
+            let mut table = [None; 256];
+            for _ in 0..20 {
+                let (a, b) = (rng.gen::<u8>(), rng.gen::<u8>());
+                let (l, h) = (a.min(b), a.max(b));
+                if rng.gen_bool(0.2) {
+                    map.remove_range(l..=h);
+                    table[l as usize..=h as usize].fill(None);
+                } else {
+                    let value = rng.gen_range(0..3);
+                    map.insert_range(l..=h, value);
+                    table[l as usize..=h as usize].fill(Some(value));
+                }
+            }
+            for (k, expected) in table.iter().enumerate() {
+                assert_eq!(map.get(&(k as u8)), expected.as_ref());
+            }
+            // runs never touch another of the same value
+            let runs: Vec<_> = map.iter().collect();
+            for pair in runs.windows(2) {
+                let ((a, x), (b, y)) = (&pair[0], &pair[1]);
+                assert!(a.high() < b.low());
+                assert!(!(u8::adjacent(a.high(), b.low()) && x == y), "{map}");
+            }
+        }
+    }
+}
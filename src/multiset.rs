@@ -0,0 +1,176 @@
+use crate::{Discrete, Ranger, RangerMap, Span};
+use core::fmt::{self, Display};
+
+/// A multiset counting how many times each value was inserted, such as the
+/// hit count of each line in a coverage run.
+///
+/// Touching runs of values with the same count are stored as one. Writes
+/// each run followed by `x` and its count, such as `1-5x3,7x1`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangerMultiset<T>(RangerMap<T, u64>);
+
+impl<T: Discrete> Default for RangerMultiset<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Discrete> RangerMultiset<T> {
+    pub fn new() -> Self {
+        RangerMultiset(RangerMap::new())
+    }
+    /// Returns how many times `value` is held
+    pub fn count_of(&self, value: &T) -> u64 {
+        self.0.get(value).copied().unwrap_or(0)
+    }
+    /// Counts `value` once more, returning its new count. The count
+    /// saturates at `u64::MAX`.
+    pub fn insert(&mut self, value: T) -> u64 {
+        let count = self.count_of(&value).saturating_add(1);
+        self.0.insert(value, count);
+        count
+    }
+    /// Counts `value` once less, returning whether it was held at all
+    pub fn remove(&mut self, value: &T) -> bool {
+        match self.count_of(value) {
+            0 => false,
+            1 => {
+                self.0.remove_range(value.clone()..=value.clone());
+                true
+            }
+            count => {
+                self.0.insert(value.clone(), count - 1);
+                true
+            }
+        }
+    }
+    /// Iterates over the runs of values sharing a count, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = (Span<T>, u64)> + '_ {
+        self.0.iter().map(|(span, count)| (span, *count))
+    }
+    /// Returns the number of runs stored
+    pub fn range_count(&self) -> usize {
+        self.0.range_count()
+    }
+    /// Returns whether no values are held
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Returns the set of values held at least once, dropping their counts
+    pub fn flatten(&self) -> Ranger<T> {
+        let mut ranger = Ranger::new();
+        for (span, _) in self.0.iter() {
+            ranger.insert_range(span.into());
+        }
+        ranger
+    }
+}
+
+impl<T: Discrete> FromIterator<T> for RangerMultiset<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut multiset = RangerMultiset::new();
+        for v in iter {
+            multiset.insert(v);
+        }
+        multiset
+    }
+}
+
+impl<T: Discrete + Display> Display for RangerMultiset<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (span, count)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}x{}", span, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn increments_split_runs() {
+        let mut lines: RangerMultiset<u32> = (1..=5).chain(1..=5).chain(1..=5).collect();
+        lines.insert(7);
+        assert_eq!(lines.to_string(), "1-5x3,7x1");
+        // mid-run, at either end and just past the end
+        assert_eq!(lines.insert(3), 4);
+        assert_eq!(lines.to_string(), "1-2x3,3x4,4-5x3,7x1");
+        lines.insert(1);
+        lines.insert(5);
+        assert_eq!(lines.to_string(), "1x4,2x3,3x4,4x3,5x4,7x1");
+        lines.insert(6);
+        assert_eq!(lines.to_string(), "1x4,2x3,3x4,4x3,5x4,6-7x1");
+        // raising the gaps rejoins the run
+        lines.insert(2);
+        lines.insert(4);
+        assert_eq!(lines.to_string(), "1-5x4,6-7x1");
+        assert_eq!(lines.range_count(), 2);
+        assert_eq!(lines.count_of(&3), 4);
+        assert_eq!(lines.count_of(&8), 0);
+    }
+
+    #[test]
+    fn decrements_split_and_drop_runs() {
+        let mut lines: RangerMultiset<i32> = (-2..=2).chain(0..=0).collect();
+        assert_eq!(lines.to_string(), "-2--1x1,0x2,1-2x1");
+        assert!(lines.remove(&0));
+        assert_eq!(lines.to_string(), "-2-2x1");
+        assert!(lines.remove(&0));
+        assert_eq!(lines.to_string(), "-2--1x1,1-2x1");
+        assert!(!lines.remove(&0));
+        assert_eq!(lines.flatten().to_string(), "-2--1,1-2");
+        for v in [-2, -1, 1, 2] {
+            assert!(lines.remove(&v));
+        }
+        assert!(lines.is_empty());
+        assert_eq!(lines.to_string(), "");
+    }
+
+    #[test]
+    fn flatten_joins_differing_counts() {
+        let lines: RangerMultiset<u8> = [1, 2, 2, 3, 3, 3, 9].into_iter().collect();
+        assert_eq!(lines.to_string(), "1x1,2x2,3x3,9x1");
+        assert_eq!(lines.flatten().to_string(), "1-3,9");
+        let spans: alloc::vec::Vec<_> = lines.iter().map(|(s, c)| (s.into_inner(), c)).collect();
+        assert_eq!(spans, [((1, 1), 1), ((2, 2), 2), ((3, 3), 3), ((9, 9), 1)]);
+    }
+
+    #[test]
+    fn random_against_hash_map() {
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let mut lines = RangerMultiset::<u8>::new();
+            let mut oracle = hashbrown::HashMap::<u8, u64>::new();
+            for _ in 0..200 {
+                let v = rng.gen_range(0..40);
+                if rng.gen_bool(0.3) {
+                    let held = oracle.get(&v).is_some_and(|&c| c > 0);
+                    assert_eq!(lines.remove(&v), held);
+                    if held {
+                        *oracle.get_mut(&v).unwrap() -= 1;
+                    }
+                } else {
+                    let count = oracle.entry(v).or_default();
+                    *count += 1;
+                    assert_eq!(lines.insert(v), *count);
+                }
+            }
+            for v in 0..=255u8 {
+                assert_eq!(lines.count_of(&v), oracle.get(&v).copied().unwrap_or(0));
+            }
+            // touching runs always differ in count
+            let runs: alloc::vec::Vec<_> = lines.iter().collect();
+            for pair in runs.windows(2) {
+                let ((a, x), (b, y)) = (&pair[0], &pair[1]);
+                assert!(!u8::adjacent(a.high(), b.low()) || x != y, "{lines}");
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{ApplyError, Discrete, Ranger};
+use core::ops::RangeInclusive;
+
+/// One change to a set, as applied in batches by [`Ranger::apply_ops`]
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum RangerOp<T> {
+    /// Inserts a single value
+    InsertValue(T),
+    /// Inserts every value in a range, which must not be reversed
+    InsertRange(RangeInclusive<T>),
+    /// Removes a single value
+    RemoveValue(T),
+    /// Removes every value in a range, which must not be reversed
+    RemoveRange(RangeInclusive<T>),
+}
+
+/// What a batch applied by [`Ranger::apply_ops`] changed
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
+pub struct ApplyReport {
+    /// Number of values inserted that were not already present, saturating
+    /// at `u128::MAX`
+    pub added: u128,
+    /// Number of values removed that were present, saturating at
+    /// `u128::MAX`
+    pub removed: u128,
+}
+
+impl<T: Discrete> Ranger<T> {
+    /// Applies `ops` in order, all or nothing: if any fails, the set is left
+    /// exactly as it was and the index of the failing op is returned with
+    /// the reason.
+    ///
+    /// Reversed ranges are caught by a check of the whole batch before
+    /// anything changes, which costs no memory. Only a set built
+    /// [`with_max_ranges`](Ranger::with_max_ranges) can fail partway, on
+    /// reaching its limit, so only then is the batch applied to a copy of
+    /// the set that replaces it on success, taking memory for a second set
+    /// of runs while it runs.
+    ///
+    /// ```
+    /// use ranger::{ApplyError, Ranger, RangerOp};
+    ///
+    /// let mut ranger: Ranger<u32> = "0-9".parse().unwrap();
+    /// let ops = [RangerOp::RemoveRange(3..=5), RangerOp::InsertValue(20)];
+    /// let report = ranger.apply_ops(&ops).unwrap();
+    /// assert_eq!((report.added, report.removed), (1, 3));
+    /// assert_eq!(ranger.to_string(), "0-2,6-9,20");
+    ///
+    /// let ops = [RangerOp::InsertValue(30), RangerOp::InsertRange(8..=4)];
+    /// assert_eq!(ranger.apply_ops(&ops), Err((1, ApplyError::ReversedRange)));
+    /// assert_eq!(ranger.to_string(), "0-2,6-9,20");
+    /// ```
+    pub fn apply_ops(&mut self, ops: &[RangerOp<T>]) -> Result<ApplyReport, (usize, ApplyError)> {
+        for (i, op) in ops.iter().enumerate() {
+            if let RangerOp::InsertRange(r) | RangerOp::RemoveRange(r) = op {
+                if r.is_empty() {
+                    return Err((i, ApplyError::ReversedRange));
+                }
+            }
+        }
+        if self.1.max_ranges.is_none() {
+            let report = apply(self, ops).expect("only a run limit fails partway");
+            return Ok(report);
+        }
+        let mut copy = self.clone();
+        let report = apply(&mut copy, ops)?;
+        *self = copy;
+        Ok(report)
+    }
+}
+
+/// Applies checked `ops` to `ranger` in order, stopping at the first that
+/// would exceed its run limit
+fn apply<T: Discrete>(
+    ranger: &mut Ranger<T>,
+    ops: &[RangerOp<T>],
+) -> Result<ApplyReport, (usize, ApplyError)> {
+    let mut report = ApplyReport::default();
+    for (i, op) in ops.iter().enumerate() {
+        let full = |_| (i, ApplyError::CapacityExceeded);
+        match op {
+            RangerOp::InsertValue(v) => {
+                let added = ranger.try_insert(v.clone()).map_err(full)?;
+                report.added = report.added.saturating_add(added as u128);
+            }
+            RangerOp::InsertRange(r) => {
+                let added = ranger.try_insert_range(r.clone()).map_err(full)?;
+                report.added = report.added.saturating_add(added);
+            }
+            RangerOp::RemoveValue(v) => {
+                report.removed = report.removed.saturating_add(ranger.remove(v) as u128);
+            }
+            RangerOp::RemoveRange(r) => {
+                let removed = ranger.remove_range(r.clone());
+                report.removed = report.removed.saturating_add(removed);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+    use rand::{thread_rng, Rng};
+
+    fn random_op(rng: &mut impl Rng) -> RangerOp<u8> {
+        let l = rng.gen_range(0..=250);
+        let h = l + rng.gen_range(0..5);
+        match rng.gen_range(0..4) {
+            0 => RangerOp::InsertValue(l),
+            1 => RangerOp::InsertRange(l..=h),
+            2 => RangerOp::RemoveValue(l),
+            _ => RangerOp::RemoveRange(l..=h),
+        }
+    }
+
+    #[test]
+    fn failing_batches_change_nothing() {
+        let mut ranger: Ranger<u8> = "1-5,10,20-30".parse().unwrap();
+        let before = ranger.clone();
+        #[allow(clippy::reversed_empty_ranges)]
+        let ops = [
+            RangerOp::InsertRange(40..=50),
+            RangerOp::RemoveValue(3),
+            RangerOp::RemoveRange(9..=8),
+        ];
+        assert_eq!(ranger.apply_ops(&ops), Err((2, ApplyError::ReversedRange)));
+        assert_eq!(ranger.to_string(), before.to_string());
+
+        let mut limited = Ranger::with_max_ranges(3);
+        limited.insert_range(1..=5);
+        limited.insert(10);
+        let before = limited.clone();
+        let ops = [
+            RangerOp::RemoveValue(10),
+            RangerOp::InsertValue(20),
+            RangerOp::InsertRange(30..=32),
+            RangerOp::InsertValue(40),
+        ];
+        assert_eq!(
+            limited.apply_ops(&ops),
+            Err((3, ApplyError::CapacityExceeded))
+        );
+        assert_eq!(limited.to_string(), before.to_string());
+        // the limit is still in force after a rollback
+        let report = limited.apply_ops(&ops[..3]).unwrap();
+        assert_eq!((report.added, report.removed), (4, 1));
+        assert_eq!(limited.to_string(), "1-5,20,30-32");
+        assert_eq!(limited.try_insert(40), Err(crate::CapacityExceeded));
+    }
+
+    #[test]
+    fn matches_sequential_application() {
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let ops: Vec<_> = (0..rng.gen_range(0..30))
+                .map(|_| random_op(&mut rng))
+                .collect();
+            let mut batched: Ranger<u8> = "0-9,50-99,200".parse().unwrap();
+            let mut sequential = batched.clone();
+            let report = batched.apply_ops(&ops).unwrap();
+            let (mut added, mut removed) = (0, 0);
+            for op in &ops {
+                match op.clone() {
+                    RangerOp::InsertValue(v) => added += sequential.insert(v) as u128,
+                    RangerOp::InsertRange(r) => added += sequential.insert_range(r),
+                    RangerOp::RemoveValue(v) => removed += sequential.remove(&v) as u128,
+                    RangerOp::RemoveRange(r) => removed += sequential.remove_range(r),
+                }
+            }
+            assert_eq!(batched, sequential);
+            assert_eq!(report, ApplyReport { added, removed });
+        }
+    }
+
+    #[test]
+    fn empty_batch() {
+        let mut ranger: Ranger<i32> = "-3-3".parse().unwrap();
+        assert_eq!(ranger.apply_ops(&[]), Ok(ApplyReport::default()));
+        assert_eq!(ranger.to_string(), "-3-3");
+        let mut limited = Ranger::<i32>::with_max_ranges(0);
+        assert_eq!(limited.apply_ops(&[]), Ok(ApplyReport::default()));
+        assert_eq!(
+            limited.apply_ops(&[RangerOp::InsertValue(1)]),
+            Err((0, ApplyError::CapacityExceeded))
+        );
+        assert!(limited.is_empty());
+    }
+}
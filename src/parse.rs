@@ -1,4 +1,6 @@
-use core::fmt;
+use crate::{discrete::step_up, Config, Discrete, Ranger};
+use alloc::collections::BTreeSet;
+use core::{fmt, str::FromStr};
 
 /// An error which can be returned when parsing MAC address.
 ///
@@ -29,16 +31,245 @@ pub enum ParseError {
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParseError::InvalidLength(len) => f.write_fmt(format_args!("Invalid length of {} characters", len,)),
-            ParseError::InvalidCharacter(chr, pos) => {
-                f.write_fmt(format_args!("Unexpected character '{}' at position {}", chr, pos,))
+            ParseError::InvalidLength(len) => {
+                f.write_fmt(format_args!("Invalid length of {} characters", len,))
             }
+            ParseError::InvalidCharacter(chr, pos) => f.write_fmt(format_args!(
+                "Unexpected character '{}' at position {}",
+                chr, pos,
+            )),
         }
     }
 }
 
 #[cfg(feature = "std")]
-impl Error for ParseError {}
+impl std::error::Error for ParseError {}
+
+/// An error which can be returned when parsing a list of ranges.
+///
+/// This error is used as the error type for the `FromStr` implementation
+/// for [Ranger], which reads the same form its `Display` writes, such as
+/// `"0-2,4,6-8"`.
+///
+/// Each enum member will contain the byte position of the offending entry
+/// when returned.
+///
+/// [Ranger]: ../struct.Ranger.html
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum ParseRangeError {
+    /// A comma-separated entry is empty, as in `"1,,3"` or `"1,"`.
+    Empty(usize),
+
+    /// An entry is neither a single value nor two values joined by `-`, or
+    /// by the separator given to [`Ranger::parse_with`], or the step after
+    /// its `/` is not a positive integer, or it steps through more than
+    /// 65536 values.
+    Invalid(usize),
+
+    /// An entry's low end is above its high end, as in `"8-6"`.
+    Reversed(usize),
+}
+
+impl fmt::Display for ParseRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRangeError::Empty(pos) => write!(f, "Empty range at position {}", pos),
+            ParseRangeError::Invalid(pos) => write!(f, "Invalid range at position {}", pos),
+            ParseRangeError::Reversed(pos) => {
+                write!(f, "Range low end above its high end at position {}", pos)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseRangeError {}
+
+impl<T> FromStr for Ranger<T>
+where
+    T: FromStr + Discrete,
+{
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_list(s, "-", |v| v.parse().ok())
+    }
+}
+
+impl<T: FromStr + Discrete> Ranger<T> {
+    /// Parses a list of ranges whose ends are joined by `separator` rather
+    /// than `-`, as written by [`display_with`](Ranger::display_with)
+    pub fn parse_with(s: &str, separator: &str) -> Result<Self, ParseRangeError> {
+        parse_list(s, separator, |v| v.parse().ok())
+    }
+}
+
+/// Most values a single stepped entry may name. Each becomes a run of its
+/// own, so without a limit a short entry such as `0-4294967295/2` could
+/// demand billions of them.
+const MAX_STEPPED_VALUES: u128 = 1 << 16;
+
+/// Reads comma-separated entries, each a single value or two values joined by
+/// `separator` and optionally followed by a `/step`, reading values with
+/// `value`
+pub(crate) fn parse_list<T: Discrete>(
+    s: &str,
+    separator: &str,
+    value: impl Fn(&str) -> Option<T>,
+) -> Result<Ranger<T>, ParseRangeError> {
+    let mut ranger = Ranger(BTreeSet::new(), Config::default());
+    walk_list(s, separator, value, |low, high| {
+        ranger.insert_range(low..=high);
+    })?;
+    Ok(ranger)
+}
+
+/// Reads a list as [`parse_list`] does, but hands each run it names to
+/// `piece` in the order written instead of collecting them, a stepped entry
+/// giving one single-value run per step. Stops at the first malformed entry,
+/// after the runs of those before it.
+fn walk_list<T: Discrete>(
+    s: &str,
+    separator: &str,
+    value: impl Fn(&str) -> Option<T>,
+    mut piece: impl FnMut(T, T),
+) -> Result<(), ParseRangeError> {
+    if s.trim().is_empty() {
+        return Ok(());
+    }
+    let mut pos = 0;
+    for entry in s.split(',') {
+        // values may themselves contain a `/`, so a step is only split off
+        // an entry that doesn't read as a plain range
+        let (low, high, step) = match parse_entry(entry, pos, separator, &value) {
+            Ok((low, high)) => (low, high, 1),
+            Err(ParseRangeError::Invalid(_)) if entry.contains('/') => {
+                let (range, step) = entry.rsplit_once('/').unwrap();
+                let step = step.trim().parse::<u128>().ok().filter(|s| *s > 0);
+                let step = step.ok_or(ParseRangeError::Invalid(pos))?;
+                let (low, high) = parse_entry(range, pos, separator, &value)?;
+                let values = T::distance(&low, &high).map(|d| d / step + 1);
+                if values.is_none_or(|n| n > MAX_STEPPED_VALUES) {
+                    return Err(ParseRangeError::Invalid(pos));
+                }
+                (low, high, step)
+            }
+            Err(e) => return Err(e),
+        };
+        if step == 1 {
+            piece(low, high);
+        } else {
+            let mut v = Some(low);
+            while let Some(low) = v.take().filter(|v| v <= &high) {
+                v = low.forward(step);
+                piece(low.clone(), low);
+            }
+        }
+        pos += entry.len() + 1;
+    }
+    Ok(())
+}
+
+impl<T: FromStr + Discrete> Ranger<T> {
+    /// Returns whether parsing `spec` would give exactly this set, failing
+    /// as parsing would if `spec` is malformed, but without allocating.
+    ///
+    /// A spec listing its entries in ascending order, as `Display` writes
+    /// them, is checked in one pass alongside the set's runs. Entries out of
+    /// order need further passes over the spec for each run.
+    pub fn matches_spec(&self, spec: &str) -> Result<bool, ParseRangeError> {
+        let value = |v: &str| v.parse().ok();
+        let mut units = self.0.iter();
+        // the run the entries read so far join into, and whether any before
+        // it differed from the set or came out of order
+        let mut run: Option<(T, T)> = None;
+        let (mut differs, mut unsorted) = (false, false);
+        let mut matches = |l: &T, h: &T| units.next().is_some_and(|u| &u.l == l && u.high() == h);
+        walk_list(spec, "-", value, |low, high| {
+            if unsorted {
+                return;
+            }
+            run = match run.take() {
+                None => Some((low, high)),
+                Some((l, _)) if low < l => {
+                    unsorted = true;
+                    None
+                }
+                Some((l, h)) if low <= h || T::adjacent(&h, &low) => Some((l, h.max(high))),
+                Some((l, h)) => {
+                    differs |= !matches(&l, &h);
+                    Some((low, high))
+                }
+            };
+        })?;
+        if !unsorted {
+            if let Some((l, h)) = run {
+                differs |= !matches(&l, &h);
+            }
+            return Ok(!differs && units.next().is_none());
+        }
+        Ok(self.covered_by_spec(spec))
+    }
+    /// Returns whether the runs of an already validated `spec`, in any
+    /// order, together hold exactly the stored values
+    fn covered_by_spec(&self, spec: &str) -> bool {
+        let value = |v: &str| v.parse().ok();
+        let mut within = true;
+        let _ = walk_list(spec, "-", value, |low, high| {
+            within &= self.contains_range(&(low..=high));
+        });
+        // every value is then in the spec's runs if each stored run is, found
+        // by moving through it from one spec run that reaches furthest to
+        // the next
+        within
+            && self.0.iter().all(|u| {
+                let mut at = u.l.clone();
+                loop {
+                    let mut reach: Option<T> = None;
+                    let _ = walk_list(spec, "-", value, |low, high| {
+                        if low <= at && at <= high && reach.as_ref().is_none_or(|r| r < &high) {
+                            reach = Some(high);
+                        }
+                    });
+                    match reach {
+                        Some(r) if &r >= u.high() => return true,
+                        Some(r) => at = step_up(&r),
+                        None => return false,
+                    }
+                }
+            })
+    }
+}
+
+/// Reads a single value or a `low-high` pair. Since the separator may also
+/// appear within values, such as a sign, every split point is tried from the
+/// left until both halves parse.
+fn parse_entry<T: Ord + Clone>(
+    entry: &str,
+    pos: usize,
+    separator: &str,
+    value: impl Fn(&str) -> Option<T>,
+) -> Result<(T, T), ParseRangeError> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return Err(ParseRangeError::Empty(pos));
+    }
+    if let Some(v) = value(entry) {
+        return Ok((v.clone(), v));
+    }
+    for (i, _) in entry.match_indices(separator).filter(|(i, _)| *i > 0) {
+        if let (Some(low), Some(high)) = (
+            value(entry[..i].trim()),
+            value(entry[i + separator.len()..].trim()),
+        ) {
+            if low > high {
+                return Err(ParseRangeError::Reversed(pos));
+            }
+            return Ok((low, high));
+        }
+    }
+    Err(ParseRangeError::Invalid(pos))
+}
 
 #[derive(Debug, Eq, PartialEq)]
 enum Delimiter {
@@ -185,4 +416,149 @@ impl<'a> Parser<'a> {
 
     //     self.read_v8_addr().map(Into::into)
     // }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn from_str() {
+        let ranger: Ranger<i8> = "-128--126,-1-2,4,6-8,11-12,14-25,27-33,35-39,125-127"
+            .parse()
+            .unwrap();
+        assert_eq!(ranger.len(), 40);
+        assert_eq!(
+            ranger.to_string(),
+            "-128--126,-1-2,4,6-8,11-12,14-25,27-33,35-39,125-127"
+        );
+        let ranger: Ranger<u16> = "8, 3-5 ,1-2,4,10-10".parse().unwrap();
+        assert_eq!(ranger.to_string(), "1-5,8,10");
+        assert_eq!("".parse::<Ranger<u16>>().unwrap(), Ranger::new());
+        assert_eq!("-5".parse::<Ranger<i32>>().unwrap().to_string(), "-5");
+
+        assert_eq!("1,,3".parse::<Ranger<u8>>(), Err(ParseRangeError::Empty(2)));
+        assert_eq!("1,".parse::<Ranger<u8>>(), Err(ParseRangeError::Empty(2)));
+        assert_eq!(
+            "0-2,8-6".parse::<Ranger<u8>>(),
+            Err(ParseRangeError::Reversed(4))
+        );
+        assert_eq!(
+            "-3--5".parse::<Ranger<i8>>(),
+            Err(ParseRangeError::Reversed(0))
+        );
+        assert_eq!(
+            "1-x".parse::<Ranger<u8>>(),
+            Err(ParseRangeError::Invalid(0))
+        );
+        assert_eq!(
+            "4,-1".parse::<Ranger<u8>>(),
+            Err(ParseRangeError::Invalid(2))
+        );
+        assert_eq!(
+            "1-2-3".parse::<Ranger<u8>>(),
+            Err(ParseRangeError::Invalid(0))
+        );
+        assert_eq!(
+            "256".parse::<Ranger<u8>>(),
+            Err(ParseRangeError::Invalid(0))
+        );
+        assert_eq!(
+            ParseRangeError::Reversed(4).to_string(),
+            "Range low end above its high end at position 4"
+        );
+    }
+
+    #[test]
+    fn matches_spec() {
+        let ranger: Ranger<u8> = "1-5,8,10-12".parse().unwrap();
+        for (spec, expected) in [
+            ("1-5,8,10-12", true),
+            (" 1-5, 8 ,10-12", true),
+            // overlapping and touching entries, in order and out of it
+            ("1-3,2-5,8,10,11-12", true),
+            ("10-12,1-2,8,3-5", true),
+            ("8,12,11,10,5,4,3,2,1", true),
+            ("1-5/2,2-4/2,8-12/2,11", true),
+            // a subset, a superset and near misses
+            ("1-5,8", false),
+            ("1-5,8,10-13", false),
+            ("1-5,7-8,10-12", false),
+            ("1-4,8,10-12", false),
+            ("0-5,8,10-12", false),
+            ("10-12,8,1-4", false),
+            ("10-12,8,1-5,6", false),
+            ("", false),
+        ] {
+            assert_eq!(ranger.matches_spec(spec), Ok(expected), "{spec}");
+        }
+        assert_eq!(Ranger::<u8>::new().matches_spec(" "), Ok(true));
+        // malformed specs fail even after a difference
+        assert_eq!(
+            ranger.matches_spec("1,2,x"),
+            Err(ParseRangeError::Invalid(4))
+        );
+        assert_eq!(
+            ranger.matches_spec("9,1-5,,"),
+            Err(ParseRangeError::Empty(6))
+        );
+        assert_eq!(
+            ranger.matches_spec("1-5,9-8"),
+            Err(ParseRangeError::Reversed(4))
+        );
+    }
+
+    #[test]
+    fn matches_spec_agrees_with_parse() {
+        use alloc::{string::String, vec::Vec};
+        use rand::{seq::SliceRandom, thread_rng, Rng};
+
+        let mut rng = thread_rng();
+        for _ in 0..2000 {
+            let mut entries: Vec<String> = (0..rng.gen_range(0..6))
+                .map(|_| {
+                    let l = rng.gen_range(0..40u8);
+                    match rng.gen_range(0..10) {
+                        0 => l.to_string(),
+                        1 => alloc::format!(
+                            "{}-{}/{}",
+                            l,
+                            l + rng.gen_range(0..10),
+                            rng.gen_range(1..4)
+                        ),
+                        2 if rng.gen_ratio(1, 10) => String::from("x"),
+                        _ => alloc::format!("{}-{}", l, l + rng.gen_range(0..10)),
+                    }
+                })
+                .collect();
+            let spec = entries.join(",");
+            let parsed = spec.parse::<Ranger<u8>>();
+            let mut ranger = parsed.clone().unwrap_or_default();
+            // the set itself, or one differing by a value
+            if rng.gen_bool(0.5) {
+                let v = rng.gen_range(0..50);
+                if !ranger.remove(&v) {
+                    ranger.insert(v);
+                }
+            }
+            let expected = parsed.map(|p| p == ranger);
+            assert_eq!(ranger.matches_spec(&spec), expected, "{spec} {ranger}");
+            entries.shuffle(&mut rng);
+            let spec = entries.join(",");
+            let expected = spec.parse::<Ranger<u8>>().map(|p| p == ranger);
+            assert_eq!(ranger.matches_spec(&spec), expected, "{spec} {ranger}");
+        }
+    }
+
+    #[test]
+    fn separator() {
+        let ranger = Ranger::<i8>::parse_with("-5..-3, 0,2..4", "..").unwrap();
+        assert_eq!(ranger.to_string(), "-5--3,0,2-4");
+        assert_eq!(ranger.display_with("..").to_string(), "-5..-3,0,2..4");
+        assert_eq!(
+            Ranger::<i8>::parse_with("1-3", ".."),
+            Err(ParseRangeError::Invalid(0))
+        );
+    }
+}
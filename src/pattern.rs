@@ -0,0 +1,342 @@
+use crate::{Discrete, Ranger};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+use num_traits::ToPrimitive;
+
+/// A pattern no string matches, for the empty set
+const NOTHING: &str = r"[^\s\S]";
+
+impl<T: Discrete + ToPrimitive> Ranger<T> {
+    /// Returns a regular expression matching the decimal form of exactly the
+    /// stored values, as an alternation of one branch per block of values
+    /// sharing a digit prefix, such as `\d|[1-9]\d|1\d{2}|2[0-4]\d|25[0-5]`
+    /// for `0-255`. Negative values are matched with their `-`, and no value
+    /// with a leading zero or `+`.
+    ///
+    /// The pattern is not anchored, so wrap it as `^(?:…)$` to match whole
+    /// strings. It uses only digits, classes such as `[0-4]`, `\d` and
+    /// counted repetition, which every common regex dialect reads alike.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let ids: Ranger<u32> = "7,10-39,120-123".parse().unwrap();
+    /// assert_eq!(ids.to_regex(), r"7|[1-3]\d|12[0-3]");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a stored value does not fit in 128 bits.
+    pub fn to_regex(&self) -> String {
+        let mut out = String::new();
+        for u in self.0.iter() {
+            match (u.l.to_u128(), u.high().to_u128()) {
+                (Some(l), Some(h)) => push_run(&mut out, "", l, h),
+                (None, Some(h)) => {
+                    push_run(&mut out, "-", 1, magnitude(&u.l));
+                    push_run(&mut out, "", 0, h);
+                }
+                _ => push_run(&mut out, "-", magnitude(u.high()), magnitude(&u.l)),
+            }
+        }
+        if out.is_empty() {
+            out.push_str(NOTHING);
+        }
+        out
+    }
+}
+
+impl Ranger<char> {
+    /// Returns a bracketed character class matching exactly the stored
+    /// characters, such as `[0-9A-F_a-f]`. Characters with a meaning inside a
+    /// class (`\`, `[`, `]`, `^`, `-`, `&` and `~`) are escaped with a
+    /// backslash, and anything but visible ASCII is written as `\u{…}`. The
+    /// empty set gives a class no character matches.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let hex: Ranger<char> = "0-9,A-F,_,a-f".parse().unwrap();
+    /// assert_eq!(hex.to_char_class(), "[0-9A-F_a-f]");
+    /// assert_eq!(hex.to_negated_char_class(), "[^0-9A-F_a-f]");
+    /// ```
+    pub fn to_char_class(&self) -> String {
+        self.char_class(false)
+    }
+    /// Returns a negated character class matching exactly the characters
+    /// not stored, written as [`to_char_class`](Self::to_char_class) writes
+    /// the stored ones. The empty set gives a class every character matches.
+    pub fn to_negated_char_class(&self) -> String {
+        self.char_class(true)
+    }
+    fn char_class(&self, negated: bool) -> String {
+        if self.is_empty() {
+            // a class must hold something, so name every character instead
+            return String::from(if negated {
+                r"[\u{0}-\u{10FFFF}]"
+            } else {
+                r"[^\u{0}-\u{10FFFF}]"
+            });
+        }
+        let mut out = String::from(if negated { "[^" } else { "[" });
+        for u in self.0.iter() {
+            let (l, h) = (u.l, *u.high());
+            push_class_char(&mut out, l);
+            if h != l {
+                // two characters read more plainly without the dash
+                if !char::adjacent(&l, &h) {
+                    out.push('-');
+                }
+                push_class_char(&mut out, h);
+            }
+        }
+        out.push(']');
+        out
+    }
+}
+
+/// Appends `c` as it must be written inside a character class
+fn push_class_char(out: &mut String, c: char) {
+    match c {
+        '\\' | '[' | ']' | '^' | '-' | '&' | '~' => {
+            out.push('\\');
+            out.push(c);
+        }
+        c if c.is_ascii_graphic() => out.push(c),
+        c => {
+            let _ = write!(out, r"\u{{{:X}}}", c as u32);
+        }
+    }
+}
+
+/// Returns the absolute value of a negative `v`
+fn magnitude<T: ToPrimitive>(v: &T) -> u128 {
+    v.to_i128()
+        .expect("stored values fit in 128 bits")
+        .unsigned_abs()
+}
+
+/// Appends a branch for each block of `low..=high` that one digit pattern
+/// covers, each preceded by `sign`
+fn push_run(out: &mut String, sign: &str, low: u128, high: u128) {
+    let mut start = low;
+    for stop in block_ends(low, high) {
+        if !out.is_empty() {
+            out.push('|');
+        }
+        out.push_str(sign);
+        push_block(out, start, stop);
+        start = stop.wrapping_add(1);
+    }
+}
+
+/// Returns in ascending order the ends of the blocks `low..=high` splits
+/// into, each block running from a number to one with the same leading
+/// digits and only nines after them. Blocks grow towards the middle of the
+/// run by a digit at a time: from `low` up to the next ends in 9, 99, and so
+/// on, and from `high` down to the last ends in 0, 00, and so on.
+fn block_ends(low: u128, high: u128) -> Vec<u128> {
+    let mut ends = vec![high];
+    let mut p = 10u128;
+    loop {
+        match (low - low % p).checked_add(p - 1) {
+            // a `low` ending in zeros starts the next block up as well, as
+            // long as that block keeps its number of digits
+            Some(_) if low.is_multiple_of(p) && low >= p => {}
+            Some(end) if end < high => ends.push(end),
+            _ => break,
+        }
+        let Some(next) = p.checked_mul(10) else { break };
+        p = next;
+    }
+    let mut p = 10u128;
+    loop {
+        // the last value before `high + 1` rounded down to a multiple of p
+        match high.checked_sub((high % p + 1) % p) {
+            Some(end) if end > low => ends.push(end),
+            _ => break,
+        }
+        let Some(next) = p.checked_mul(10) else { break };
+        p = next;
+    }
+    ends.sort_unstable();
+    ends.dedup();
+    ends
+}
+
+/// Appends the pattern for `start..=stop`, two numbers of the same length
+/// that agree on their leading digits and then run from zeros to nines
+/// after one position that may differ
+fn push_block(out: &mut String, start: u128, stop: u128) {
+    let (start, stop) = (start.to_string(), stop.to_string());
+    debug_assert_eq!(start.len(), stop.len());
+    let mut any = 0;
+    for (a, b) in start.chars().zip(stop.chars()) {
+        if a == b {
+            out.push(a);
+        } else if a == '0' && b == '9' {
+            any += 1;
+        } else {
+            out.extend(['[', a, '-', b, ']']);
+        }
+    }
+    match any {
+        0 => {}
+        1 => out.push_str(r"\d"),
+        n => {
+            out.push_str(r"\d{");
+            out.push_str(&n.to_string());
+            out.push('}');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::vec::Vec;
+    use rand::{seq::SliceRandom, thread_rng, Rng};
+    use regex::Regex;
+
+    fn whole(ranger: &Ranger<impl Discrete + ToPrimitive>) -> Regex {
+        Regex::new(&format!("^(?:{})$", ranger.to_regex())).unwrap()
+    }
+
+    #[test]
+    fn known_patterns() {
+        let cases = [
+            ("0-255", r"\d|[1-9]\d|1\d{2}|2[0-4]\d|25[0-5]"),
+            ("0-9", r"\d"),
+            ("5", "5"),
+            ("10-99", r"[1-9]\d"),
+            ("100-999", r"[1-9]\d{2}"),
+            ("19-21", "19|2[0-1]"),
+            ("0,2,4", "0|2|4"),
+            ("10-35", r"[1-2]\d|3[0-5]"),
+            ("200-259", r"2[0-5]\d"),
+            ("25-1000", r"2[5-9]|[3-9]\d|[1-9]\d{2}|1000"),
+        ];
+        for (list, pattern) in cases {
+            let ranger: Ranger<u32> = list.parse().unwrap();
+            assert_eq!(ranger.to_regex(), pattern, "{list}");
+        }
+        let ranger: Ranger<i32> = "-12--3,0-1".parse().unwrap();
+        assert_eq!(ranger.to_regex(), r"-[3-9]|-1[0-2]|[0-1]");
+        let ranger: Ranger<i32> = "-2-2".parse().unwrap();
+        assert_eq!(ranger.to_regex(), r"-[1-2]|[0-2]");
+        assert_eq!(Ranger::<u8>::new().to_regex(), NOTHING);
+    }
+
+    #[test]
+    fn every_u8_set() {
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let mut ranger = Ranger::<u8>::new();
+            for _ in 0..rng.gen_range(0..6) {
+                let l = rng.gen_range(0..=255);
+                ranger.insert_range(l..=l.saturating_add(rng.gen_range(0..80)));
+            }
+            let re = whole(&ranger);
+            for v in 0..1000u32 {
+                let stored = u8::try_from(v).is_ok_and(|v| ranger.contains(&v));
+                assert_eq!(re.is_match(&v.to_string()), stored, "{ranger} {v}");
+            }
+            assert!(!re.is_match("007") && !re.is_match("+1") && !re.is_match(""));
+        }
+    }
+
+    #[test]
+    fn random_wide_runs() {
+        let mut rng = thread_rng();
+        for _ in 0..20 {
+            let mut ranger = Ranger::<i32>::new();
+            for _ in 0..rng.gen_range(1..4) {
+                let l = rng.gen_range(-70_000..70_000);
+                ranger.insert_range(l..=l + rng.gen_range(0..30_000));
+            }
+            let re = whole(&ranger);
+            let (low, high) = (ranger.first_range().unwrap(), ranger.last_range().unwrap());
+            for v in low.start() - 1_000..=high.end() + 1_000 {
+                assert_eq!(re.is_match(&v.to_string()), ranger.contains(&v), "{v}");
+            }
+            assert!(!re.is_match("-0"));
+        }
+    }
+
+    #[test]
+    fn char_classes() {
+        let cases: [(&[_], _); 6] = [
+            (&['a'..='z'], "[a-z]"),
+            (&['a'..='b', 'x'..='x'], "[abx]"),
+            (&['-'..='-', '['..='^'], r"[\-\[-\^]"),
+            (&['&'..='&', '~'..='~'], r"[\&\~]"),
+            (
+                &[' '..=' ', 'é'..='é', '😀'..='😀'],
+                r"[\u{20}\u{E9}\u{1F600}]",
+            ),
+            (&['\0'..='\u{1f}'], r"[\u{0}-\u{1F}]"),
+        ];
+        for (runs, class) in cases {
+            let mut ranger = Ranger::new();
+            for r in runs {
+                ranger.insert_range(r.clone());
+            }
+            assert_eq!(ranger.to_char_class(), class, "{runs:?}");
+        }
+        let empty = Ranger::<char>::new();
+        assert_eq!(empty.to_char_class(), r"[^\u{0}-\u{10FFFF}]");
+        assert_eq!(empty.to_negated_char_class(), r"[\u{0}-\u{10FFFF}]");
+    }
+
+    #[test]
+    fn char_classes_agree_with_contains() {
+        let mut rng = thread_rng();
+        // every character with a meaning in classes, and some far beyond
+        let mut samples: Vec<char> = ('\0'..='\u{ff}').collect();
+        samples.extend(['\u{d7ff}', '\u{e000}', '\u{fffd}', '\u{10000}', char::MAX]);
+        samples.extend((0..500).map(|_| rng.gen::<char>()));
+        for _ in 0..100 {
+            let mut ranger = Ranger::<char>::new();
+            for _ in 0..rng.gen_range(0..8) {
+                let l = *samples.choose(&mut rng).unwrap();
+                let h = samples[rng.gen_range(0..samples.len())].max(l);
+                if rng.gen_bool(0.5) {
+                    ranger.insert(l);
+                } else {
+                    ranger.insert_range(l..=h);
+                }
+            }
+            let class = Regex::new(&format!("^{}$", ranger.to_char_class())).unwrap();
+            let negated = Regex::new(&format!("^{}$", ranger.to_negated_char_class())).unwrap();
+            let mut buf = [0; 4];
+            for c in &samples {
+                let c = c.encode_utf8(&mut buf);
+                let stored = ranger.contains(&c.chars().next().unwrap());
+                assert_eq!(class.is_match(c), stored, "{ranger:?} {c:?}");
+                assert_eq!(negated.is_match(c), !stored, "{ranger:?} {c:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn extreme_values() {
+        let ranger = Ranger::from(0..=u128::MAX);
+        let re = whole(&ranger);
+        assert!(re.is_match(&u128::MAX.to_string()));
+        assert!(!re.is_match(&(u128::MAX as f64 * 10.0).to_string()));
+        let ranger = Ranger::from([i128::MIN, i128::MAX]);
+        let re = whole(&ranger);
+        assert!(re.is_match(&i128::MIN.to_string()) && re.is_match(&i128::MAX.to_string()));
+        assert!(!re.is_match(&(i128::MAX - 1).to_string()));
+        let ranger = Ranger::from(u128::MAX - 20..=u128::MAX);
+        let re = whole(&ranger);
+        for v in u128::MAX - 30..=u128::MAX {
+            assert_eq!(re.is_match(&v.to_string()), ranger.contains(&v));
+        }
+    }
+}
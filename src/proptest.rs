@@ -0,0 +1,110 @@
+//! Strategies for generating sets in property tests
+
+use crate::{Config, Discrete, Ranger};
+use ::proptest::{collection::vec, prelude::*};
+use alloc::collections::BTreeSet;
+use core::{fmt::Debug, ops::RangeInclusive};
+
+/// Runs are drawn narrow more often than not, so that sets keep some shape
+/// instead of merging into one run
+const NARROW: u128 = 15;
+
+/// Generates sets of values drawn from `values`, holding at most `max_runs`
+/// runs. Shrinks toward fewer, narrower runs.
+pub fn ranger_of<T>(values: RangeInclusive<T>, max_runs: usize) -> impl Strategy<Value = Ranger<T>>
+where
+    T: Discrete + Debug,
+    RangeInclusive<T>: Strategy<Value = T>,
+{
+    let end = values.end().clone();
+    let widest = T::distance(values.start(), values.end()).unwrap_or(u128::MAX);
+    let widths = prop_oneof![3 => 0..=widest.min(NARROW), 1 => 0..=widest];
+    vec((values, widths), 0..=max_runs).prop_map(move |runs| {
+        let mut ranger = Ranger(BTreeSet::new(), Config::default());
+        for (low, w) in runs {
+            let high = low
+                .forward(w)
+                .filter(|h| h <= &end)
+                .unwrap_or_else(|| end.clone());
+            ranger.insert_range(low..=high);
+        }
+        ranger
+    })
+}
+
+/// Generates a non-empty set as [`ranger_of`] does, along with one of the
+/// values it holds
+pub fn ranger_with_value<T>(
+    values: RangeInclusive<T>,
+    max_runs: usize,
+) -> impl Strategy<Value = (Ranger<T>, T)>
+where
+    T: Discrete + Debug,
+    RangeInclusive<T>: Strategy<Value = T>,
+{
+    (ranger_of(values.clone(), max_runs), values).prop_map(|(mut ranger, v)| {
+        ranger.insert_range(v.clone()..=v.clone());
+        (ranger, v)
+    })
+}
+
+/// Generates a set as [`ranger_of`] does, along with a value from `values`
+/// it does not hold
+pub fn ranger_without_value<T>(
+    values: RangeInclusive<T>,
+    max_runs: usize,
+) -> impl Strategy<Value = (Ranger<T>, T)>
+where
+    T: Discrete + Debug,
+    RangeInclusive<T>: Strategy<Value = T>,
+{
+    (ranger_of(values.clone(), max_runs), values).prop_map(|(mut ranger, v)| {
+        ranger.remove(&v);
+        (ranger, v)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    proptest! {
+        #[test]
+        fn insertion_order_is_irrelevant(
+            values in ranger_of(i8::MIN..=i8::MAX, 12)
+                .prop_map(|r| r.0.iter().flat_map(|u| u.values()).collect::<Vec<_>>())
+                .prop_shuffle()
+        ) {
+            let mut ranger = Ranger::new();
+            for v in values.iter() {
+                ranger.insert(*v);
+            }
+            let mut sorted = values.clone();
+            sorted.sort();
+            let mut expected = Ranger::new();
+            for v in sorted {
+                expected.insert(v);
+            }
+            prop_assert_eq!(ranger, expected);
+        }
+
+        #[test]
+        fn runs_stay_within_bounds(ranger in ranger_of(100u16..=5000, 8)) {
+            prop_assert!(ranger.range_count() <= 8);
+            if let (Some(first), Some(last)) = (ranger.0.first(), ranger.0.last()) {
+                prop_assert!(first.l >= 100 && *last.high() <= 5000);
+            }
+        }
+
+        #[test]
+        fn membership((ranger, v) in ranger_with_value(0u32..=1000, 6)) {
+            prop_assert!(ranger.contains(&v));
+        }
+
+        #[test]
+        fn non_membership((ranger, v) in ranger_without_value(i64::MIN..=i64::MAX, 6)) {
+            prop_assert!(!ranger.contains(&v));
+        }
+    }
+}
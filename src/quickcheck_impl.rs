@@ -0,0 +1,82 @@
+use crate::{Config, Discrete, Ranger, Unit};
+use alloc::{boxed::Box, collections::BTreeSet};
+use quickcheck::{Arbitrary, Gen};
+
+/// Draws up to a quarter of the generator's size in runs, each starting at
+/// an arbitrary value and spanning at most a few hundred values
+impl<T> Arbitrary for Ranger<T>
+where
+    T: Arbitrary + Discrete,
+{
+    fn arbitrary(g: &mut Gen) -> Self {
+        let runs = usize::arbitrary(g) % (g.size() / 4 + 1);
+        let mut ranger = Ranger(BTreeSet::new(), Config::default());
+        for _ in 0..runs {
+            let low = T::arbitrary(g);
+            let w = u8::arbitrary(g) as u128;
+            // stop at the top of the type rather than wrapping
+            let high = (0..=w).rev().find_map(|w| low.forward(w)).unwrap();
+            ranger.insert_range(low..=high);
+        }
+        ranger
+    }
+
+    /// Tries dropping each run, then halving each run from its high end
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let runs = self.0.len();
+        let without = self.clone();
+        let narrowed = self.clone();
+        Box::new(
+            (0..runs)
+                .map(move |i| {
+                    let mut ranger = without.clone();
+                    let u = ranger.0.iter().nth(i).unwrap().clone();
+                    ranger.0.remove(&u);
+                    ranger
+                })
+                .chain((0..runs).filter_map(move |i| {
+                    let u = narrowed.0.iter().nth(i)?;
+                    let w = T::distance(&u.l, u.high())?;
+                    if w == 0 {
+                        return None;
+                    }
+                    let high = u.l.forward(w / 2)?;
+                    let mut ranger = narrowed.clone();
+                    let u = u.clone();
+                    ranger.0.remove(&u);
+                    ranger.0.insert(Unit::new(u.l, high));
+                    Some(ranger)
+                })),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn insert_then_contains(mut ranger: Ranger<u16>, v: u16) -> bool {
+        ranger.insert(v);
+        ranger.contains(&v)
+    }
+
+    #[quickcheck]
+    fn shrinks_are_smaller(ranger: Ranger<i32>) -> bool {
+        ranger
+            .shrink()
+            .all(|s| s.range_count() < ranger.range_count() || s.len() < ranger.len())
+    }
+
+    #[test]
+    fn shrink() {
+        let ranger: Ranger<u8> = "1-4,10,250-255".parse().unwrap();
+        let shrunk: Vec<_> = ranger.shrink().map(|r| r.range_count()).collect();
+        assert_eq!(shrunk, [2, 2, 2, 3, 3]);
+        let shrunk: Vec<Ranger<u8>> = ranger.shrink().collect();
+        assert_eq!(shrunk[3], "1-2,10,250-255".parse::<Ranger<u8>>().unwrap());
+        assert_eq!(shrunk[4], "1-4,10,250-252".parse::<Ranger<u8>>().unwrap());
+    }
+}
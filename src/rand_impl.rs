@@ -0,0 +1,76 @@
+use crate::{Discrete, Ranger, Span};
+use rand::Rng;
+
+impl<T: Discrete> Ranger<T> {
+    /// Returns a stored value drawn uniformly at random, or `None` if the set
+    /// is empty. Takes time linear in the number of runs.
+    ///
+    /// A set holding more than `u128::MAX` values, which only a full `u128`
+    /// or `i128` set can, is sampled over its first `u128::MAX` values.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut i = rng.gen_range(0..self.len());
+        for u in self.0.iter() {
+            let len = u.len();
+            if i < len {
+                return u.l.forward(i);
+            }
+            i -= len;
+        }
+        unreachable!("the index is below the number of values")
+    }
+    /// Returns a run picked uniformly at random, regardless of its width, or
+    /// `None` if the set is empty. This is cheaper than [`sample`](Self::sample)
+    /// but favors the values of narrow runs.
+    pub fn sample_range<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Span<T>> {
+        if self.is_empty() {
+            return None;
+        }
+        let i = rng.gen_range(0..self.0.len());
+        self.0.iter().nth(i).map(|u| u.span())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn samples_cover_members_only() {
+        let mut rng = thread_rng();
+        let ranger: Ranger<u8> = "3,10-19,200-254".parse().unwrap();
+        let mut seen = [0u32; 256];
+        for _ in 0..66_000 {
+            let v = ranger.sample(&mut rng).unwrap();
+            seen[v as usize] += 1;
+        }
+        for (v, count) in seen.iter().enumerate() {
+            if ranger.contains(&(v as u8)) {
+                // 66 values expected 1000 times each
+                assert!((700..1300).contains(count), "{v} seen {count} times");
+            } else {
+                assert_eq!(*count, 0, "{v}");
+            }
+        }
+        let full = Ranger::from(i128::MIN..=i128::MAX);
+        assert!(full.sample(&mut rng).is_some());
+        assert_eq!(Ranger::<u8>::new().sample(&mut rng), None);
+    }
+
+    #[test]
+    fn ranges_sampled_evenly() {
+        let mut rng = thread_rng();
+        let ranger: Ranger<u32> = "0,10-1000000,5000000".parse().unwrap();
+        let mut seen = [0u32; 3];
+        for _ in 0..3000 {
+            let span = ranger.sample_range(&mut rng).unwrap();
+            let i = ranger.ranges().position(|s| s == span).unwrap();
+            seen[i] += 1;
+        }
+        assert!(seen.iter().all(|&c| (700..1300).contains(&c)), "{seen:?}");
+        assert_eq!(Ranger::<u8>::new().sample_range(&mut rng), None);
+    }
+}
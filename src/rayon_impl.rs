@@ -0,0 +1,75 @@
+use crate::{discrete::step_up, Discrete, Ranger, Span, Unit};
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+use rayon::prelude::*;
+
+impl<T: Discrete + Send + Sync> Ranger<T> {
+    /// Returns a parallel iterator over the runs
+    pub fn par_ranges(&self) -> impl ParallelIterator<Item = Span<T>> + '_ {
+        self.0.par_iter().map(Unit::span)
+    }
+    /// Returns a parallel iterator over every stored value, in no particular
+    /// order. Runs are cut into pieces of roughly equal size first, so a
+    /// single wide run is still shared out between threads.
+    pub fn par_values(&self) -> impl ParallelIterator<Item = T> + '_ {
+        let pieces = rayon::current_num_threads() * 4;
+        let target = (self.len() / pieces as u128).max(1);
+        self.pieces(target).into_par_iter().flat_map_iter(|r| {
+            let (l, h) = r.into_inner();
+            core::iter::successors(Some(l), move |v| if v < &h { v.successor() } else { None })
+        })
+    }
+    /// Cuts the runs into pieces of at most `target` values
+    fn pieces(&self, target: u128) -> Vec<RangeInclusive<T>> {
+        let mut pieces = Vec::new();
+        for u in self.0.iter() {
+            let mut l = u.l.clone();
+            loop {
+                match l.forward(target - 1).filter(|h| h < u.high()) {
+                    Some(h) => {
+                        let next = step_up(&h);
+                        pieces.push(l..=h);
+                        l = next;
+                    }
+                    None => {
+                        pieces.push(l..=u.high().clone());
+                        break;
+                    }
+                }
+            }
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_sum() {
+        let ranger: Ranger<u64> = "3-9,100-200000,300000,400000-400010".parse().unwrap();
+        let sequential: u64 = ranger.ranges().flat_map(RangeInclusive::from).sum();
+        assert_eq!(ranger.par_values().sum::<u64>(), sequential);
+        assert_eq!(ranger.par_values().count() as u128, ranger.len());
+        assert_eq!(ranger.par_ranges().count(), ranger.range_count());
+    }
+
+    #[test]
+    fn dominant_run() {
+        let mut ranger = Ranger::new();
+        ranger.insert_range(0..=999_999u32);
+        for v in (2_000_000..2_000_100).step_by(2) {
+            ranger.insert(v);
+        }
+        let pieces = ranger.pieces(ranger.len() / 16);
+        let wide = pieces.iter().filter(|r| *r.start() < 1_000_000).count();
+        assert!(wide >= 16, "{wide} pieces of the dominant run");
+        assert_eq!(pieces.len(), wide + 50);
+        let rejoined = pieces.iter().fold(Ranger::new(), |mut acc, r| {
+            acc.insert_range(r.clone());
+            acc
+        });
+        assert_eq!(rejoined, ranger);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{Config, Discrete, Ranger, Span};
+use alloc::collections::BTreeSet;
+use core::fmt;
+use rkyv::{
+    bytecheck::{CheckBytes, Verify},
+    munge::munge,
+    rancor::{fail, Fallible, Source},
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Place, Portable, Serialize,
+};
+
+/// Zero-copy form of a [`Ranger`]: its runs as a sorted slice of
+/// `[low, high]` pairs, queried by binary search without deserializing.
+///
+/// Checked access verifies that the pairs are ordered and that no two runs
+/// touch, so an archive that passes validation holds a well-formed set.
+#[derive(CheckBytes, Portable)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[rkyv(crate = rkyv)]
+#[repr(transparent)]
+pub struct ArchivedRanger<T: Archive> {
+    runs: ArchivedVec<[T::Archived; 2]>,
+}
+
+impl<T: Archive> ArchivedRanger<T>
+where
+    T::Archived: Clone + Into<T>,
+{
+    fn native(pair: &[T::Archived; 2]) -> (T, T) {
+        (pair[0].clone().into(), pair[1].clone().into())
+    }
+    /// Checks whether `value` is stored, in O(log n)
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: Ord,
+    {
+        let runs = self.runs.as_slice();
+        let i = runs.partition_point(|pair| &Self::native(pair).1 < value);
+        runs.get(i)
+            .is_some_and(|pair| &Self::native(pair).0 <= value)
+    }
+    /// Iterates over the stored runs in ascending order
+    pub fn ranges(&self) -> impl Iterator<Item = Span<T>> + '_ {
+        self.runs.iter().map(|pair| {
+            let (low, high) = Self::native(pair);
+            Span::new_unchecked(low, high)
+        })
+    }
+    /// Returns the number of runs
+    pub fn range_count(&self) -> usize {
+        self.runs.len()
+    }
+}
+
+#[derive(Debug)]
+struct InvalidRuns;
+
+impl fmt::Display for InvalidRuns {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Archived ranges are out of order, reversed or touching")
+    }
+}
+
+impl core::error::Error for InvalidRuns {}
+
+unsafe impl<T, C> Verify<C> for ArchivedRanger<T>
+where
+    T: Archive + Discrete,
+    T::Archived: Clone + Into<T>,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        let mut previous: Option<T> = None;
+        for pair in self.runs.iter() {
+            let (low, high) = Self::native(pair);
+            if low > high || previous.is_some_and(|p| p >= low || T::adjacent(&p, &low)) {
+                fail!(InvalidRuns);
+            }
+            previous = Some(high);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Archive> Archive for Ranger<T> {
+    type Archived = ArchivedRanger<T>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedRanger { runs } = out);
+        ArchivedVec::resolve_from_len(self.0.len(), resolver, runs);
+    }
+}
+
+impl<T, S> Serialize<S> for Ranger<T>
+where
+    T: Serialize<S> + Clone,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::<[T::Archived; 2]>::serialize_from_iter::<[T; 2], _, _>(
+            self.0.iter().map(|u| [u.l.clone(), u.high().clone()]),
+            serializer,
+        )
+    }
+}
+
+impl<T, D> Deserialize<Ranger<T>, D> for ArchivedRanger<T>
+where
+    T: Archive + Discrete,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Ranger<T>, D::Error> {
+        let mut ranger = Ranger(BTreeSet::new(), Config::default());
+        for [low, high] in self.runs.iter() {
+            let low = low.deserialize(deserializer)?;
+            let high = high.deserialize(deserializer)?;
+            ranger.insert_range(low..=high);
+        }
+        Ok(ranger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::ops::RangeInclusive;
+    use rkyv::{rancor::Error, util::AlignedVec};
+
+    #[test]
+    fn round_trip() {
+        let ranger: Ranger<u32> = "0-2,4,6-8,1000-4000000000".parse().unwrap();
+        let bytes = rkyv::to_bytes::<Error>(&ranger).unwrap();
+        let back: Ranger<u32> = rkyv::from_bytes::<Ranger<u32>, Error>(&bytes).unwrap();
+        assert_eq!(back, ranger);
+
+        let ranger: Ranger<i16> = "-32768--32700,-1-2,32767".parse().unwrap();
+        let bytes = rkyv::to_bytes::<Error>(&ranger).unwrap();
+        assert_eq!(
+            rkyv::from_bytes::<Ranger<i16>, Error>(&bytes).unwrap(),
+            ranger
+        );
+        let empty = Ranger::<u64>::new();
+        let bytes = rkyv::to_bytes::<Error>(&empty).unwrap();
+        assert_eq!(
+            rkyv::from_bytes::<Ranger<u64>, Error>(&bytes).unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn archived_queries() {
+        let ranger: Ranger<u64> = "0-2,4,6-8,1000-4000000000".parse().unwrap();
+        let bytes = rkyv::to_bytes::<Error>(&ranger).unwrap();
+        let archived = rkyv::access::<ArchivedRanger<u64>, Error>(&bytes).unwrap();
+        assert_eq!(archived.range_count(), 4);
+        for v in 0..1100 {
+            assert_eq!(archived.contains(&v), ranger.contains(&v), "{}", v);
+        }
+        assert!(archived.contains(&4_000_000_000));
+        assert!(!archived.contains(&4_000_000_001));
+        let ranges: Vec<_> = archived.ranges().map(RangeInclusive::from).collect();
+        assert_eq!(ranges, [0..=2, 4..=4, 6..=8, 1000..=4_000_000_000]);
+    }
+
+    #[test]
+    fn validation() {
+        // [[0, 2], [3, 5]] touch, so the archive can't come from a Ranger
+        let runs: Vec<[u32; 2]> = Vec::from([[0, 2], [3, 5]]);
+        let bytes: AlignedVec = rkyv::to_bytes::<Error>(&runs).unwrap();
+        assert!(rkyv::access::<ArchivedRanger<u32>, Error>(&bytes).is_err());
+        let runs: Vec<[u32; 2]> = Vec::from([[4, 5], [0, 2]]);
+        let bytes = rkyv::to_bytes::<Error>(&runs).unwrap();
+        assert!(rkyv::access::<ArchivedRanger<u32>, Error>(&bytes).is_err());
+        let runs: Vec<[u32; 2]> = Vec::from([[0, 2], [7, 5]]);
+        let bytes = rkyv::to_bytes::<Error>(&runs).unwrap();
+        assert!(rkyv::access::<ArchivedRanger<u32>, Error>(&bytes).is_err());
+        let runs: Vec<[u32; 2]> = Vec::from([[0, 2], [4, 5]]);
+        let bytes = rkyv::to_bytes::<Error>(&runs).unwrap();
+        let archived = rkyv::access::<ArchivedRanger<u32>, Error>(&bytes).unwrap();
+        assert!(archived.contains(&4) && !archived.contains(&3));
+    }
+}
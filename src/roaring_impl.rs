@@ -0,0 +1,133 @@
+use crate::{Config, Discrete, Ranger, Unit};
+use alloc::collections::BTreeSet;
+use core::ops::RangeInclusive;
+use roaring::{RoaringBitmap, RoaringTreemap};
+
+/// Builds a set from runs given in ascending order, joining those that touch
+fn from_runs<T: Discrete>(runs: impl Iterator<Item = RangeInclusive<T>>) -> Ranger<T> {
+    let mut set = BTreeSet::new();
+    let mut pending: Option<(T, T)> = None;
+    for (l, h) in runs.map(RangeInclusive::into_inner) {
+        pending = match pending {
+            Some((pl, ph)) if T::adjacent(&ph, &l) => Some((pl, h)),
+            Some((pl, ph)) => {
+                set.insert(Unit::new(pl, ph));
+                Some((l, h))
+            }
+            None => Some((l, h)),
+        };
+    }
+    if let Some((l, h)) = pending {
+        set.insert(Unit::new(l, h));
+    }
+    Ranger(set, Config::default())
+}
+
+/// Inserts each run whole, so the cost follows the number of runs rather
+/// than of values
+impl From<&Ranger<u32>> for RoaringBitmap {
+    fn from(ranger: &Ranger<u32>) -> Self {
+        let mut bitmap = RoaringBitmap::new();
+        for u in ranger.0.iter() {
+            bitmap.insert_range(u.l..=*u.high());
+        }
+        bitmap
+    }
+}
+
+/// Reads the bitmap a run at a time rather than a value at a time
+impl From<&RoaringBitmap> for Ranger<u32> {
+    fn from(bitmap: &RoaringBitmap) -> Self {
+        let mut iter = bitmap.iter();
+        from_runs(core::iter::from_fn(|| iter.next_range()))
+    }
+}
+
+/// Inserts each run whole, so the cost follows the number of runs rather
+/// than of values
+impl From<&Ranger<u64>> for RoaringTreemap {
+    fn from(ranger: &Ranger<u64>) -> Self {
+        let mut treemap = RoaringTreemap::new();
+        for u in ranger.0.iter() {
+            treemap.insert_range(u.l..=*u.high());
+        }
+        treemap
+    }
+}
+
+/// Reads each bitmap of the treemap a run at a time, joining runs that
+/// continue from one bitmap into the next
+impl From<&RoaringTreemap> for Ranger<u64> {
+    fn from(treemap: &RoaringTreemap) -> Self {
+        let runs = treemap.bitmaps().flat_map(|(high, bitmap)| {
+            let base = u64::from(high) << 32;
+            let mut iter = bitmap.iter();
+            core::iter::from_fn(move || iter.next_range())
+                .map(move |r| base + u64::from(*r.start())..=base + u64::from(*r.end()))
+        });
+        from_runs(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn bitmap_round_trips() {
+        // dense runs, isolated values, and runs across 65536-value containers
+        let ranger: Ranger<u32> = "0-99999,100001,131070-200000,4294967295".parse().unwrap();
+        let bitmap = RoaringBitmap::from(&ranger);
+        assert_eq!(u128::from(bitmap.len()), ranger.len());
+        assert!(bitmap.contains(65536) && !bitmap.contains(100000));
+        let back = Ranger::from(&bitmap);
+        assert_eq!(back.to_string(), ranger.to_string());
+        assert_eq!(back.range_count(), 4);
+        let full: Ranger<u32> = "0-4294967295".parse().unwrap();
+        let bitmap = RoaringBitmap::from(&full);
+        assert_eq!(bitmap.len(), 1 << 32);
+        assert_eq!(Ranger::from(&bitmap), full);
+        assert!(Ranger::from(&RoaringBitmap::new()).is_empty());
+    }
+
+    #[test]
+    fn treemap_round_trips() {
+        // runs crossing the 32-bit boundary between bitmaps
+        let ranger: Ranger<u64> = "5,4294967290-4294967300,8589934591-8589934592"
+            .parse()
+            .unwrap();
+        let treemap = RoaringTreemap::from(&ranger);
+        assert_eq!(u128::from(treemap.len()), ranger.len());
+        let back = Ranger::from(&treemap);
+        assert_eq!(back, ranger);
+        assert_eq!(back.range_count(), 3);
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let mut ranger = Ranger::<u32>::new();
+            for _ in 0..rng.gen_range(0..20) {
+                let l = rng.gen_range(0..1 << 20);
+                if rng.gen_bool(0.5) {
+                    ranger.insert(l);
+                } else {
+                    ranger.insert_range(l..=l + rng.gen_range(0..200_000));
+                }
+            }
+            let bitmap = RoaringBitmap::from(&ranger);
+            assert_eq!(u128::from(bitmap.len()), ranger.len());
+            assert_eq!(Ranger::from(&bitmap), ranger);
+            let mut wide = Ranger::<u64>::new();
+            for s in ranger.ranges() {
+                wide.insert_range(u64::from(*s.low()) << 12..=u64::from(*s.high()) << 12);
+            }
+            let treemap = RoaringTreemap::from(&wide);
+            assert_eq!(u128::from(treemap.len()), wide.len());
+            assert_eq!(Ranger::from(&treemap), wide);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{Discrete, OverflowError, Ranger};
+use num_traits::{CheckedAdd, CheckedMul, Num};
+
+/// Which coarse units [`Ranger::unscale_by`] keeps when a run covers only
+/// part of one
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub enum Rounding {
+    /// Keeps only the units every value of which is stored
+    Inner,
+    /// Keeps every unit any value of which is stored
+    Outer,
+}
+
+impl<T: Discrete + Num> Ranger<T> {
+    /// Converts each run from units of `factor` values to single values, so
+    /// that `a-b` becomes `a*factor-((b+1)*factor-1)`, as when turning block
+    /// numbers into byte offsets. Runs of adjacent units stay adjacent, and
+    /// so are merged. Fails without building anything if a value would
+    /// overflow.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let blocks: Ranger<u64> = "0,2-3".parse().unwrap();
+    /// assert_eq!(blocks.scale_by(512).unwrap().to_string(), "0-511,1024-2047");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not positive.
+    pub fn scale_by(&self, factor: T) -> Result<Ranger<T>, OverflowError>
+    where
+        T: CheckedAdd + CheckedMul,
+    {
+        assert!(factor > T::zero(), "scale factor must be positive");
+        let last = factor.clone() - T::one();
+        let mut scaled = Ranger::new();
+        for u in self.0.iter() {
+            let low = u.l.checked_mul(&factor).ok_or(OverflowError)?;
+            // (high + 1) * factor - 1 without overflowing on the way
+            let high = u
+                .high()
+                .checked_mul(&factor)
+                .and_then(|h| h.checked_add(&last))
+                .ok_or(OverflowError)?;
+            scaled.insert_range(low..=high);
+        }
+        Ok(scaled)
+    }
+    /// Converts each run from single values to units of `factor` values,
+    /// the inverse of [`scale_by`](Ranger::scale_by). A unit covered only in
+    /// part is kept or dropped as `rounding` says, as when turning byte
+    /// offsets into the blocks they fully cover or touch at all.
+    ///
+    /// ```
+    /// use ranger::{Ranger, Rounding};
+    ///
+    /// let bytes: Ranger<u64> = "100-1535".parse().unwrap();
+    /// assert_eq!(bytes.unscale_by(512, Rounding::Inner).to_string(), "1-2");
+    /// assert_eq!(bytes.unscale_by(512, Rounding::Outer).to_string(), "0-2");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not positive.
+    pub fn unscale_by(&self, factor: T, rounding: Rounding) -> Ranger<T> {
+        assert!(factor > T::zero(), "scale factor must be positive");
+        let last = factor.clone() - T::one();
+        let mut unscaled = Ranger::new();
+        for u in self.0.iter() {
+            let (low, low_offset) = div_floor(&u.l, &factor);
+            let (high, high_offset) = div_floor(u.high(), &factor);
+            let range = match rounding {
+                Rounding::Outer => low..=high,
+                Rounding::Inner => {
+                    let low = if low_offset.is_zero() {
+                        low
+                    } else {
+                        low + T::one()
+                    };
+                    let high = if high_offset == last {
+                        high
+                    } else {
+                        high - T::one()
+                    };
+                    low..=high
+                }
+            };
+            // a run within a single unit covers none of it entirely
+            if !range.is_empty() {
+                unscaled.insert_range(range);
+            }
+        }
+        unscaled
+    }
+}
+
+/// Divides `value` by the positive `factor` rounding down, returning the
+/// quotient and the offset of `value` within its unit
+fn div_floor<T: Num + Ord + Clone>(value: &T, factor: &T) -> (T, T) {
+    let quotient = value.clone() / factor.clone();
+    let remainder = value.clone() % factor.clone();
+    if remainder < T::zero() {
+        (quotient - T::one(), remainder + factor.clone())
+    } else {
+        (quotient, remainder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{thread_rng, Rng};
+
+    fn set<T: Discrete + core::str::FromStr>(s: &str) -> Ranger<T> {
+        s.parse().ok().unwrap()
+    }
+
+    #[test]
+    fn factor_one_is_identity() {
+        let ranger: Ranger<i16> = set("-32768--5,0,7-9,32767");
+        assert_eq!(ranger.scale_by(1), Ok(ranger.clone()));
+        for rounding in [Rounding::Inner, Rounding::Outer] {
+            assert_eq!(ranger.unscale_by(1, rounding), ranger);
+        }
+    }
+
+    #[test]
+    fn scaling() {
+        // adjacent blocks scale to touching runs, which merge
+        let blocks: Ranger<u32> = set("0,1-2,5");
+        let bytes = blocks.scale_by(4).unwrap();
+        assert_eq!(bytes.to_string(), "0-11,20-23");
+        assert_eq!(bytes.range_count(), 2);
+        let signed: Ranger<i8> = set("-3--2,0");
+        assert_eq!(signed.scale_by(10).unwrap().to_string(), "-30--11,0-9");
+        // the last block ends exactly at the top of the type
+        let top: Ranger<u8> = set("63");
+        assert_eq!(top.scale_by(4).unwrap().to_string(), "252-255");
+        let bottom: Ranger<i8> = set("-32");
+        assert_eq!(bottom.scale_by(4).unwrap().to_string(), "-128--125");
+        assert!(Ranger::<u8>::new().scale_by(100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn scaling_overflow() {
+        let ranger: Ranger<u8> = set("1,64");
+        assert_eq!(ranger.scale_by(4), Err(OverflowError));
+        let ranger: Ranger<i8> = set("-33,0");
+        assert_eq!(ranger.scale_by(4), Err(OverflowError));
+        let ranger: Ranger<u8> = set("0");
+        assert_eq!(ranger.scale_by(255).unwrap().to_string(), "0-254");
+        let ranger: Ranger<u8> = set("1");
+        assert_eq!(ranger.scale_by(255), Err(OverflowError));
+    }
+
+    #[test]
+    fn unscaling_rounds_unaligned_runs() {
+        let bytes: Ranger<u32> = set("0-3,6-9,13,16-27,30-31");
+        assert_eq!(bytes.unscale_by(4, Rounding::Inner).to_string(), "0,4-6");
+        assert_eq!(bytes.unscale_by(4, Rounding::Outer).to_string(), "0-7");
+        let signed: Ranger<i8> = set("-128--121,-7-6,125-127");
+        assert_eq!(
+            signed.unscale_by(4, Rounding::Inner).to_string(),
+            "-32--31,-1-0"
+        );
+        assert_eq!(
+            signed.unscale_by(4, Rounding::Outer).to_string(),
+            "-32--31,-2-1,31"
+        );
+        let all: Ranger<u8> = set("0-255");
+        assert_eq!(all.unscale_by(100, Rounding::Inner).to_string(), "0-1");
+        assert_eq!(all.unscale_by(100, Rounding::Outer).to_string(), "0-2");
+    }
+
+    #[test]
+    fn unscaling_inverts_scaling() {
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let factor = rng.gen_range(1..50);
+            let mut blocks = Ranger::<i32>::new();
+            for _ in 0..rng.gen_range(0..10) {
+                let l = rng.gen_range(-1000..1000);
+                blocks.insert_range(l..=l + rng.gen_range(0..10));
+            }
+            let bytes = blocks.scale_by(factor).unwrap();
+            assert_eq!(bytes.len(), blocks.len() * factor as u128);
+            for rounding in [Rounding::Inner, Rounding::Outer] {
+                assert_eq!(bytes.unscale_by(factor, rounding), blocks);
+            }
+            // losing one byte loses its block to Inner, but not to Outer
+            // unless the block was that byte
+            let Some(byte) = bytes
+                .first_range()
+                .map(|r| *r.start() + rng.gen_range(0..factor))
+            else {
+                continue;
+            };
+            let mut trimmed = bytes.clone();
+            trimmed.remove(&byte);
+            let mut expected = blocks.clone();
+            expected.remove(&div_floor(&byte, &factor).0);
+            assert_eq!(trimmed.unscale_by(factor, Rounding::Inner), expected);
+            let outer = trimmed.unscale_by(factor, Rounding::Outer);
+            assert_eq!(outer, if factor == 1 { expected } else { blocks });
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_factor() {
+        let ranger: Ranger<u8> = set("1");
+        let _ = ranger.scale_by(0);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{IndexOutOfBounds, Ranger};
+
+impl Ranger<usize> {
+    /// Yields the elements of `items` at the stored indices, in index order,
+    /// skipping indices past the end of the slice. Each run is taken as one
+    /// subslice, so the cost follows the number of runs and elements yielded
+    /// rather than the width of the indices.
+    ///
+    /// The elements borrow from `items` alone, so they outlive the iterator
+    /// and the set:
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let items = ["a", "b", "c", "d", "e", "f", "g", "h"];
+    /// let picked: Vec<&str> = {
+    ///     let choice: Ranger<usize> = "1-3,6,9-12".parse().unwrap();
+    ///     choice.select_from(&items).copied().collect()
+    /// };
+    /// assert_eq!(picked, ["b", "c", "d", "g"]);
+    /// ```
+    pub fn select_from<'s, 'a: 's, U>(
+        &'s self,
+        items: &'a [U],
+    ) -> impl Iterator<Item = &'a U> + 's {
+        self.0
+            .iter()
+            .take_while(move |u| u.l < items.len())
+            .flat_map(move |u| &items[u.l..=(*u.high()).min(items.len() - 1)])
+    }
+    /// Yields the elements of `items` at the stored indices as
+    /// [`select_from`](Self::select_from) does, but fails with the lowest
+    /// index past the end of the slice, if any, before yielding anything
+    pub fn try_select_from<'s, 'a: 's, U>(
+        &'s self,
+        items: &'a [U],
+    ) -> Result<impl Iterator<Item = &'a U> + 's, IndexOutOfBounds> {
+        // the first run ending at or past the end holds the lowest such index
+        if let Some(u) = self.0.range::<usize, _>(items.len()..).next() {
+            return Err(IndexOutOfBounds(u.l.max(items.len())));
+        }
+        Ok(self.select_from(items))
+    }
+    /// Returns the stored indices that fall within a slice of `len` elements
+    pub fn selected_indices(&self, len: usize) -> Ranger<usize> {
+        match len.checked_sub(1) {
+            Some(last) => {
+                let mut ranger = self.clone();
+                ranger.remove_above(&last);
+                ranger
+            }
+            None => Ranger::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+
+    fn letters() -> Vec<char> {
+        ('a'..='j').collect()
+    }
+
+    #[test]
+    fn past_the_end() {
+        let items = letters();
+        let choice: Ranger<usize> = "1-3,7,8-14,20".parse().unwrap();
+        let picked: Vec<_> = choice.select_from(&items).collect();
+        assert_eq!(picked, [&'b', &'c', &'d', &'h', &'i', &'j']);
+        assert_eq!(
+            choice.try_select_from(&items).err(),
+            Some(IndexOutOfBounds(10))
+        );
+        let choice: Ranger<usize> = "0,12-14".parse().unwrap();
+        assert_eq!(
+            choice.try_select_from(&items).err(),
+            Some(IndexOutOfBounds(12))
+        );
+        assert_eq!(choice.selected_indices(items.len()).to_string(), "0");
+        let choice: Ranger<usize> = "2-6,9-14".parse().unwrap();
+        assert_eq!(choice.selected_indices(items.len()).to_string(), "2-6,9");
+        assert_eq!(choice.selected_indices(4).to_string(), "2-3");
+        assert!(choice.selected_indices(0).is_empty());
+        assert_eq!(choice.select_from(&[] as &[char]).count(), 0);
+    }
+
+    #[test]
+    fn empty_and_whole() {
+        let items = letters();
+        let none = Ranger::<usize>::new();
+        assert_eq!(none.select_from(&items).count(), 0);
+        assert_eq!(none.try_select_from(&items).unwrap().count(), 0);
+        assert!(none.selected_indices(10).is_empty());
+        let all = Ranger::from(0..=items.len() - 1);
+        let picked: Vec<char> = all.try_select_from(&items).unwrap().copied().collect();
+        assert_eq!(picked, items);
+        assert_eq!(all.selected_indices(items.len()), all);
+        let wider = Ranger::from(0..=usize::MAX);
+        assert_eq!(wider.select_from(&items).count(), items.len());
+        assert_eq!(
+            wider.try_select_from(&items).err(),
+            Some(IndexOutOfBounds(10))
+        );
+    }
+}
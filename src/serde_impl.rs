@@ -0,0 +1,155 @@
+use crate::{Config, Discrete, Ranger};
+use alloc::collections::BTreeSet;
+use core::{
+    fmt::{self, Display},
+    marker::PhantomData,
+    str::FromStr,
+};
+use serde::{
+    de::{self, SeqAccess, Unexpected, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Writes the same compact string as `Display`, such as `"0-2,4,6-8"`, for
+/// human-readable formats and a sequence of `[low, high]` pairs otherwise
+impl<T: Eq + Display + Serialize> Serialize for Ranger<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.collect_seq(self.0.iter().map(|u| (&u.l, u.high())))
+        }
+    }
+}
+
+/// Reads either the compact string form or a sequence of `[low, high]` pairs,
+/// normalizing overlapping or unsorted entries. Formats that don't describe
+/// their own shapes, like postcard, are read as pairs.
+impl<'de, T> Deserialize<'de> for Ranger<T>
+where
+    T: FromStr + Discrete + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(RangerVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_seq(RangerVisitor(PhantomData))
+        }
+    }
+}
+
+struct RangerVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for RangerVisitor<T>
+where
+    T: FromStr + Discrete + Deserialize<'de>,
+{
+    type Value = Ranger<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of ranges such as \"0-2,4,6-8\" or [[0,2],[4,4],[6,8]]")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut ranger = Ranger(BTreeSet::new(), Config::default());
+        while let Some((low, high)) = seq.next_element::<(T, T)>()? {
+            if low > high {
+                return Err(de::Error::invalid_value(
+                    Unexpected::Other("range with its low end above its high end"),
+                    &self,
+                ));
+            }
+            ranger.insert_range(low..=high);
+        }
+        Ok(ranger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn string_round_trip() {
+        let ranger: Ranger<u16> = "0-2,4,6-8,443,8000-8080".parse().unwrap();
+        let json = serde_json::to_string(&ranger).unwrap();
+        assert_eq!(json, "\"0-2,4,6-8,443,8000-8080\"");
+        assert_eq!(serde_json::from_str::<Ranger<u16>>(&json).unwrap(), ranger);
+
+        let ranger: Ranger<i64> = "-128--126,-1-2,4,9223372036854775807".parse().unwrap();
+        let json = serde_json::to_string(&ranger).unwrap();
+        assert_eq!(serde_json::from_str::<Ranger<i64>>(&json).unwrap(), ranger);
+
+        let empty = Ranger::<i64>::new();
+        assert_eq!(serde_json::to_string(&empty).unwrap(), "\"\"");
+        assert_eq!(serde_json::from_str::<Ranger<i64>>("\"\"").unwrap(), empty);
+
+        let normalized: Ranger<u8> = serde_json::from_str("\"7,1-3,2-5,6\"").unwrap();
+        assert_eq!(normalized.to_string(), "1-7");
+    }
+
+    #[test]
+    fn string_errors() {
+        let err = serde_json::from_str::<Ranger<u8>>("\"1-2,9-4\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Range low end above its high end at position 4"));
+        let err = serde_json::from_str::<Ranger<u8>>("\"300\"").unwrap_err();
+        assert!(err.to_string().contains("Invalid range at position 0"));
+        assert!(serde_json::from_str::<Ranger<u8>>("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn pairs() {
+        let ranger: Ranger<i32> = serde_json::from_str("[[6,8],[0,2],[4,4],[1,3]]").unwrap();
+        assert_eq!(ranger.to_string(), "0-4,6-8");
+        let ranger: Ranger<i32> = serde_json::from_str("[]").unwrap();
+        assert!(ranger.is_empty());
+        let err = serde_json::from_str::<Ranger<i32>>("[[0,2],[8,6]]").unwrap_err();
+        assert!(err.to_string().contains("low end above its high end"));
+        assert!(serde_json::from_str::<Ranger<u8>>("[[0,256]]").is_err());
+        assert!(serde_json::from_str::<Ranger<u8>>("[[0,1,2]]").is_err());
+
+        let ranger: Ranger<i64> = "-128--126,-1-2,4,6-8".parse().unwrap();
+        let bytes = postcard::to_allocvec(&ranger).unwrap();
+        // one length byte, then zigzag varint pairs
+        assert_eq!(bytes.first(), Some(&4));
+        assert_eq!(postcard::from_bytes::<Ranger<i64>>(&bytes).unwrap(), ranger);
+        let empty = Ranger::<u16>::new();
+        let bytes = postcard::to_allocvec(&empty).unwrap();
+        assert_eq!(bytes, [0]);
+        assert_eq!(postcard::from_bytes::<Ranger<u16>>(&bytes).unwrap(), empty);
+        let reversed = postcard::to_allocvec(&[(5u16, 1u16)]).unwrap();
+        assert!(postcard::from_bytes::<Ranger<u16>>(&reversed).is_err());
+    }
+
+    #[test]
+    fn random_overlapping_pairs() {
+        use rand::{thread_rng, Rng};
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let pairs: alloc::vec::Vec<(u8, u8)> = (0..rng.gen_range(0..20))
+                .map(|_| {
+                    let l = rng.gen::<u8>();
+                    (l, l.saturating_add(rng.gen_range(0..40)))
+                })
+                .collect();
+            let mut expected = Ranger::new();
+            for &(l, h) in &pairs {
+                expected.insert_range(l..=h);
+            }
+            let bytes = postcard::to_allocvec(&pairs).unwrap();
+            let ranger = postcard::from_bytes::<Ranger<u8>>(&bytes).unwrap();
+            assert_eq!(ranger.to_string(), expected.to_string());
+            for v in 0..=255u8 {
+                let stored = pairs.iter().any(|&(l, h)| (l..=h).contains(&v));
+                assert_eq!(ranger.contains(&v), stored);
+            }
+        }
+    }
+}
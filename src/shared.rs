@@ -0,0 +1,121 @@
+use crate::Ranger;
+use alloc::sync::Arc;
+use core::{
+    fmt::{self, Display},
+    ops::Deref,
+};
+
+/// A set that can hand out read-only snapshots of itself in constant time.
+///
+/// Snapshots share the runs with the live set, which copies them only on
+/// the first mutation after a snapshot was taken, through
+/// [`make_mut`](SharedRanger::make_mut). Reads go through `Deref`.
+#[derive(Clone, Debug, Default)]
+pub struct SharedRanger<T>(Arc<Ranger<T>>);
+
+/// A read-only view of a [`SharedRanger`] as it was when the snapshot was
+/// taken, answering every query of a [`Ranger`] through `Deref`
+#[derive(Clone, Debug)]
+pub struct SharedSnapshot<T>(Arc<Ranger<T>>);
+
+impl<T> SharedRanger<T> {
+    pub fn new(ranger: Ranger<T>) -> Self {
+        SharedRanger(Arc::new(ranger))
+    }
+    /// Returns a snapshot of the current contents without copying them
+    pub fn snapshot(&self) -> SharedSnapshot<T> {
+        SharedSnapshot(Arc::clone(&self.0))
+    }
+}
+
+impl<T: Clone> SharedRanger<T> {
+    /// Returns the set for mutation, first copying it if any snapshot still
+    /// shares it
+    pub fn make_mut(&mut self) -> &mut Ranger<T> {
+        Arc::make_mut(&mut self.0)
+    }
+    /// Unwraps the set, copying it if any snapshot still shares it
+    pub fn into_inner(self) -> Ranger<T> {
+        Arc::unwrap_or_clone(self.0)
+    }
+}
+
+impl<T> SharedSnapshot<T> {
+    /// Whether two snapshots share the same runs
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+}
+
+impl<T> From<Ranger<T>> for SharedRanger<T> {
+    fn from(ranger: Ranger<T>) -> Self {
+        Self::new(ranger)
+    }
+}
+
+impl<T> Deref for SharedRanger<T> {
+    type Target = Ranger<T>;
+
+    fn deref(&self) -> &Ranger<T> {
+        &self.0
+    }
+}
+
+impl<T> Deref for SharedSnapshot<T> {
+    type Target = Ranger<T>;
+
+    fn deref(&self) -> &Ranger<T> {
+        &self.0
+    }
+}
+
+impl<T: Eq + Display> Display for SharedRanger<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Eq + Display> Display for SharedSnapshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+    use core::ops::RangeInclusive;
+
+    #[test]
+    fn snapshots() {
+        let mut live = SharedRanger::new("0-9,20-29".parse::<Ranger<u32>>().unwrap());
+        let before = live.snapshot();
+        let again: Vec<_> = (0..1000).map(|_| live.snapshot()).collect();
+        assert!(again.iter().all(|s| SharedSnapshot::ptr_eq(s, &before)));
+
+        for v in (0..10_000).step_by(3) {
+            live.make_mut().insert(v);
+        }
+        live.make_mut().remove_range(5..=25);
+        assert!(!live.contains(&6) && live.contains(&3000));
+        assert_eq!(before.to_string(), "0-9,20-29");
+        assert_eq!(before.len(), 20);
+        assert!(before.contains(&7) && !before.contains(&3000));
+        assert!(before
+            .ranges()
+            .map(RangeInclusive::from)
+            .eq([0..=9, 20..=29]));
+
+        // holding no snapshot, mutation happens in place
+        drop((before, again));
+        let after = live.snapshot();
+        assert!(live.make_mut().insert(7));
+        assert!(!after.contains(&7));
+        drop(after);
+        let ptr = Arc::as_ptr(&live.0);
+        live.make_mut().insert(8);
+        assert_eq!(Arc::as_ptr(&live.0), ptr);
+        assert_eq!(live.to_string(), live.clone().into_inner().to_string());
+    }
+}
@@ -0,0 +1,378 @@
+use crate::{
+    discrete::{added_len, run_len, step_down, step_up},
+    inclusive_bounds, write_runs, Discrete, FormatOptions, ParseRangeError, Ranger, Span, Unit,
+};
+use allocator_api2::{
+    alloc::{Allocator, Global},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    ops::{RangeBounds, RangeInclusive},
+    str::FromStr,
+};
+
+/// A set of values stored as a sorted vector of runs, for sets that rarely
+/// hold more than a few dozen of them.
+///
+/// It stores exactly what a [`Ranger`] does and writes and parses the same
+/// text, but lookups are a binary search over contiguous memory, while
+/// insertions and removals shift every run above the one they change. It
+/// should be the faster of the two while runs are few, and fall behind once
+/// shifting them costs more than walking the tree, a point that comes sooner
+/// for larger element types and that `cargo bench --bench small` measures.
+/// Appending above the last run stays cheap at any size. Construction
+/// options such as gap tolerance are not supported.
+///
+/// The runs live in memory from the allocator `A`, such as an arena, which
+/// [`new_in`](SmallRanger::new_in) takes. No operation allocates from
+/// anywhere else, while `Display` and parsing go through the global
+/// allocator's default.
+#[derive(Clone)]
+pub struct SmallRanger<T, A: Allocator = Global>(Vec<(T, T), A>);
+
+impl<T> Default for SmallRanger<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SmallRanger<T> {
+    pub fn new() -> Self {
+        SmallRanger(Vec::new())
+    }
+}
+
+impl<T, A: Allocator> SmallRanger<T, A> {
+    /// Creates an empty set whose runs are allocated by `alloc`
+    pub fn new_in(alloc: A) -> Self {
+        SmallRanger(Vec::new_in(alloc))
+    }
+    /// Returns the number of runs
+    pub fn range_count(&self) -> usize {
+        self.0.len()
+    }
+    /// Returns the heap bytes reserved for runs, spare capacity included,
+    /// which unlike [`Ranger::memory_usage`] is exact, elements' own heap
+    /// data aside
+    pub fn memory_usage(&self) -> usize {
+        self.0.capacity() * size_of::<(T, T)>()
+    }
+    /// Returns whether no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<T: Discrete, A: Allocator> SmallRanger<T, A> {
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.0.partition_point(|(_, h)| h < value);
+        self.0.get(i).is_some_and(|(l, _)| l <= value)
+    }
+    /// Returns whether every value in `range` is stored. An empty range is
+    /// trivially contained.
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        let i = self.0.partition_point(|(_, h)| h < range.start());
+        self.0
+            .get(i)
+            .is_some_and(|(l, h)| l <= range.start() && h >= range.end())
+    }
+    /// Inserts `value`, returning whether it was not already present
+    pub fn insert(&mut self, value: T) -> bool {
+        self.insert_range(value.clone()..=value) > 0
+    }
+    /// Inserts every value in `range`, merging with any runs it overlaps or
+    /// touches. Returns the number of values that were not already present.
+    pub fn insert_range(&mut self, range: RangeInclusive<T>) -> u128 {
+        let (l, h) = range.into_inner();
+        if l > h {
+            return 0;
+        }
+        let start = self
+            .0
+            .partition_point(|(_, rh)| rh < &l && !T::adjacent(rh, &l));
+        if let Some((rl, rh)) = self.0.get(start) {
+            if rl <= &l && rh >= &h {
+                return 0;
+            }
+        }
+        let end = self
+            .0
+            .partition_point(|(rl, _)| rl <= &h || T::adjacent(&h, rl));
+        let absorbed = self.0[start..end]
+            .iter()
+            .fold(0u128, |acc, (rl, rh)| acc.saturating_add(run_len(rl, rh)));
+        let (l, h) = match (self.0.get(start), end.checked_sub(1).map(|i| &self.0[i])) {
+            (Some(first), Some(last)) if start < end => (
+                core::cmp::min(l, first.0.clone()),
+                core::cmp::max(h, last.1.clone()),
+            ),
+            _ => (l, h),
+        };
+        let added = added_len(&l, &h, absorbed);
+        self.0.splice(start..end, [(l, h)]);
+        added
+    }
+    /// Removes `value`, splitting the run containing it if necessary. Returns
+    /// whether the value was present.
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.remove_range(value.clone()..=value.clone()) > 0
+    }
+    /// Removes every value within `bounds`, which may have exclusive or
+    /// unbounded ends, returning the number of values that were present
+    pub fn remove_range<R: RangeBounds<T>>(&mut self, bounds: R) -> u128 {
+        let Some((low, high)) = inclusive_bounds(&bounds) else {
+            return 0;
+        };
+        let start = low
+            .as_ref()
+            .map_or(0, |low| self.0.partition_point(|(_, h)| h < low));
+        let end = high.as_ref().map_or(self.0.len(), |high| {
+            self.0.partition_point(|(l, _)| l <= high)
+        });
+        if start >= end {
+            return 0;
+        }
+        // only the first and last runs can keep anything
+        let (mut below, mut above) = (None, None);
+        let mut removed = 0u128;
+        for (l, h) in self.0.drain(start..end) {
+            let from = match &low {
+                Some(low) if &l < low => {
+                    below = Some((l, step_down(low)));
+                    low.clone()
+                }
+                _ => l,
+            };
+            let to = match &high {
+                Some(high) if &h > high => {
+                    above = Some((step_up(high), h));
+                    high.clone()
+                }
+                _ => h,
+            };
+            removed = removed.saturating_add(run_len(&from, &to));
+        }
+        self.0.splice(start..start, below.into_iter().chain(above));
+        removed
+    }
+    /// Returns the number of values stored, saturating at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        self.0
+            .iter()
+            .fold(0u128, |acc, (l, h)| acc.saturating_add(run_len(l, h)))
+    }
+    /// Returns the run at `index` counting from the lowest, or `None` past
+    /// the last, in constant time
+    pub fn nth_range(&self, index: usize) -> Option<RangeInclusive<T>> {
+        self.0.get(index).map(|(l, h)| l.clone()..=h.clone())
+    }
+    /// Iterates over the runs in ascending order
+    pub fn ranges(&self) -> impl Iterator<Item = Span<T>> + '_ {
+        self.0
+            .iter()
+            .map(|(l, h)| Span::new_unchecked(l.clone(), h.clone()))
+    }
+}
+
+impl<T: PartialEq, A: Allocator, B: Allocator> PartialEq<SmallRanger<T, B>> for SmallRanger<T, A> {
+    fn eq(&self, other: &SmallRanger<T, B>) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for SmallRanger<T, A> {}
+
+impl<T: Hash, A: Allocator> Hash for SmallRanger<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0[..].hash(state);
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for SmallRanger<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SmallRanger").field(&&self.0[..]).finish()
+    }
+}
+
+impl<T: Eq + Display, A: Allocator> Display for SmallRanger<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_runs(f, self.0.iter().map(|(l, h)| (l, h)), &FormatOptions::new())
+    }
+}
+
+impl<T: FromStr + Discrete> FromStr for SmallRanger<T> {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Ranger<T>>().map(Self::from)
+    }
+}
+
+impl<T: Clone> From<Ranger<T>> for SmallRanger<T> {
+    fn from(ranger: Ranger<T>) -> Self {
+        SmallRanger(
+            ranger
+                .0
+                .into_iter()
+                .map(|u| {
+                    let h = u.high().clone();
+                    (u.l, h)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T: Discrete, A: Allocator> From<SmallRanger<T, A>> for Ranger<T> {
+    fn from(small: SmallRanger<T, A>) -> Self {
+        let mut ranger = Ranger::new();
+        ranger.0 = small.0.into_iter().map(|(l, h)| Unit::new(l, h)).collect();
+        ranger
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{seq::SliceRandom, thread_rng, Rng};
+
+    #[test]
+    fn agrees_with_ranger() {
+        let mut rng = thread_rng();
+        for _ in 0..200 {
+            let mut values: Vec<i8> = (-40..40).collect();
+            values.shuffle(&mut rng);
+            let mut tree = Ranger::new();
+            let mut small = SmallRanger::new();
+            for v in values {
+                let (a, b) = (v, v.saturating_add(rng.gen_range(0..4)));
+                let (grew, shrank) = match rng.gen_range(0..4) {
+                    0 => (tree.insert(v) as u128, small.insert(v) as u128),
+                    1 => (tree.insert_range(a..=b), small.insert_range(a..=b)),
+                    2 => (tree.remove(&v) as u128, small.remove(&v) as u128),
+                    _ => (tree.remove_range(a..b), small.remove_range(a..b)),
+                };
+                assert_eq!(grew, shrank);
+                assert_eq!(small.to_string(), tree.to_string());
+                assert_eq!(small.len(), tree.len());
+                assert_eq!(small.range_count(), tree.range_count());
+                assert_eq!(small.contains(&v), tree.contains(&v));
+                assert_eq!(
+                    small.contains_range(&(a..=b)),
+                    tree.contains_range(&(a..=b))
+                );
+                assert!(small.ranges().eq(tree.ranges()));
+                let last = tree.range_count().saturating_sub(1);
+                assert_eq!(small.nth_range(last), tree.nth_range(last));
+            }
+            assert!(small.memory_usage() >= small.range_count() * 2);
+            small.0.shrink_to_fit();
+            assert_eq!(small.memory_usage(), small.range_count() * 2);
+            assert_eq!(Ranger::from(small.clone()), tree);
+            assert_eq!(SmallRanger::from(tree.clone()), small);
+            assert_eq!(tree.to_string().parse::<SmallRanger<i8>>(), Ok(small));
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        let mut small = SmallRanger::new();
+        assert_eq!(small.insert_range(0..=u8::MAX), 256);
+        assert_eq!(small.insert_range(3..=9), 0);
+        assert_eq!(small.remove_range(..), 256);
+        assert!(small.is_empty());
+        assert_eq!(small.insert_range(RangeInclusive::new(5, 1)), 0);
+        assert_eq!(small.to_string(), "");
+    }
+
+    /// Hands out memory from a fixed buffer and never frees it
+    struct Bump {
+        buf: core::cell::UnsafeCell<[u64; 512]>,
+        used: core::cell::Cell<usize>,
+    }
+
+    unsafe impl Allocator for &Bump {
+        fn allocate(
+            &self,
+            layout: core::alloc::Layout,
+        ) -> Result<core::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+            let base = self.buf.get() as *mut u8;
+            let start = self.used.get().next_multiple_of(layout.align());
+            let end = start + layout.size();
+            if end > 512 * 8 {
+                return Err(allocator_api2::alloc::AllocError);
+            }
+            self.used.set(end);
+            let ptr = unsafe { base.add(start) };
+            let slice = core::ptr::slice_from_raw_parts_mut(ptr, layout.size());
+            Ok(core::ptr::NonNull::new(slice).unwrap())
+        }
+        unsafe fn deallocate(&self, _: core::ptr::NonNull<u8>, _: core::alloc::Layout) {}
+    }
+
+    extern crate std;
+
+    std::thread_local! {
+        static GLOBAL_ALLOCS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    /// Counts the global allocations made on each thread, so that a test can
+    /// tell its own apart from those of the tests running alongside it
+    struct Counting;
+
+    unsafe impl core::alloc::GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+            let _ = GLOBAL_ALLOCS.try_with(|n| n.set(n.get() + 1));
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static COUNTING: Counting = Counting;
+
+    fn global_allocs() -> usize {
+        GLOBAL_ALLOCS.with(|n| n.get())
+    }
+
+    #[test]
+    fn arena() {
+        let bump = Bump {
+            buf: core::cell::UnsafeCell::new([0; 512]),
+            used: core::cell::Cell::new(0),
+        };
+        let mut small = SmallRanger::new_in(&bump);
+        let before = global_allocs();
+        let mut inserted = 0;
+        for v in [5u32, 1, 9, 2, 3, 30, 31, 7, 100, 1] {
+            inserted += small.insert(v) as u32;
+        }
+        let added = small.insert_range(40..=60);
+        let removed = small.remove_range(45..50);
+        let was_present = small.remove(&2);
+        let not_present = small.remove(&2);
+        let contained = small.contains(&50) && small.contains_range(&(40..=44));
+        assert_eq!(global_allocs(), before, "allocated outside the arena");
+        assert_eq!((inserted, added, removed), (9, 21, 5));
+        assert!(was_present && !not_present && contained);
+        assert!(bump.used.get() > 0);
+        let within = bump.buf.get() as usize..bump.buf.get() as usize + 512 * 8;
+        assert!(within.contains(&(small.0.as_ptr() as usize)));
+        let expected: SmallRanger<u32> = "1,3,5,7,9,30-31,40-44,50-60,100".parse().unwrap();
+        assert_eq!(small, expected);
+        assert_eq!(small.to_string(), "1,3,5,7,9,30-31,40-44,50-60,100");
+        // the count does see allocations made through the global allocator
+        let before = global_allocs();
+        let mut global = SmallRanger::new();
+        global.insert(1u32);
+        assert!(global_allocs() > before);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::Discrete;
+use core::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
+
+/// One run of consecutive values, from `low` up to and including `high`.
+///
+/// This is what the sets yield when walking their runs. Unlike a
+/// `RangeInclusive`, a span is never empty: its low end never lies above its
+/// high end. It converts to and from `RangeInclusive` and `(low, high)`
+/// pairs, the conversions into a span failing if the range is empty.
+///
+#[cfg_attr(feature = "alloc", doc = "```")]
+#[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+/// use ranger::{Ranger, Span};
+///
+/// let ranger: Ranger<u32> = "1-3,7,10-19".parse().unwrap();
+/// for span in ranger.ranges() {
+///     match span.len() {
+///         1 => assert_eq!(span.low(), &7),
+///         n => assert!(n == 3 || span.contains(&15)),
+///     }
+/// }
+/// let widest = ranger.ranges().max_by_key(Span::len).unwrap();
+/// assert_eq!(widest.to_string(), "10-19");
+/// assert_eq!(widest.into_inner(), (10, 19));
+///
+/// let span = Span::try_from(4..=8).unwrap();
+/// assert!(span.overlaps(&Span::new(8, 9).unwrap()));
+/// assert_eq!(std::ops::RangeInclusive::from(span), 4..=8);
+/// assert!(Span::try_from(8..=4).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span<T> {
+    low: T,
+    high: T,
+}
+
+impl<T: Ord> Span<T> {
+    /// Creates the span from `low` to `high`, or returns `None` if `low`
+    /// lies above `high`
+    pub fn new(low: T, high: T) -> Option<Self> {
+        (low <= high).then_some(Span { low, high })
+    }
+    /// Whether `value` lies within the span
+    pub fn contains(&self, value: &T) -> bool {
+        &self.low <= value && value <= &self.high
+    }
+    /// Whether the two spans share at least one value
+    pub fn overlaps(&self, other: &Span<T>) -> bool {
+        self.low <= other.high && other.low <= self.high
+    }
+}
+
+impl<T> Span<T> {
+    /// Creates the span without checking that `low` does not lie above
+    /// `high`, for callers whose runs already guarantee it
+    pub(crate) fn new_unchecked(low: T, high: T) -> Self {
+        Span { low, high }
+    }
+    /// Returns the lowest value in the span
+    pub fn low(&self) -> &T {
+        &self.low
+    }
+    /// Returns the highest value in the span
+    pub fn high(&self) -> &T {
+        &self.high
+    }
+    /// Splits the span into its low and high ends
+    pub fn into_inner(self) -> (T, T) {
+        (self.low, self.high)
+    }
+}
+
+impl<T: Discrete> Span<T> {
+    /// Returns the number of values in the span, saturating at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        T::distance(&self.low, &self.high).map_or(u128::MAX, |w| w.saturating_add(1))
+    }
+    /// Always `false`, as a span holds at least one value
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+/// Writes `low-high`, or just `low` for a span of one value, as the sets do
+/// for each of their runs
+impl<T: Eq + Display> Display for Span<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.low == self.high {
+            write!(f, "{}", self.low)
+        } else {
+            write!(f, "{}-{}", self.low, self.high)
+        }
+    }
+}
+
+impl<T> From<Span<T>> for RangeInclusive<T> {
+    fn from(span: Span<T>) -> Self {
+        span.low..=span.high
+    }
+}
+
+impl<T> From<Span<T>> for (T, T) {
+    fn from(span: Span<T>) -> Self {
+        (span.low, span.high)
+    }
+}
+
+/// Fails, handing the range back, if it is empty
+impl<T: Ord> TryFrom<RangeInclusive<T>> for Span<T> {
+    type Error = RangeInclusive<T>;
+
+    fn try_from(range: RangeInclusive<T>) -> Result<Self, Self::Error> {
+        if range.is_empty() {
+            return Err(range);
+        }
+        let (low, high) = range.into_inner();
+        Ok(Span { low, high })
+    }
+}
+
+/// Fails, handing the pair back, if its low end lies above its high end
+impl<T: Ord> TryFrom<(T, T)> for Span<T> {
+    type Error = (T, T);
+
+    fn try_from((low, high): (T, T)) -> Result<Self, Self::Error> {
+        if low > high {
+            return Err((low, high));
+        }
+        Ok(Span { low, high })
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn conversions() {
+        let span = Span::new(-3i8, 4).unwrap();
+        assert_eq!((span.low(), span.high()), (&-3, &4));
+        assert_eq!(span.len(), 8);
+        assert_eq!(RangeInclusive::from(span), -3..=4);
+        assert_eq!(<(i8, i8)>::from(span), (-3, 4));
+        assert_eq!(Span::try_from(-3..=4), Ok(span));
+        assert_eq!(Span::try_from((-3, 4)), Ok(span));
+        assert_eq!(Span::try_from((4, -3)), Err((4, -3)));
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 4..=-3;
+        assert_eq!(Span::try_from(empty.clone()), Err(empty));
+        assert_eq!(Span::new(5, 4), None);
+        assert_eq!(Span::new(u128::MIN, u128::MAX).unwrap().len(), u128::MAX);
+    }
+
+    #[test]
+    fn predicates_and_display() {
+        let span = Span::new(10u8, 20).unwrap();
+        assert!(span.contains(&10) && span.contains(&20) && !span.contains(&21));
+        assert!(span.overlaps(&Span::new(20, 30).unwrap()));
+        assert!(span.overlaps(&Span::new(12, 13).unwrap()));
+        assert!(!span.overlaps(&Span::new(21, 30).unwrap()));
+        assert!(!span.overlaps(&Span::new(0, 9).unwrap()));
+        assert_eq!(span.to_string(), "10-20");
+        assert_eq!(Span::new(7, 7).unwrap().to_string(), "7");
+    }
+}
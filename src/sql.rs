@@ -0,0 +1,215 @@
+use crate::{Discrete, Ranger};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::{Display, Write};
+
+/// A predicate no row satisfies, for the empty set
+const NOTHING: &str = "1=0";
+
+/// Runs of more values than this are always tested as a range, so that a
+/// wide run is never listed value by value
+const MAX_LISTED: u128 = 64;
+
+/// How [`Ranger::to_sql_predicate_with`] writes a predicate
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct SqlOptions {
+    /// Runs of at least this many values are tested as a range, and shorter
+    /// ones have their values listed in a single `IN`. Single values are
+    /// always listed, and runs of more than 64 values never are, however
+    /// high this is set. 3 by default.
+    pub min_range: u128,
+    /// Writes ranges as `col BETWEEN l AND h` if set, as it is by default, or
+    /// as `(col >= l AND col <= h)` for dialects or planners that handle
+    /// comparisons better
+    pub between: bool,
+}
+
+impl SqlOptions {
+    /// Returns the default options
+    pub const fn new() -> Self {
+        SqlOptions {
+            min_range: 3,
+            between: true,
+        }
+    }
+}
+
+impl Default for SqlOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Discrete + Display> Ranger<T> {
+    /// Returns an SQL predicate true for exactly the stored values of
+    /// `column`, written with the default [`SqlOptions`].
+    ///
+    /// `column` is written verbatim, so the caller must quote or escape it as
+    /// the database requires; never pass it unchecked input. Values are
+    /// written in their `Display` form, which suits numeric columns.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let ids: Ranger<u32> = "4,6-8,14-25,27".parse().unwrap();
+    /// assert_eq!(
+    ///     ids.to_sql_predicate("id"),
+    ///     "(id BETWEEN 6 AND 8 OR id BETWEEN 14 AND 25 OR id IN (4, 27))"
+    /// );
+    /// assert_eq!(Ranger::<u32>::new().to_sql_predicate("id"), "1=0");
+    /// ```
+    pub fn to_sql_predicate(&self, column: &str) -> String {
+        self.to_sql_predicate_with(column, &SqlOptions::new())
+    }
+    /// Returns an SQL predicate true for exactly the stored values of
+    /// `column`, which is written verbatim as for
+    /// [`to_sql_predicate`](Ranger::to_sql_predicate).
+    ///
+    /// The predicate tests each long enough run as a range, in ascending
+    /// order, then every other value in one `IN`, or with `=` if there is
+    /// only one. It is wrapped in parentheses so that it can be joined to
+    /// others with `AND`. The empty set gives `1=0`, which no row satisfies.
+    ///
+    /// ```
+    /// use ranger::{Ranger, SqlOptions};
+    ///
+    /// let ids: Ranger<i64> = "-3--1,10-11".parse().unwrap();
+    /// let opts = SqlOptions { min_range: 2, between: false };
+    /// assert_eq!(
+    ///     ids.to_sql_predicate_with("t.id", &opts),
+    ///     "((t.id >= -3 AND t.id <= -1) OR (t.id >= 10 AND t.id <= 11))"
+    /// );
+    /// ```
+    pub fn to_sql_predicate_with(&self, column: &str, opts: &SqlOptions) -> String {
+        if self.0.is_empty() {
+            return NOTHING.into();
+        }
+        let min_steps = opts.min_range.clamp(2, MAX_LISTED + 1) - 1;
+        let mut terms = Vec::new();
+        let mut listed = Vec::new();
+        for u in self.0.iter() {
+            let (l, h) = (&u.l, u.high());
+            let long = T::distance(l, h).is_none_or(|d| d >= min_steps);
+            if !long {
+                // short runs are listed value by value
+                let mut v = l.clone();
+                while &v < h {
+                    let next = v.successor().expect("below the high end");
+                    listed.push(v);
+                    v = next;
+                }
+                listed.push(v);
+            } else if opts.between {
+                terms.push(format!("{column} BETWEEN {l} AND {h}"));
+            } else {
+                terms.push(format!("({column} >= {l} AND {column} <= {h})"));
+            }
+        }
+        match listed.as_slice() {
+            [] => {}
+            [v] => terms.push(format!("{column} = {v}")),
+            values => {
+                let mut term = format!("{column} IN (");
+                for (i, v) in values.iter().enumerate() {
+                    let sep = if i == 0 { "" } else { ", " };
+                    write!(term, "{sep}{v}").expect("writing to a String");
+                }
+                term.push(')');
+                terms.push(term);
+            }
+        }
+        format!("({})", terms.join(" OR "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(s: &str) -> Ranger<i32> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn fixtures() {
+        assert_eq!(set("5").to_sql_predicate("id"), "(id = 5)");
+        assert_eq!(
+            set("1-1000000").to_sql_predicate("\"Id\""),
+            "(\"Id\" BETWEEN 1 AND 1000000)"
+        );
+        assert_eq!(
+            set("-10--8,-1-0,3,7-8,20-29").to_sql_predicate("x"),
+            "(x BETWEEN -10 AND -8 OR x BETWEEN 20 AND 29 OR x IN (-1, 0, 3, 7, 8))"
+        );
+        let opts = SqlOptions {
+            between: false,
+            ..SqlOptions::new()
+        };
+        assert_eq!(
+            set("1,3,5-9").to_sql_predicate_with("n", &opts),
+            "((n >= 5 AND n <= 9) OR n IN (1, 3))"
+        );
+        let full: Ranger<u128> = "0-340282366920938463463374607431768211455".parse().unwrap();
+        assert_eq!(
+            full.to_sql_predicate("id"),
+            "(id BETWEEN 0 AND 340282366920938463463374607431768211455)"
+        );
+    }
+
+    #[test]
+    fn empty_set_matches_nothing() {
+        let empty = Ranger::<u8>::new();
+        assert_eq!(empty.to_sql_predicate("id"), "1=0");
+        let opts = SqlOptions {
+            min_range: 0,
+            between: false,
+        };
+        assert_eq!(empty.to_sql_predicate_with("id", &opts), "1=0");
+    }
+
+    #[test]
+    fn threshold() {
+        let ranger = set("1,3-4,10-12,20-23");
+        let with = |min_range| {
+            let opts = SqlOptions {
+                min_range,
+                ..SqlOptions::new()
+            };
+            ranger.to_sql_predicate_with("id", &opts)
+        };
+        // single values are listed however low the threshold
+        for min_range in [0, 1, 2] {
+            assert_eq!(
+                with(min_range),
+                "(id BETWEEN 3 AND 4 OR id BETWEEN 10 AND 12 OR id BETWEEN 20 AND 23 OR id = 1)"
+            );
+        }
+        assert_eq!(
+            with(3),
+            "(id BETWEEN 10 AND 12 OR id BETWEEN 20 AND 23 OR id IN (1, 3, 4))"
+        );
+        assert_eq!(
+            with(4),
+            "(id BETWEEN 20 AND 23 OR id IN (1, 3, 4, 10, 11, 12))"
+        );
+        assert_eq!(with(5), "(id IN (1, 3, 4, 10, 11, 12, 20, 21, 22, 23))");
+        assert_eq!(with(u128::MAX), with(5));
+    }
+
+    #[test]
+    fn wide_runs_are_never_listed() {
+        let opts = SqlOptions {
+            min_range: u128::MAX,
+            ..SqlOptions::new()
+        };
+        let full: Ranger<u64> = "0-18446744073709551615".parse().unwrap();
+        assert_eq!(
+            full.to_sql_predicate_with("id", &opts),
+            "(id BETWEEN 0 AND 18446744073709551615)"
+        );
+        // 64 values are still listed, and 65 no longer
+        let ranger = set("1-64,100-164");
+        let predicate = ranger.to_sql_predicate_with("id", &opts);
+        assert!(predicate.starts_with("(id BETWEEN 100 AND 164 OR id IN (1, 2, 3, "));
+        assert!(predicate.ends_with(", 63, 64))"));
+    }
+}
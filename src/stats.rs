@@ -0,0 +1,483 @@
+use crate::{
+    discrete::{step_down, step_up},
+    Discrete, Ranger, Span, Unit,
+};
+use alloc::collections::btree_set;
+use core::{
+    fmt::{self, Display},
+    iter::Peekable,
+    ops::RangeInclusive,
+};
+use num_traits::{ToPrimitive, Zero};
+
+/// Iterator over coverage counts per bucket, see [`Ranger::histogram`]
+#[derive(Clone, Debug)]
+pub struct Histogram<'a, T> {
+    runs: Peekable<btree_set::Iter<'a, Unit<T>>>,
+    next_start: Option<T>,
+    bucket_width: u128,
+    max: Option<&'a T>,
+}
+
+impl<'a, T: Discrete> Iterator for Histogram<'a, T> {
+    type Item = (RangeInclusive<T>, u128);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.next_start.take()?;
+        let max = self.max?;
+        // a bucket running past the end of the type is cut at the last value
+        let end = start
+            .forward(self.bucket_width - 1)
+            .unwrap_or_else(|| max.clone());
+        let mut count = 0u128;
+        while let Some(u) = self.runs.peek() {
+            if u.l > end {
+                break;
+            }
+            let l = core::cmp::max(&u.l, &start);
+            let h = core::cmp::min(u.high(), &end);
+            count += T::distance(l, h).unwrap() + 1;
+            if u.high() > &end {
+                break;
+            }
+            self.runs.next();
+        }
+        if &end < max {
+            self.next_start = end.successor();
+        }
+        Some((start..=end, count))
+    }
+}
+
+impl<T: Discrete> Ranger<T> {
+    /// Counts the stored values falling in each of a series of buckets of
+    /// `bucket_width` values, aligned so that one bucket starts at `origin`.
+    ///
+    /// Every bucket between the lowest and highest stored values is yielded,
+    /// including those holding no values, so the output has no holes. Buckets
+    /// that would extend past the limits of `T` are truncated to the stored
+    /// values. Runs in O(runs + buckets) without visiting individual values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bucket_width` is not positive.
+    pub fn histogram(&self, origin: T, bucket_width: T) -> Histogram<'_, T>
+    where
+        T: ToPrimitive + Zero,
+    {
+        assert!(bucket_width > T::zero(), "bucket width must be positive");
+        let w = bucket_width.to_u128().unwrap_or(u128::MAX);
+        let next_start = self.0.first().map(|first| {
+            let min = &first.l;
+            if min >= &origin {
+                let d = T::distance(&origin, min).unwrap_or(u128::MAX);
+                origin.forward(d - d % w).unwrap()
+            } else {
+                let d = T::distance(min, &origin).unwrap_or(u128::MAX);
+                d.div_ceil(w)
+                    .checked_mul(w)
+                    .and_then(|back| origin.backward(back))
+                    .unwrap_or_else(|| min.clone())
+            }
+        });
+        Histogram {
+            runs: self.0.iter().peekable(),
+            next_start,
+            bucket_width: w,
+            max: self.0.last().map(|u| u.high()),
+        }
+    }
+}
+
+/// Figures describing a set at a glance, see [`Ranger::summary`].
+///
+/// Writes them on one line for logging, such as
+/// `count=1234 runs=17 span=0-9999 density=12.3%`, leaving out the span and
+/// density of an empty set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangerSummary<T> {
+    /// Number of values stored, saturating at `u128::MAX`
+    pub count: u128,
+    /// Number of runs stored
+    pub runs: usize,
+    /// Lowest stored value, or `None` if the set is empty
+    pub min: Option<T>,
+    /// Highest stored value, or `None` if the set is empty
+    pub max: Option<T>,
+    /// The run holding the most values, the lowest of any tied, or `None` if
+    /// the set is empty
+    pub longest_run: Option<Span<T>>,
+    /// The missing values between two runs forming the widest such gap, the
+    /// lowest of any tied, or `None` if there are fewer than two runs
+    pub widest_gap: Option<Span<T>>,
+    /// Share of the values from `min` to `max` that are stored, from zero to
+    /// one, or `None` if the set is empty
+    pub density: Option<f64>,
+}
+
+impl<T: Eq + Display> Display for RangerSummary<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "count={} runs={}", self.count, self.runs)?;
+        if let (Some(min), Some(max)) = (&self.min, &self.max) {
+            write!(f, " span={}", min)?;
+            if min != max {
+                write!(f, "-{}", max)?;
+            }
+        }
+        if let Some(density) = self.density {
+            write!(f, " density={:.1}%", density * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Discrete> Ranger<T> {
+    /// Returns the count, extremes, longest run, widest gap and density of
+    /// the set, gathered in one pass over its runs
+    pub fn summary(&self) -> RangerSummary<T> {
+        let mut count = 0u128;
+        let mut longest: Option<(u128, &Unit<T>)> = None;
+        let mut widest: Option<(u128, &T, &T)> = None;
+        let mut prev: Option<&Unit<T>> = None;
+        for u in self.0.iter() {
+            let len = u.len();
+            count = count.saturating_add(len);
+            if longest.is_none_or(|(l, _)| len > l) {
+                longest = Some((len, u));
+            }
+            if let Some(p) = prev {
+                // one more than the gap's width, which is enough to compare
+                let d = T::distance(p.high(), &u.l).unwrap_or(u128::MAX);
+                if widest.is_none_or(|(w, _, _)| d > w) {
+                    widest = Some((d, p.high(), &u.l));
+                }
+            }
+            prev = Some(u);
+        }
+        let min = self.0.first().map(|u| u.l.clone());
+        let max = self.0.last().map(|u| u.high().clone());
+        let density = min.as_ref().zip(max.as_ref()).map(|(min, max)| {
+            let width = T::distance(min, max).map_or(u128::MAX, |w| w.saturating_add(1));
+            count as f64 / width as f64
+        });
+        RangerSummary {
+            count,
+            runs: self.0.len(),
+            min,
+            max,
+            longest_run: longest.map(|(_, u)| u.span()),
+            widest_gap: widest.map(|(_, h, l)| Span::new_unchecked(step_up(h), step_down(l))),
+            density,
+        }
+    }
+}
+
+/// How much of a domain a set covers, see [`Ranger::coverage`]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct Coverage {
+    /// Number of values in the domain that are stored, saturating at
+    /// `u128::MAX`
+    pub covered: u128,
+    /// Number of values in the domain, saturating at `u128::MAX`
+    pub total: u128,
+}
+
+impl Coverage {
+    /// Returns the share of the domain that is stored, from zero to one. An
+    /// empty domain counts as not covered at all.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.covered as f64 / self.total as f64
+    }
+}
+
+impl<T: Discrete> Ranger<T> {
+    /// Counts the values of `domain` and how many of them are stored,
+    /// walking only the runs that overlap it. Values stored outside the
+    /// domain are ignored, and a reversed domain is empty.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let done: Ranger<u32> = "0-899,950-999,2000-2999".parse().unwrap();
+    /// let coverage = done.coverage(0..=999);
+    /// assert_eq!((coverage.covered, coverage.total), (950, 1000));
+    /// assert_eq!(coverage.fraction(), 0.95);
+    /// ```
+    pub fn coverage(&self, domain: RangeInclusive<T>) -> Coverage {
+        if domain.is_empty() {
+            return Coverage {
+                covered: 0,
+                total: 0,
+            };
+        }
+        let (low, high) = (domain.start(), domain.end());
+        let total = T::distance(low, high).map_or(u128::MAX, |w| w.saturating_add(1));
+        let covered = self
+            .0
+            .range::<T, _>(low..)
+            .take_while(|u| &u.l <= high)
+            .fold(0u128, |covered, u| {
+                let l = core::cmp::max(&u.l, low);
+                let h = core::cmp::min(u.high(), high);
+                let len = T::distance(l, h).map_or(u128::MAX, |w| w.saturating_add(1));
+                covered.saturating_add(len)
+            });
+        Coverage { covered, total }
+    }
+}
+
+/// Returns a run width given as `T` as a count of values, taking anything
+/// below zero as zero
+fn width_of<T: ToPrimitive + Zero + PartialOrd>(width: T) -> u128 {
+    match width.to_u128() {
+        Some(width) => width,
+        None if width > T::zero() => u128::MAX,
+        None => 0,
+    }
+}
+
+impl<T: Discrete + ToPrimitive + Zero> Ranger<T> {
+    /// Iterates over the runs holding at least `min_width` values, in
+    /// ascending order. A single value is a run of width one.
+    ///
+    /// ```
+    /// use ranger::Ranger;
+    ///
+    /// let free: Ranger<u64> = "0-63,100,200-262,300-499".parse().unwrap();
+    /// assert_eq!(free.count_ranges_at_least(64), 2);
+    /// let big: Vec<_> = free.ranges_at_least(64).collect();
+    /// assert_eq!(big, [0..=63, 300..=499]);
+    /// ```
+    pub fn ranges_at_least(&self, min_width: T) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let min_width = width_of(min_width);
+        self.0
+            .iter()
+            .filter(move |u| u.len() >= min_width)
+            .map(|u| u.l.clone()..=u.high().clone())
+    }
+    /// Returns the number of runs holding at least `min_width` values
+    pub fn count_ranges_at_least(&self, min_width: T) -> usize {
+        let min_width = width_of(min_width);
+        self.0.iter().filter(|u| u.len() >= min_width).count()
+    }
+    /// Iterates over the runs holding fewer than `max_width` values, in
+    /// ascending order: those [`ranges_at_least`](Self::ranges_at_least)
+    /// leaves out for the same width
+    pub fn ranges_shorter_than(
+        &self,
+        max_width: T,
+    ) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        let max_width = width_of(max_width);
+        self.0
+            .iter()
+            .filter(move |u| u.len() < max_width)
+            .map(|u| u.l.clone()..=u.high().clone())
+    }
+    /// Returns the number of runs holding fewer than `max_width` values
+    pub fn count_ranges_shorter_than(&self, max_width: T) -> usize {
+        let max_width = width_of(max_width);
+        self.0.iter().filter(|u| u.len() < max_width).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+
+    #[test]
+    fn histogram() {
+        let mut ranger = Ranger::<i32>::new();
+        ranger.insert_range(3..=12);
+        ranger.insert_range(20..=29);
+        ranger.insert_range(45..=45);
+        let buckets: Vec<_> = ranger.histogram(0, 10).collect();
+        assert_eq!(
+            buckets,
+            [
+                (0..=9, 7),
+                (10..=19, 3),
+                (20..=29, 10),
+                (30..=39, 0),
+                (40..=49, 1)
+            ]
+        );
+        // a clone taken part way resumes from the same bucket
+        let mut histogram = ranger.histogram(0, 10);
+        histogram.next();
+        let rest: Vec<_> = histogram.clone().collect();
+        assert!(histogram.eq(rest));
+        assert!(alloc::format!("{:?}", ranger.histogram(0, 10)).starts_with("Histogram"));
+        let buckets: Vec<_> = ranger.histogram(-3, 10).collect();
+        assert_eq!(
+            buckets,
+            [
+                (-3..=6, 4),
+                (7..=16, 6),
+                (17..=26, 7),
+                (27..=36, 3),
+                (37..=46, 1)
+            ]
+        );
+        let buckets: Vec<_> = ranger.histogram(1004, 20).collect();
+        assert_eq!(
+            buckets,
+            [(-16..=3, 1), (4..=23, 13), (24..=43, 6), (44..=63, 1)]
+        );
+        let buckets: Vec<_> = ranger.histogram(0, 1).collect();
+        assert_eq!(buckets.len(), 43);
+        assert_eq!(buckets.iter().map(|(_, c)| c).sum::<u128>(), ranger.len());
+        assert!(buckets
+            .iter()
+            .all(|(r, c)| *c == ranger.contains(r.start()) as u128));
+        assert_eq!(Ranger::<i32>::new().histogram(0, 5).count(), 0);
+
+        let mut ranger = Ranger::<u8>::new();
+        ranger.insert_range(0..=4);
+        ranger.insert_range(250..=255);
+        let buckets: Vec<_> = ranger.histogram(100, 100).collect();
+        assert_eq!(buckets, [(0..=99, 5), (100..=199, 0), (200..=255, 6)]);
+        let ranger = Ranger::<i8>::with_gap_tolerance(0);
+        assert_eq!(ranger.histogram(0, i8::MAX).count(), 0);
+    }
+
+    #[test]
+    fn summary() {
+        let mut ranger = Ranger::<i32>::new();
+        ranger.insert_range(3..=12);
+        ranger.insert_range(20..=29);
+        ranger.insert_range(45..=45);
+        let summary = ranger.summary();
+        assert_eq!(summary.count, 21);
+        assert_eq!(summary.runs, 3);
+        assert_eq!((summary.min, summary.max), (Some(3), Some(45)));
+        // the first of the two runs of ten
+        assert_eq!(summary.longest_run, Span::new(3, 12));
+        assert_eq!(summary.widest_gap, Span::new(30, 44));
+        assert_eq!(summary.density, Some(21.0 / 43.0));
+        assert_eq!(
+            summary.to_string(),
+            "count=21 runs=3 span=3-45 density=48.8%"
+        );
+
+        let ranger: Ranger<u8> = "7".parse().unwrap();
+        let summary = ranger.summary();
+        assert_eq!(summary.longest_run, Span::new(7, 7));
+        assert_eq!(summary.widest_gap, None);
+        assert_eq!(summary.to_string(), "count=1 runs=1 span=7 density=100.0%");
+        // equal gaps keep the lowest
+        let ranger: Ranger<u8> = "0,2,4-9,11".parse().unwrap();
+        assert_eq!(ranger.summary().widest_gap, Span::new(1, 1));
+        assert_eq!(
+            ranger.summary().to_string(),
+            "count=9 runs=4 span=0-11 density=75.0%"
+        );
+
+        let summary = Ranger::<u8>::new().summary();
+        assert_eq!(
+            summary,
+            RangerSummary {
+                count: 0,
+                runs: 0,
+                min: None,
+                max: None,
+                longest_run: None,
+                widest_gap: None,
+                density: None,
+            }
+        );
+        assert_eq!(summary.to_string(), "count=0 runs=0");
+        let full = Ranger::from(i128::MIN..=i128::MAX).summary();
+        assert_eq!(full.count, u128::MAX);
+        assert_eq!(full.density, Some(1.0));
+    }
+
+    #[test]
+    fn coverage() {
+        let ranger: Ranger<i32> = "-20--5,0-9,15,30-49,60-70".parse().unwrap();
+        // cut at both edges: -10 to -5, then 0-9, 15 and 30 to 34
+        let coverage = ranger.coverage(-10..=34);
+        assert_eq!(
+            coverage,
+            Coverage {
+                covered: 22,
+                total: 45
+            }
+        );
+        assert_eq!(coverage.fraction(), 22.0 / 45.0);
+        assert_eq!(ranger.coverage(-100..=100).covered, ranger.len());
+        assert_eq!(ranger.coverage(10..=14).covered, 0);
+        assert_eq!(ranger.coverage(50..=59).fraction(), 0.0);
+        assert_eq!(ranger.coverage(31..=48).fraction(), 1.0);
+        // single values
+        let single = ranger.coverage(15..=15);
+        assert_eq!((single.covered, single.total), (1, 1));
+        assert_eq!(ranger.coverage(16..=16).covered, 0);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = ranger.coverage(9..=0);
+        assert_eq!((reversed.covered, reversed.total), (0, 0));
+        assert_eq!(reversed.fraction(), 0.0);
+        let empty = Ranger::<u8>::new().coverage(0..=255);
+        assert_eq!((empty.covered, empty.total), (0, 256));
+        let full = Ranger::from(i128::MIN..=i128::MAX).coverage(i128::MIN..=i128::MAX);
+        assert_eq!((full.covered, full.total), (u128::MAX, u128::MAX));
+        assert_eq!(full.fraction(), 1.0);
+    }
+
+    #[test]
+    fn ranges_by_width() {
+        // widths 1, 2, 3, 4 and 1
+        let ranger: Ranger<i16> = "-10,0-1,5-7,10-13,20".parse().unwrap();
+        let at_least = |w| ranger.ranges_at_least(w).collect::<Vec<_>>();
+        let shorter = |w| ranger.ranges_shorter_than(w).collect::<Vec<_>>();
+        assert_eq!(at_least(3), [5..=7, 10..=13]);
+        assert_eq!(shorter(3), [-10..=-10, 0..=1, 20..=20]);
+        // one below, at and one above the widest run
+        assert_eq!(at_least(4), [10..=13]);
+        assert_eq!(at_least(5), []);
+        assert_eq!(ranger.count_ranges_at_least(3), 2);
+        assert_eq!(ranger.count_ranges_shorter_than(4), 4);
+        assert_eq!(ranger.count_ranges_shorter_than(5), 5);
+        // single values have width one
+        assert_eq!(ranger.count_ranges_at_least(1), 5);
+        assert_eq!(ranger.count_ranges_shorter_than(1), 0);
+        assert_eq!(shorter(2), [-10..=-10, 20..=20]);
+        // widths of zero or below admit every run
+        assert_eq!(ranger.count_ranges_at_least(0), 5);
+        assert_eq!(ranger.count_ranges_at_least(-3), 5);
+        assert_eq!(ranger.count_ranges_shorter_than(-3), 0);
+        for w in -2..8 {
+            assert_eq!(
+                ranger.count_ranges_at_least(w) + ranger.count_ranges_shorter_than(w),
+                ranger.range_count()
+            );
+            assert_eq!(ranger.count_ranges_at_least(w), at_least(w).len());
+        }
+        // the gaps between the runs, from the complement
+        let gaps = ranger.invert_within(-10..=20);
+        assert_eq!(gaps.to_string(), "-9--1,2-4,8-9,14-19");
+        assert_eq!(gaps.count_ranges_at_least(6), 2);
+        assert_eq!(gaps.count_ranges_shorter_than(3), 1);
+        // runs too wide to count in the type itself
+        let full = Ranger::from(i8::MIN..=i8::MAX);
+        assert_eq!(full.count_ranges_at_least(i8::MAX), 1);
+        let full = Ranger::from(i128::MIN..=i128::MAX);
+        assert_eq!(full.count_ranges_at_least(i128::MAX), 1);
+        assert_eq!(full.count_ranges_shorter_than(i128::MAX), 0);
+        let full = Ranger::from(0..=u128::MAX);
+        assert_eq!(full.count_ranges_at_least(u128::MAX), 1);
+        assert_eq!(full.count_ranges_shorter_than(u128::MAX), 0);
+        assert_eq!(Ranger::<u8>::new().count_ranges_at_least(0), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn histogram_zero_width() {
+        let mut ranger = Ranger::<i32>::new();
+        ranger.insert(1);
+        ranger.histogram(0, 0);
+    }
+}
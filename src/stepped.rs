@@ -0,0 +1,203 @@
+use crate::{Discrete, Ranger, Unit};
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Display, Write},
+    ops::RangeInclusive,
+};
+
+impl<T: Discrete> Ranger<T> {
+    /// Inserts every `step`th value of `range` starting from its low end, as
+    /// in the `0-100/5` form that parsing accepts. A `step` of zero or one
+    /// inserts the whole range. Returns the number of values that were not
+    /// already present.
+    pub fn insert_stepped(&mut self, range: RangeInclusive<T>, step: u128) -> u128 {
+        if step <= 1 {
+            return self.insert_range(range);
+        }
+        let (mut v, high) = range.into_inner();
+        if v > high {
+            return 0;
+        }
+        let mut added = 0;
+        loop {
+            let next = v.forward(step).filter(|n| n <= &high);
+            added += self.insert(v) as u128;
+            match next {
+                Some(n) => v = n,
+                None => return added,
+            }
+        }
+    }
+    /// Returns a value displaying the set like its `Display` form, except
+    /// that runs of single values spaced evenly apart are written as
+    /// `low-high/step` wherever that is shorter
+    pub fn display_stepped(&self) -> DisplayStepped<'_, T> {
+        DisplayStepped { ranger: self }
+    }
+}
+
+/// Writes a set like its `Display` form, but folding evenly spaced single
+/// values into `low-high/step`. Returned by [`Ranger::display_stepped`].
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayStepped<'a, T> {
+    ranger: &'a Ranger<T>,
+}
+
+/// Counts the bytes written to it
+struct Width(usize);
+
+impl Write for Width {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+fn width(v: &impl Display) -> usize {
+    let mut w = Width(0);
+    // counting never fails
+    let _ = write!(w, "{}", v);
+    w.0
+}
+
+/// Returns the number of units and the step of the longest evenly spaced
+/// progression of single values that `units` starts with, if writing it as
+/// `low-high/step` is shorter than listing its values
+fn progression<T: Discrete + Display>(units: &[&Unit<T>]) -> Option<(usize, u128)> {
+    let single = |u: &Unit<T>| u.high() == &u.l;
+    let (a, b) = (units.first()?, units.get(1)?);
+    if !single(a) || !single(b) {
+        return None;
+    }
+    let step = T::distance(&a.l, &b.l)?;
+    let mut k = 2;
+    while units
+        .get(k)
+        .is_some_and(|u| single(u) && T::distance(&units[k - 1].l, &u.l) == Some(step))
+    {
+        k += 1;
+    }
+    let listed: usize = units[..k].iter().map(|u| width(&u.l) + 1).sum::<usize>() - 1;
+    let folded = width(&a.l) + width(&units[k - 1].l) + width(&step) + 2;
+    (folded < listed).then_some((k, step))
+}
+
+impl<T: Discrete + Display> Display for DisplayStepped<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units: Vec<&Unit<T>> = self.ranger.0.iter().collect();
+        let mut i = 0;
+        while i < units.len() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            match progression(&units[i..]) {
+                Some((k, step)) => {
+                    write!(f, "{}-{}/{}", units[i].l, units[i + k - 1].l, step)?;
+                    i += k;
+                }
+                None => {
+                    write!(f, "{}", units[i])?;
+                    i += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseRangeError;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse_steps() {
+        let ranger: Ranger<u32> = "0-100/5".parse().unwrap();
+        assert_eq!(ranger.len(), 21);
+        assert!(ranger.contains(&95) && ranger.contains(&100) && !ranger.contains(&99));
+        // the high end need not be on the progression
+        let ranger: Ranger<u32> = "3-20/7, 40".parse().unwrap();
+        assert_eq!(ranger.to_string(), "3,10,17,40");
+        let ranger: Ranger<i8> = "-9--1/4".parse().unwrap();
+        assert_eq!(ranger.to_string(), "-9,-5,-1");
+        // a step wider than the range keeps only its low end
+        assert_eq!("5-9/10".parse::<Ranger<u8>>().unwrap().to_string(), "5");
+        assert_eq!("7/3".parse::<Ranger<u8>>().unwrap().to_string(), "7");
+        assert_eq!("0-4/1".parse::<Ranger<u8>>().unwrap().to_string(), "0-4");
+        for bad in ["0-4/0", "0-4/", "0-4/x", "0-4/-2", "0-4/2/2"] {
+            assert_eq!(bad.parse::<Ranger<u8>>(), Err(ParseRangeError::Invalid(0)));
+        }
+        assert_eq!(
+            "1,9-3/2".parse::<Ranger<u8>>(),
+            Err(ParseRangeError::Reversed(2))
+        );
+    }
+
+    #[test]
+    fn stepped_entries_are_capped() {
+        // a short entry may not expand to billions of runs
+        assert_eq!(
+            "1,0-4294967295/2".parse::<Ranger<u32>>(),
+            Err(ParseRangeError::Invalid(2))
+        );
+        let all: Ranger<u32> = "0-4294967295".parse().unwrap();
+        assert_eq!(
+            all.matches_spec("0-4294967295/3"),
+            Err(ParseRangeError::Invalid(0))
+        );
+        // up to 65536 values are still read, checked here against an empty
+        // set rather than building that many runs
+        let empty = Ranger::<u32>::new();
+        assert_eq!(empty.matches_spec("0-131070/2"), Ok(false));
+        assert_eq!(empty.matches_spec("0-4294967295/65536"), Ok(false));
+        assert_eq!(
+            empty.matches_spec("0-131072/2"),
+            Err(ParseRangeError::Invalid(0))
+        );
+        let ranger: Ranger<u32> = "0-1000/2".parse().unwrap();
+        assert_eq!(ranger.len(), 501);
+    }
+
+    #[test]
+    fn insert_stepped() {
+        let mut ranger = Ranger::<u8>::new();
+        assert_eq!(ranger.insert_stepped(250..=255, 2), 3);
+        assert_eq!(ranger.to_string(), "250,252,254");
+        assert_eq!(ranger.insert_stepped(250..=255, 4), 0);
+        assert_eq!(ranger.insert_stepped(251..=255, 2), 3);
+        assert_eq!(ranger.to_string(), "250-255");
+        assert_eq!(ranger.insert_stepped(0..=3, 0), 4);
+        #[allow(clippy::reversed_empty_ranges)]
+        let added = ranger.insert_stepped(9..=3, 2);
+        assert_eq!(added, 0);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for (text, plain) in [
+            ("0-100/5", None),
+            ("0-20/10", Some("0,10,20")),
+            ("100-700/200", None),
+            ("1-3,10-50/10,60-63", None),
+            ("1-3,10,20,30,40-43", Some("1-3,10,20,30,40-43")),
+            ("5", Some("5")),
+            ("0-9/3,12-14", None),
+        ] {
+            let ranger: Ranger<u32> = text.parse().unwrap();
+            let stepped = ranger.display_stepped().to_string();
+            assert_eq!(stepped, plain.unwrap_or(text));
+            assert_eq!(stepped.parse::<Ranger<u32>>().unwrap(), ranger);
+        }
+        // the default form is unchanged
+        let ranger: Ranger<u32> = "0-100/25".parse().unwrap();
+        assert_eq!(ranger.to_string(), "0,25,50,75,100");
+        assert_eq!(ranger.display_stepped().to_string(), "0-100/25");
+        // step one is just a run
+        let ranger: Ranger<u32> = "0-100/1".parse().unwrap();
+        assert_eq!(ranger.display_stepped().to_string(), "0-100");
+        let ranger: Ranger<i16> = "-300-300/100".parse().unwrap();
+        assert_eq!(ranger.display_stepped().to_string(), "-300-300/100");
+        assert_eq!(Ranger::<u8>::new().display_stepped().to_string(), "");
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{Discrete, OutOfRange, ParseRangeError, Ranger};
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+use num_traits::Zero;
+
+impl<T: Discrete + Zero> Ranger<T> {
+    /// Parses a mask in the form Linux sysfs uses for CPU and node masks,
+    /// such as `ff,00000f0f`: comma-separated 32-bit hex words with the most
+    /// significant first, where bit `i` across the words stands for the
+    /// value `i`. Words may have fewer than eight digits and a trailing
+    /// newline is ignored, so the contents of a sysfs file can be passed as
+    /// read. An empty word is reported as [`Empty`](ParseRangeError::Empty)
+    /// and any other malformed one as [`Invalid`](ParseRangeError::Invalid),
+    /// each with the word's byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` cannot hold the index of a set bit.
+    pub fn from_sysfs_mask(s: &str) -> Result<Ranger<T>, ParseRangeError> {
+        let s = s.strip_suffix('\n').unwrap_or(s);
+        let mut words = Vec::new();
+        let mut pos = 0;
+        for word in s.split(',') {
+            if word.is_empty() {
+                return Err(ParseRangeError::Empty(pos));
+            }
+            // from_str_radix would also take a sign
+            let digits = word.trim_start_matches('0');
+            if digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ParseRangeError::Invalid(pos));
+            }
+            words.push(u32::from_str_radix(word, 16).expect("at most eight hex digits"));
+            pos += word.len() + 1;
+        }
+        let bytes: Vec<u8> = words.iter().rev().flat_map(|w| w.to_le_bytes()).collect();
+        Ok(Self::from_bitmap_bytes(&bytes))
+    }
+    /// Returns the set as a sysfs mask of `width_words` 32-bit words, each
+    /// written as eight lowercase hex digits, most significant first, as
+    /// read by [`from_sysfs_mask`](Self::from_sysfs_mask). Fails if any value
+    /// is negative or does not fit in the words.
+    pub fn to_sysfs_mask(&self, width_words: usize) -> Result<String, OutOfRange> {
+        let bytes = self.to_bitmap_bytes(width_words * 4)?;
+        let mut s = String::with_capacity(width_words * 9);
+        for (i, word) in bytes.chunks_exact(4).rev().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            let word = u32::from_le_bytes(word.try_into().unwrap());
+            // writing to a String never fails
+            let _ = write!(s, "{:08x}", word);
+        }
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn sysfs_strings() {
+        // contents of cpumap, cpulist and friends, newline included
+        for (mask, list, words) in [
+            ("ff,00000f0f\n", "0-3,8-11,32-39", 2),
+            ("00000001\n", "0", 1),
+            ("ffffffff,ffffffff\n", "0-63", 2),
+            ("00000000,00000000,00000000,00000003\n", "0-1", 4),
+            ("0000ffff,ffff0000,00000000\n", "48-79", 3),
+            ("00000000,00000000\n", "", 2),
+            ("80000000,00000000,00000001\n", "0,95", 3),
+        ] {
+            let ranger = Ranger::<u32>::from_sysfs_mask(mask).unwrap();
+            assert_eq!(ranger.to_string(), list, "{mask}");
+            let canonical = ranger.to_sysfs_mask(words).unwrap();
+            if mask.starts_with("ff,") {
+                assert_eq!(canonical, "000000ff,00000f0f");
+            } else {
+                assert_eq!(canonical, mask.trim_end());
+            }
+            // the cpulist form converts back the other way
+            let ranger: Ranger<u32> = list.parse().unwrap();
+            assert_eq!(ranger.to_sysfs_mask(words).unwrap(), canonical);
+        }
+    }
+
+    #[test]
+    fn loose_input() {
+        let parse = Ranger::<u16>::from_sysfs_mask;
+        assert_eq!(parse("f").unwrap().to_string(), "0-3");
+        assert_eq!(parse("0000000000f0").unwrap().to_string(), "4-7");
+        assert_eq!(parse("1,0").unwrap().to_string(), "32");
+        assert_eq!(parse("0").unwrap().to_string(), "");
+        assert_eq!(parse("A,B\n").unwrap().to_string(), "0-1,3,33,35");
+        assert_eq!(parse(""), Err(ParseRangeError::Empty(0)));
+        assert_eq!(parse("ff,,1"), Err(ParseRangeError::Empty(3)));
+        assert_eq!(parse("ff,1g"), Err(ParseRangeError::Invalid(3)));
+        assert_eq!(parse("+f"), Err(ParseRangeError::Invalid(0)));
+        assert_eq!(parse("1,100000000"), Err(ParseRangeError::Invalid(2)));
+        assert_eq!(parse("f\n\n"), Err(ParseRangeError::Invalid(0)));
+    }
+
+    #[test]
+    fn too_narrow() {
+        let ranger: Ranger<i32> = "0,40".parse().unwrap();
+        assert_eq!(ranger.to_sysfs_mask(1), Err(OutOfRange));
+        assert_eq!(ranger.to_sysfs_mask(2).unwrap(), "00000100,00000001");
+        let ranger: Ranger<i32> = "-1".parse().unwrap();
+        assert_eq!(ranger.to_sysfs_mask(4), Err(OutOfRange));
+        assert_eq!(Ranger::<u8>::new().to_sysfs_mask(0).unwrap(), "");
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut rng = thread_rng();
+        for _ in 0..500 {
+            let words = rng.gen_range(1..12);
+            let mut ranger = Ranger::<u32>::new();
+            for _ in 0..rng.gen_range(0..8) {
+                let l = rng.gen_range(0..words as u32 * 32);
+                let h = rng.gen_range(l..words as u32 * 32);
+                ranger.insert_range(l..=h);
+            }
+            let mask = ranger.to_sysfs_mask(words).unwrap();
+            assert_eq!(mask.len(), words * 9 - 1);
+            assert_eq!(Ranger::from_sysfs_mask(&mask).unwrap(), ranger);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Ranger, Span};
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Write};
+
+/// Asserts that two sets hold the same values, as `assert_eq!` does, but on
+/// failure reports only the runs found in one set and not the other, such as
+/// `only in left: 14-19; only in right: 14-25`.
+///
+/// ```should_panic
+/// use ranger::{assert_ranger_eq, Ranger};
+///
+/// let left: Ranger<u32> = "1-10,14-19,40".parse().unwrap();
+/// let right: Ranger<u32> = "1-10,14-25,40".parse().unwrap();
+/// assert_ranger_eq(&left, &right);
+/// ```
+#[track_caller]
+pub fn assert_ranger_eq<T: Ord + Clone + Display>(left: &Ranger<T>, right: &Ranger<T>) {
+    if let Some(difference) = explain_difference(left, right) {
+        panic!("assertion `left == right` failed: {difference}");
+    }
+}
+
+/// Returns the runs found in one set and not the other, formatted as
+/// [`assert_ranger_eq`] reports them, or `None` if the sets hold the same
+/// values. Runs are compared whole, so a run that differs from its
+/// counterpart in a single value is listed for both sides.
+///
+/// ```
+/// use ranger::{explain_difference, Ranger};
+///
+/// let left: Ranger<i32> = "-5--1,3,7-9".parse().unwrap();
+/// let right: Ranger<i32> = "-5--1,7-8".parse().unwrap();
+/// assert_eq!(
+///     explain_difference(&left, &right).as_deref(),
+///     Some("only in left: 3,7-9; only in right: 7-8")
+/// );
+/// assert_eq!(explain_difference(&left, &left.clone()), None);
+/// ```
+pub fn explain_difference<T: Ord + Clone + Display>(
+    left: &Ranger<T>,
+    right: &Ranger<T>,
+) -> Option<String> {
+    let (mut only_left, mut only_right) = (Vec::new(), Vec::new());
+    let (mut l, mut r) = (left.ranges().peekable(), right.ranges().peekable());
+    // runs don't overlap, so both sides are in order of their low ends
+    loop {
+        match (l.peek(), r.peek()) {
+            (None, None) => break,
+            (Some(a), Some(b)) if a == b => {
+                l.next();
+                r.next();
+            }
+            (Some(a), Some(b)) if (a.low(), a.high()) < (b.low(), b.high()) => {
+                only_left.extend(l.next());
+            }
+            (Some(_), None) => only_left.extend(l.next()),
+            _ => only_right.extend(r.next()),
+        }
+    }
+    let mut out = String::new();
+    for (side, runs) in [("left", only_left), ("right", only_right)] {
+        if runs.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str("; ");
+        }
+        write!(out, "only in {side}: ").expect("writing to a String");
+        push_runs(&mut out, &runs);
+    }
+    (!out.is_empty()).then_some(out)
+}
+
+fn push_runs<T: Eq + Display>(out: &mut String, runs: &[Span<T>]) {
+    for (i, run) in runs.iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        write!(out, "{sep}{run}").expect("writing to a String");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(s: &str) -> Ranger<u32> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn reports_only_differing_runs() {
+        let left = set("0-9,14-19,30,50-59,70");
+        let right = set("0-9,14-25,30,70,80-89");
+        assert_eq!(
+            explain_difference(&left, &right).as_deref(),
+            Some("only in left: 14-19,50-59; only in right: 14-25,80-89")
+        );
+        assert_eq!(
+            explain_difference(&set("1-5"), &set("")).as_deref(),
+            Some("only in left: 1-5")
+        );
+        assert_eq!(
+            explain_difference(&set(""), &set("1-5,7")).as_deref(),
+            Some("only in right: 1-5,7")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left == right` failed: only in left: 4; \
+                               only in right: 5-6")]
+    fn panics_with_the_difference() {
+        assert_ranger_eq(&set("1-2,4"), &set("1-2,5-6"));
+    }
+
+    #[test]
+    fn equal_sets_pass() {
+        let ranger = set("3-8,20,30-4000");
+        assert_eq!(explain_difference(&ranger, &ranger.clone()), None);
+        assert_ranger_eq(&ranger, &ranger.clone());
+        assert_ranger_eq(&set(""), &set(""));
+        // settings don't take part, as for ==
+        let mut tolerant = Ranger::with_gap_tolerance(5);
+        tolerant.insert_range(3..=8);
+        tolerant.insert(20);
+        tolerant.insert_range(30..=4000);
+        assert_eq!(explain_difference(&ranger, &tolerant), None);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{parse::parse_list, Discrete, DisplayWith, ParseRangeError, Ranger};
+use time::{Date, Month};
+
+/// Days follow one another across month and year ends, leap days included
+impl Discrete for Date {
+    fn successor(&self) -> Option<Self> {
+        self.next_day()
+    }
+    fn predecessor(&self) -> Option<Self> {
+        self.previous_day()
+    }
+    fn distance(lower: &Self, upper: &Self) -> Option<u128> {
+        u128::try_from(upper.to_julian_day() - lower.to_julian_day()).ok()
+    }
+    fn forward(&self, steps: u128) -> Option<Self> {
+        let day = self
+            .to_julian_day()
+            .checked_add(i32::try_from(steps).ok()?)?;
+        Date::from_julian_day(day).ok()
+    }
+    fn backward(&self, steps: u128) -> Option<Self> {
+        let day = self
+            .to_julian_day()
+            .checked_sub(i32::try_from(steps).ok()?)?;
+        Date::from_julian_day(day).ok()
+    }
+}
+
+/// Reads an ISO 8601 calendar date such as `2024-02-29`
+fn parse_iso(s: &str) -> Option<Date> {
+    let (rest, day) = s.rsplit_once('-')?;
+    let (year, month) = rest.rsplit_once('-')?;
+    let digits = |v: &str| v.len() == 2 && v.bytes().all(|b| b.is_ascii_digit());
+    if year.len() < 4 || !digits(month) || !digits(day) {
+        return None;
+    }
+    let month = Month::try_from(month.parse::<u8>().ok()?).ok()?;
+    Date::from_calendar_date(year.parse().ok()?, month, day.parse().ok()?).ok()
+}
+
+impl Ranger<Date> {
+    /// Returns a value displaying the set with ISO dates, joining the ends of
+    /// each run with `..` as in `2024-01-03..2024-01-09,2024-01-12`
+    pub fn display_dates(&self) -> DisplayWith<'_, Date> {
+        self.display_with("..")
+    }
+    /// Parses ISO dates in the form written by
+    /// [`display_dates`](Ranger::display_dates)
+    pub fn parse_dates(s: &str) -> Result<Self, ParseRangeError> {
+        Self::parse_dates_with(s, "..")
+    }
+    /// Parses ISO dates whose run ends are joined by `separator`, which may
+    /// even be `-`
+    pub fn parse_dates_with(s: &str, separator: &str) -> Result<Self, ParseRangeError> {
+        parse_list(s, separator, parse_iso)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn date(year: i32, month: u8, day: u8) -> Date {
+        Date::from_calendar_date(year, Month::try_from(month).unwrap(), day).unwrap()
+    }
+
+    #[test]
+    fn leap_day() {
+        let mut days = Ranger::new();
+        for day in [date(2024, 3, 1), date(2024, 2, 28)] {
+            days.insert(day);
+        }
+        assert_eq!(days.range_count(), 2);
+        days.insert(date(2024, 2, 29));
+        assert_eq!(days.range_count(), 1);
+        assert_eq!(days.display_dates().to_string(), "2024-02-28..2024-03-01");
+
+        let mut common = Ranger::new();
+        common.insert(date(2023, 2, 28));
+        common.insert(date(2023, 3, 1));
+        assert_eq!(common.range_count(), 1);
+
+        let text = days.display_dates().to_string();
+        assert_eq!(Ranger::parse_dates(&text), Ok(days));
+        assert!(Ranger::parse_dates("2023-02-29").is_err());
+    }
+
+    #[test]
+    fn year_end() {
+        let mut days = Ranger::new();
+        days.insert_range(date(2023, 12, 30)..=date(2023, 12, 31));
+        days.insert_range(date(2024, 1, 1)..=date(2024, 1, 9));
+        days.insert(date(2024, 1, 12));
+        assert_eq!(days.len(), 12);
+        assert_eq!(
+            days.display_with("-").to_string(),
+            "2023-12-30-2024-01-09,2024-01-12"
+        );
+        let text = days.display_dates().to_string();
+        assert_eq!(text, "2023-12-30..2024-01-09,2024-01-12");
+        assert_eq!(Ranger::parse_dates(&text), Ok(days.clone()));
+        assert_eq!(
+            Ranger::parse_dates_with("2023-12-30-2024-01-09, 2024-01-12", "-"),
+            Ok(days)
+        );
+        assert_eq!(
+            Ranger::parse_dates("2024-01-09..2023-12-30"),
+            Err(ParseRangeError::Reversed(0))
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use crate::{
+    discrete::{step_down, step_up},
+    inclusive_bounds, touches, Discrete, Ranger, Span, Unit,
+};
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Display},
+    ops::{Deref, RangeBounds, RangeInclusive},
+};
+
+/// A change to the runs of a [`RangerWatched`], as handed to its observer.
+/// Each event describes what happened to the runs themselves, so a run that
+/// is extended is reported as having grown rather than as removed and
+/// created again.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RunEvent<T> {
+    /// A run was added that touches no other
+    Created(Span<T>),
+    /// A run was extended at either end or both, without meeting another
+    Grew { from: Span<T>, to: Span<T> },
+    /// Two or more runs were joined into one, along with any values inserted
+    /// between them. `absorbed` lists the runs as they were, in ascending
+    /// order.
+    Merged {
+        into: Span<T>,
+        absorbed: Vec<Span<T>>,
+    },
+    /// A run lost values at one end
+    Shrank { from: Span<T>, to: Span<T> },
+    /// A run lost values from its middle, leaving a run on each side
+    Split {
+        from: Span<T>,
+        into: (Span<T>, Span<T>),
+    },
+    /// A run was removed entirely
+    Removed(Span<T>),
+}
+
+/// A [`Ranger`] that reports every change to its runs to an observer as it
+/// happens, for keeping caches of per-run data in step.
+///
+/// Mutation goes through the wrapper's own methods, each of which reports
+/// its events in ascending order of the runs involved before returning.
+/// Reads go through `Deref`.
+///
+/// ```
+/// use ranger::{Ranger, RangerWatched, RunEvent, Span};
+///
+/// let mut events = Vec::new();
+/// let mut watched = RangerWatched::new(Ranger::new(), |e| events.push(e));
+/// watched.insert_range(0..=4);
+/// watched.insert_range(8..=9);
+/// watched.insert_range(5..=7);
+/// drop(watched);
+/// assert_eq!(events[2], RunEvent::Merged {
+///     into: Span::new(0, 9).unwrap(),
+///     absorbed: vec![Span::new(0, 4).unwrap(), Span::new(8, 9).unwrap()],
+/// });
+/// ```
+pub struct RangerWatched<T, F> {
+    ranger: Ranger<T>,
+    observer: F,
+}
+
+impl<T, F: FnMut(RunEvent<T>)> RangerWatched<T, F> {
+    /// Wraps `ranger`, whose existing runs are reported to no one, so that
+    /// its changes are reported to `observer`
+    pub fn new(ranger: Ranger<T>, observer: F) -> Self {
+        RangerWatched { ranger, observer }
+    }
+    /// Unwraps the set, dropping the observer
+    pub fn into_inner(self) -> Ranger<T> {
+        self.ranger
+    }
+}
+
+impl<T: Discrete, F: FnMut(RunEvent<T>)> RangerWatched<T, F> {
+    /// Inserts `value`, returning whether it was not already present
+    pub fn insert(&mut self, value: T) -> bool {
+        self.insert_range(value.clone()..=value) > 0
+    }
+    /// Inserts every value in `range` as [`Ranger::insert_range`] does,
+    /// returning the number of values that were not already present
+    pub fn insert_range(&mut self, range: RangeInclusive<T>) -> u128 {
+        // nothing changes, even where runs within the gap tolerance are
+        // left unjoined
+        if range.is_empty() || self.ranger.contains_range(&range) {
+            return 0;
+        }
+        let absorbed = self.runs_joined_by(range.start(), range.end());
+        let added = self.ranger.insert_range(range.clone());
+        let into = self
+            .ranger
+            .unit_containing(range.start())
+            .map(Unit::span)
+            .expect("the range was inserted");
+        let mut absorbed = absorbed.into_iter();
+        match (absorbed.next(), absorbed.len()) {
+            (None, _) => (self.observer)(RunEvent::Created(into)),
+            (Some(from), 0) => (self.observer)(RunEvent::Grew { from, to: into }),
+            (Some(first), _) => {
+                let absorbed = core::iter::once(first).chain(absorbed).collect();
+                (self.observer)(RunEvent::Merged { into, absorbed })
+            }
+        }
+        added
+    }
+    /// Removes `value`, returning whether it was present
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.remove_range(value..=value) > 0
+    }
+    /// Removes every value within `bounds` as [`Ranger::remove_range`] does,
+    /// returning the number of values that were present
+    pub fn remove_range<R: RangeBounds<T>>(&mut self, bounds: R) -> u128 {
+        let Some((low, high)) = inclusive_bounds(&bounds) else {
+            return 0;
+        };
+        let hit: Vec<Span<T>> = match &low {
+            Some(low) => self.ranger.0.range::<T, _>(low..),
+            None => self.ranger.0.range::<Unit<T>, _>(..),
+        }
+        .take_while(|u| high.as_ref().is_none_or(|h| &u.l <= h))
+        .map(Unit::span)
+        .collect();
+        let removed = self.ranger.remove_range(bounds);
+        for from in hit {
+            let below = low
+                .as_ref()
+                .filter(|low| from.low() < low)
+                .map(|low| Span::new_unchecked(from.low().clone(), step_down(low)));
+            let above = high
+                .as_ref()
+                .filter(|high| from.high() > high)
+                .map(|high| Span::new_unchecked(step_up(high), from.high().clone()));
+            let event = match (below, above) {
+                (Some(below), Some(above)) => RunEvent::Split {
+                    from,
+                    into: (below, above),
+                },
+                (Some(to), None) | (None, Some(to)) => RunEvent::Shrank { from, to },
+                (None, None) => RunEvent::Removed(from),
+            };
+            (self.observer)(event);
+        }
+        removed
+    }
+    /// The runs inserting `low..=high` would join, as
+    /// [`Ranger::insert_range`] finds them
+    fn runs_joined_by(&self, low: &T, high: &T) -> Vec<Span<T>> {
+        let (set, config) = (&self.ranger.0, &self.ranger.1);
+        let mut runs = Vec::new();
+        if let Some(u) = set.range::<T, _>(..low).next_back() {
+            if touches(u.high(), low, config) {
+                runs.push(u.span());
+            }
+        }
+        // a run joined above extends the reach of the insertion
+        let mut high = high.clone();
+        for u in set.range::<T, _>(low..) {
+            if !u.overlaps(low, &high) && !touches(&high, &u.l, config) {
+                break;
+            }
+            high = core::cmp::max(high, u.high().clone());
+            runs.push(u.span());
+        }
+        runs
+    }
+}
+
+impl<T, F> Deref for RangerWatched<T, F> {
+    type Target = Ranger<T>;
+
+    fn deref(&self) -> &Ranger<T> {
+        &self.ranger
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for RangerWatched<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RangerWatched").field(&self.ranger).finish()
+    }
+}
+
+impl<T: Eq + Display, F> Display for RangerWatched<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.ranger.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+    use rand::{thread_rng, Rng};
+
+    fn span(l: i32, h: i32) -> Span<i32> {
+        Span::new(l, h).unwrap()
+    }
+
+    #[test]
+    fn scripted_events() {
+        let mut events = Vec::new();
+        let mut watched = RangerWatched::new(Ranger::new(), |e| events.push(e));
+        assert_eq!(watched.insert_range(10..=19), 10);
+        assert!(watched.insert(30));
+        // covered already, so nothing happens
+        assert_eq!(watched.insert_range(12..=15), 0);
+        assert!(watched.insert(20));
+        assert_eq!(watched.insert_range(40..=45), 6);
+        // bridges two runs
+        assert_eq!(watched.insert_range(21..=29), 9);
+        assert_eq!(watched.to_string(), "10-30,40-45");
+        assert_eq!(watched.remove_range(15..=16), 2);
+        assert_eq!(watched.remove_range(25..=42), 9);
+        assert!(watched.remove(&10));
+        assert!(!watched.remove(&10));
+        assert_eq!(watched.remove_range(..), 15);
+        assert!(watched.is_empty());
+        drop(watched);
+        assert_eq!(
+            events,
+            [
+                RunEvent::Created(span(10, 19)),
+                RunEvent::Created(span(30, 30)),
+                RunEvent::Grew {
+                    from: span(10, 19),
+                    to: span(10, 20)
+                },
+                RunEvent::Created(span(40, 45)),
+                RunEvent::Merged {
+                    into: span(10, 30),
+                    absorbed: vec![span(10, 20), span(30, 30)]
+                },
+                RunEvent::Split {
+                    from: span(10, 30),
+                    into: (span(10, 14), span(17, 30))
+                },
+                RunEvent::Shrank {
+                    from: span(17, 30),
+                    to: span(17, 24)
+                },
+                RunEvent::Shrank {
+                    from: span(40, 45),
+                    to: span(43, 45)
+                },
+                RunEvent::Shrank {
+                    from: span(10, 14),
+                    to: span(11, 14)
+                },
+                RunEvent::Removed(span(11, 14)),
+                RunEvent::Removed(span(17, 24)),
+                RunEvent::Removed(span(43, 45)),
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_across_the_gap_tolerance() {
+        let mut events = Vec::new();
+        let mut watched = RangerWatched::new(Ranger::with_gap_tolerance(2), |e| events.push(e));
+        watched.insert_range(0..=3);
+        watched.insert_range(10..=12);
+        watched.insert_range(6..=7);
+        watched.remove(&5);
+        // still within the tolerance of each other, but already covered
+        watched.insert(6);
+        drop(watched);
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events[2],
+            RunEvent::Merged {
+                into: span(0, 12),
+                absorbed: vec![span(0, 3), span(10, 12)]
+            }
+        );
+    }
+
+    /// Replays the events onto a plain list of runs
+    fn replay(runs: &mut Vec<Span<i32>>, event: RunEvent<i32>) {
+        let (gone, new): (Vec<_>, Vec<_>) = match event {
+            RunEvent::Created(s) => (vec![], vec![s]),
+            RunEvent::Grew { from, to } | RunEvent::Shrank { from, to } => (vec![from], vec![to]),
+            RunEvent::Merged { into, absorbed } => (absorbed, vec![into]),
+            RunEvent::Split { from, into } => (vec![from], vec![into.0, into.1]),
+            RunEvent::Removed(s) => (vec![s], vec![]),
+        };
+        for s in gone {
+            let i = runs
+                .iter()
+                .position(|r| r == &s)
+                .expect("event names a live run");
+            runs.remove(i);
+        }
+        runs.extend(new);
+        runs.sort_by(|a, b| a.low().cmp(b.low()));
+    }
+
+    #[test]
+    fn events_replay_to_the_same_runs() {
+        let mut rng = thread_rng();
+        let mut runs = Vec::new();
+        let mut watched = RangerWatched::new(Ranger::new(), |e| replay(&mut runs, e));
+        for _ in 0..2000 {
+            let l = rng.gen_range(-200..200);
+            let h = l + rng.gen_range(0..20);
+            match rng.gen_range(0..4) {
+                0 => {
+                    watched.insert(l);
+                }
+                1 => {
+                    watched.insert_range(l..=h);
+                }
+                2 => {
+                    watched.remove(&l);
+                }
+                _ => {
+                    watched.remove_range(l..h);
+                }
+            }
+        }
+        let ranger = watched.into_inner();
+        assert!(runs.iter().cloned().eq(ranger.ranges()));
+    }
+}
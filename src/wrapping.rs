@@ -0,0 +1,175 @@
+use crate::{write_runs, AmbiguousSpan, Config, Discrete, FormatOptions, Ranger};
+use alloc::collections::BTreeSet;
+use core::{
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
+use num_traits::Bounded;
+
+/// A set over a circular space such as serial numbers, where the value after
+/// `T::max_value()` is `T::min_value()` and a run may straddle the two.
+///
+/// Spans are given as a start and an end counted forward from it, and may
+/// cover at most half of the space, as serial number arithmetic (RFC 1982)
+/// can't order the ends of anything wider. Internally a straddling run is
+/// kept as the two runs meeting the ends of the type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WrappingRanger<T>(Ranger<T>);
+
+impl<T: Discrete + Bounded> Default for WrappingRanger<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Discrete + Bounded> WrappingRanger<T> {
+    pub fn new() -> Self {
+        WrappingRanger(Ranger(BTreeSet::new(), Config::default()))
+    }
+    /// Inserts `value`, returning whether it was not already present
+    pub fn insert(&mut self, value: T) -> bool {
+        self.0.insert_range(value.clone()..=value) > 0
+    }
+    pub fn contains(&self, value: &T) -> bool {
+        self.0.unit_containing(value).is_some()
+    }
+    /// Removes `value`, returning whether it was present
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.0.remove(value)
+    }
+    /// Splits the span from `start` forward to `end` at the wrap point
+    fn pieces(
+        start: T,
+        end: T,
+    ) -> Result<(RangeInclusive<T>, Option<RangeInclusive<T>>), AmbiguousSpan> {
+        let (min, max) = (T::min_value(), T::max_value());
+        let half = T::distance(&min, &max).unwrap_or(u128::MAX) / 2;
+        if start <= end {
+            if T::distance(&start, &end).is_none_or(|d| d > half) {
+                return Err(AmbiguousSpan);
+            }
+            Ok((start..=end, None))
+        } else {
+            let d = T::distance(&start, &max)
+                .zip(T::distance(&min, &end))
+                .and_then(|(a, b)| a.checked_add(b)?.checked_add(1));
+            if d.is_none_or(|d| d > half) {
+                return Err(AmbiguousSpan);
+            }
+            Ok((start..=max, Some(min..=end)))
+        }
+    }
+    /// Inserts every value from `start` forward to `end`, wrapping past the
+    /// top of the type if `end` is below `start`. Returns the number of values
+    /// that were not already present.
+    pub fn insert_span(&mut self, start: T, end: T) -> Result<u128, AmbiguousSpan> {
+        let (first, second) = Self::pieces(start, end)?;
+        let added = self.0.insert_range(first);
+        Ok(added + second.map_or(0, |r| self.0.insert_range(r)))
+    }
+    /// Removes every value from `start` forward to `end`, wrapping past the
+    /// top of the type if `end` is below `start`. Returns the number of values
+    /// that were present.
+    pub fn remove_span(&mut self, start: T, end: T) -> Result<u128, AmbiguousSpan> {
+        let (first, second) = Self::pieces(start, end)?;
+        let removed = self.0.remove_range(first);
+        Ok(removed + second.map_or(0, |r| self.0.remove_range(r)))
+    }
+    /// Returns the number of values stored, saturating at `u128::MAX`
+    pub fn len(&self) -> u128 {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Whether the first and last runs meet across the wrap point
+    fn straddles(&self) -> bool {
+        let set = &self.0 .0;
+        set.len() > 1
+            && set.first().is_some_and(|u| u.l == T::min_value())
+            && set.last().is_some_and(|u| u.high() == &T::max_value())
+    }
+    /// Returns the number of runs, counting one straddling the wrap point once
+    pub fn range_count(&self) -> usize {
+        self.0.range_count() - self.straddles() as usize
+    }
+    /// Iterates over the runs as `(start, end)` pairs in ascending order of
+    /// their start. A run straddling the wrap point comes last, with its end
+    /// below its start.
+    pub fn ranges(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        let set = &self.0 .0;
+        let straddles = self.straddles();
+        let joined = straddles.then(|| {
+            let (first, last) = (set.first().unwrap(), set.last().unwrap());
+            (last.l.clone(), first.high().clone())
+        });
+        set.iter()
+            .skip(straddles as usize)
+            .take(set.len() - 2 * straddles as usize)
+            .map(|u| (u.l.clone(), u.high().clone()))
+            .chain(joined)
+    }
+}
+
+impl<T: Discrete + Bounded + Display> Display for WrappingRanger<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_runs(f, self.ranges(), &FormatOptions::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+
+    #[test]
+    fn wrap_point() {
+        let mut serials = WrappingRanger::new();
+        for v in [0xffff_fffe, 0, 0xffff_ffff, 1, 100u32] {
+            assert!(serials.insert(v));
+        }
+        assert!(!serials.insert(0));
+        assert_eq!(serials.to_string(), "100,4294967294-1");
+        assert_eq!(serials.range_count(), 2);
+        assert_eq!(serials.len(), 5);
+        assert!(serials.contains(&0xffff_ffff) && serials.contains(&1));
+        assert!(!serials.contains(&2));
+        let ranges: Vec<_> = serials.ranges().collect();
+        assert_eq!(ranges, [(100, 100), (0xffff_fffe, 1)]);
+
+        assert!(serials.remove(&0));
+        assert_eq!(serials.to_string(), "1,100,4294967294-4294967295");
+        assert_eq!(serials.insert_span(0xffff_fff0, 3), Ok(17));
+        assert_eq!(serials.to_string(), "100,4294967280-3");
+        assert_eq!(serials.remove_span(0xffff_ffff, 0), Ok(2));
+        assert_eq!(serials.to_string(), "1-3,100,4294967280-4294967294");
+
+        // the whole space is one run that doesn't straddle
+        let mut full = WrappingRanger::<u8>::new();
+        full.insert_span(0, 127).unwrap();
+        full.insert_span(128, 255).unwrap();
+        assert_eq!(full.range_count(), 1);
+        assert_eq!(full.to_string(), "0-255");
+    }
+
+    #[test]
+    fn ambiguity_limit() {
+        let mut ranger = WrappingRanger::<u8>::new();
+        assert_eq!(ranger.insert_span(0, 128), Err(AmbiguousSpan));
+        assert_eq!(ranger.insert_span(200, 72), Err(AmbiguousSpan));
+        assert_eq!(ranger.remove_span(129, 1), Err(AmbiguousSpan));
+        assert!(ranger.is_empty());
+        assert_eq!(ranger.insert_span(200, 71), Ok(128));
+        assert_eq!(ranger.to_string(), "200-71");
+        assert_eq!(ranger.insert_span(10, 137), Ok(66));
+        assert_eq!(ranger.to_string(), "200-137");
+        assert_eq!(ranger.remove_span(5, 5), Ok(1));
+        assert_eq!(ranger.to_string(), "6-137,200-4");
+        let mut wide = WrappingRanger::<u128>::new();
+        assert_eq!(wide.insert_span(0, u128::MAX / 2), Ok(1 << 127));
+        assert_eq!(
+            wide.insert_span(u128::MAX, u128::MAX / 2),
+            Err(AmbiguousSpan)
+        );
+    }
+}